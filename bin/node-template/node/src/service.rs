@@ -197,6 +197,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			keystore: keystore_container.sync_keystore(),
 			task_manager: &mut task_manager,
 			transaction_pool: transaction_pool.clone(),
+			transaction_pool_maintainer: None,
 			rpc_extensions_builder,
 			on_demand: None,
 			remote_blockchain: None,
@@ -373,6 +374,7 @@ pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		remote_blockchain: Some(backend.remote_blockchain()),
 		transaction_pool,
+		transaction_pool_maintainer: None,
 		task_manager: &mut task_manager,
 		on_demand: Some(on_demand),
 		rpc_extensions_builder: Box::new(|_, _| ()),
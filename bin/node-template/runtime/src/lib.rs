@@ -33,7 +33,7 @@ pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
 	traits::{KeyOwnerProofSystem, Randomness},
 	weights::{
-		Weight, IdentityFee,
+		Weight, IdentityFee, DispatchClass,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 	},
 };
@@ -101,9 +101,9 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
-	//   the compatible custom types.
-	spec_version: 100,
+	// This value is kept at 100 or above to notify Polkadot-JS App
+	//   (https://polkadot.js.org/apps) to use the compatible custom types.
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -378,6 +378,11 @@ impl_runtime_apis! {
 		) -> TransactionValidity {
 			Executive::validate_transaction(source, tx)
 		}
+
+		fn max_extrinsic_length() -> u32 {
+			let max = BlockLength::get().max;
+			*max.get(DispatchClass::Normal).max(max.get(DispatchClass::Operational))
+		}
 	}
 
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
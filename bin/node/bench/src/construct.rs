@@ -251,6 +251,16 @@ impl sp_transaction_pool::TransactionPool for Transactions {
 		unimplemented!()
 	}
 
+	fn submit_one_encoded(
+		&self,
+		_at: &BlockId<Self::Block>,
+		_source: TransactionSource,
+		_xt: TransactionFor<Self>,
+		_encoded: &[u8],
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		unimplemented!()
+	}
+
 	fn submit_and_watch(
 		&self,
 		_at: &BlockId<Self::Block>,
@@ -291,6 +301,10 @@ impl sp_transaction_pool::TransactionPool for Transactions {
 		unimplemented!()
 	}
 
+	fn hash_of_encoded(&self, _encoded: &[u8]) -> TxHash<Self> {
+		unimplemented!()
+	}
+
 	fn ready_transaction(&self, _hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
 		unimplemented!()
 	}
@@ -265,6 +265,7 @@ pub fn new_full_base(
 			network: network.clone(),
 			rpc_extensions_builder: Box::new(rpc_extensions_builder),
 			transaction_pool: transaction_pool.clone(),
+			transaction_pool_maintainer: None,
 			task_manager: &mut task_manager,
 			on_demand: None,
 			remote_blockchain: None,
@@ -507,6 +508,7 @@ pub fn new_light_base(
 			rpc_extensions_builder: Box::new(sc_service::NoopRpcExtensionBuilder(rpc_extensions)),
 			client: client.clone(),
 			transaction_pool: transaction_pool.clone(),
+			transaction_pool_maintainer: None,
 			keystore: keystore_container.sync_keystore(),
 			config, backend, network_status_sinks, system_rpc_tx,
 			network: network.clone(),
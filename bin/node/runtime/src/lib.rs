@@ -113,8 +113,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 265,
-	impl_version: 1,
+	spec_version: 266,
+	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 2,
 };
@@ -1231,6 +1231,11 @@ impl_runtime_apis! {
 		) -> TransactionValidity {
 			Executive::validate_transaction(source, tx)
 		}
+
+		fn max_extrinsic_length() -> u32 {
+			let max = RuntimeBlockLength::get().max;
+			*max.get(DispatchClass::Normal).max(max.get(DispatchClass::Operational))
+		}
 	}
 
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
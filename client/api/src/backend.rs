@@ -463,6 +463,16 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 	/// Returns state backend with post-state of given block.
 	fn state_at(&self, block: BlockId<Block>) -> sp_blockchain::Result<Self::State>;
 
+	/// Pin the block with the given hash, preventing its state and body from being pruned until
+	/// it is unpinned.
+	///
+	/// Pins are reference counted: the block has to be unpinned as many times as it was pinned.
+	/// Fails if the state of the block is not available anymore.
+	fn pin_block(&self, hash: &Block::Hash) -> sp_blockchain::Result<()>;
+
+	/// Release a pin on the block with the given hash, taken by [`Backend::pin_block`].
+	fn unpin_block(&self, hash: &Block::Hash);
+
 	/// Attempts to revert the chain by `n` blocks. If `revert_finalized` is set it will attempt to
 	/// revert past any finalized block, this is unsafe and can potentially leave the node in an
 	/// inconsistent state.
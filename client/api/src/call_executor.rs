@@ -26,7 +26,7 @@ use sp_runtime::{
 use sp_state_machine::{
 	OverlayedChanges, ExecutionManager, ExecutionStrategy, StorageProof,
 };
-use sc_executor::{RuntimeVersion, NativeVersion};
+use sc_executor::{RuntimeVersion, NativeVersion, sp_wasm_interface::Function};
 use sp_externalities::Extensions;
 use sp_core::NativeOrEncoded;
 
@@ -131,4 +131,7 @@ pub trait CallExecutor<B: BlockT> {
 
 	/// Get runtime version if supported.
 	fn native_runtime_version(&self) -> Option<&NativeVersion>;
+
+	/// Get the host functions provided to the runtime.
+	fn host_functions(&self) -> &[&'static dyn Function];
 }
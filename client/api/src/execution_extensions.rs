@@ -26,7 +26,10 @@ use std::sync::{Weak, Arc};
 use codec::Decode;
 use sp_core::{
 	ExecutionContext,
-	offchain::{self, OffchainWorkerExt, TransactionPoolExt, OffchainDbExt},
+	traits::{ChainPropertiesExt, VerificationExecutorExt},
+	offchain::{
+		self, OffchainWorkerExt, TransactionPoolExt, TransactionPoolReaderExt, OffchainDbExt,
+	},
 };
 use sp_keystore::{KeystoreExt, SyncCryptoStorePtr};
 use sp_runtime::{
@@ -36,6 +39,7 @@ use sp_runtime::{
 use sp_state_machine::{ExecutionStrategy, ExecutionManager, DefaultHandler};
 use sp_externalities::Extensions;
 use parking_lot::RwLock;
+use crate::verification_pool::VerificationPool;
 
 /// Execution strategies settings.
 #[derive(Debug, Clone)]
@@ -104,7 +108,10 @@ pub struct ExecutionExtensions<Block: traits::Block> {
 	// That's also the reason why it's being registered lazily instead of
 	// during initialization.
 	transaction_pool: RwLock<Option<Weak<dyn sp_transaction_pool::OffchainSubmitTransaction<Block>>>>,
+	ready_transactions: RwLock<Option<Weak<dyn sp_transaction_pool::OffchainReadyTransactions>>>,
 	extensions_factory: RwLock<Box<dyn ExtensionsFactory>>,
+	verification_pool: Option<VerificationPool>,
+	chain_properties: Option<Vec<u8>>,
 }
 
 impl<Block: traits::Block> Default for ExecutionExtensions<Block> {
@@ -114,7 +121,10 @@ impl<Block: traits::Block> Default for ExecutionExtensions<Block> {
 			keystore: None,
 			offchain_db: None,
 			transaction_pool: RwLock::new(None),
+			ready_transactions: RwLock::new(None),
 			extensions_factory: RwLock::new(Box::new(())),
+			verification_pool: None,
+			chain_properties: None,
 		}
 	}
 }
@@ -134,9 +144,44 @@ impl<Block: traits::Block> ExecutionExtensions<Block> {
 			offchain_db,
 			extensions_factory: RwLock::new(extensions_factory),
 			transaction_pool,
+			ready_transactions: RwLock::new(None),
+			verification_pool: None,
+			chain_properties: None,
 		}
 	}
 
+	/// Use the given pool for signature verification work of the node.
+	///
+	/// Batch signature verification of every runtime call is spawned on this pool,
+	/// through [`VerificationExecutorExt`]. Other runtime tasks keep using the
+	/// spawn handle of the client.
+	pub fn with_verification_pool(mut self, pool: VerificationPool) -> Self {
+		self.verification_pool = Some(pool);
+		self
+	}
+
+	/// Expose the given properties of the chain to offchain calls.
+	///
+	/// The properties are node configuration, so they are only provided to
+	/// [`ExecutionContext::OffchainCall`]s (offchain workers, RPC and other calls made outside
+	/// of block import and construction) to keep consensus code independent of them.
+	///
+	/// `properties` is a SCALE-encoded `Vec<(String, String)>`, see [`ChainPropertiesExt`].
+	pub fn with_chain_properties(mut self, properties: Vec<u8>) -> Self {
+		self.chain_properties = Some(properties);
+		self
+	}
+
+	/// Get the node-wide signature verification pool, if any.
+	pub fn verification_pool(&self) -> Option<&VerificationPool> {
+		self.verification_pool.as_ref()
+	}
+
+	/// Create externalities to access the offchain database, if any.
+	pub fn offchain_db(&self) -> Option<Box<dyn offchain::DbExternalities>> {
+		self.offchain_db.as_ref().map(|factory| factory.create())
+	}
+
 	/// Get a reference to the execution strategies.
 	pub fn strategies(&self) -> &ExecutionStrategies {
 		&self.strategies
@@ -149,9 +194,13 @@ impl<Block: traits::Block> ExecutionExtensions<Block> {
 
 	/// Register transaction pool extension.
 	pub fn register_transaction_pool<T>(&self, pool: &Arc<T>)
-		where T: sp_transaction_pool::OffchainSubmitTransaction<Block> + 'static
+		where
+			T: sp_transaction_pool::OffchainSubmitTransaction<Block>
+				+ sp_transaction_pool::OffchainReadyTransactions
+				+ 'static
 	{
 		*self.transaction_pool.write() = Some(Arc::downgrade(&pool) as _);
+		*self.ready_transactions.write() = Some(Arc::downgrade(&pool) as _);
 	}
 
 	/// Based on the execution context and capabilities it produces
@@ -161,6 +210,16 @@ impl<Block: traits::Block> ExecutionExtensions<Block> {
 
 		let mut extensions = self.extensions_factory.read().extensions_for(capabilities);
 
+		if let (ExecutionContext::OffchainCall(_), Some(properties)) =
+			(&context, self.chain_properties.as_ref())
+		{
+			extensions.register(ChainPropertiesExt(properties.clone()));
+		}
+
+		if let Some(pool) = self.verification_pool.as_ref() {
+			extensions.register(VerificationExecutorExt::new(pool.clone()));
+		}
+
 		if capabilities.has(offchain::Capability::Keystore) {
 			if let Some(ref keystore) = self.keystore {
 				extensions.register(KeystoreExt(keystore.clone()));
@@ -178,6 +237,9 @@ impl<Block: traits::Block> ExecutionExtensions<Block> {
 					),
 				);
 			}
+			if let Some(pool) = self.ready_transactions.read().as_ref().and_then(|x| x.upgrade()) {
+				extensions.register(TransactionPoolReaderExt::new(ReadyTransactionsAdapter(pool)));
+			}
 		}
 
 		if capabilities.has(offchain::Capability::OffchainDbRead) ||
@@ -250,3 +312,12 @@ impl<Block: traits::Block> offchain::TransactionPool for TransactionPoolAdapter<
 		self.pool.submit_at(&self.at, xt)
 	}
 }
+
+/// A wrapper type to expose the ready transactions of the actual transaction pool.
+struct ReadyTransactionsAdapter(Arc<dyn sp_transaction_pool::OffchainReadyTransactions>);
+
+impl offchain::TransactionPoolReader for ReadyTransactionsAdapter {
+	fn ready_transactions(&self, max_count: u32, max_bytes: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.0.ready_transactions(max_count, max_bytes)
+	}
+}
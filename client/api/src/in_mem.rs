@@ -766,6 +766,16 @@ impl<Block: BlockT> backend::Backend<Block> for Backend<Block> where Block::Hash
 		}
 	}
 
+	fn pin_block(&self, hash: &Block::Hash) -> sp_blockchain::Result<()> {
+		// States are never pruned.
+		match self.states.read().contains_key(hash) {
+			true => Ok(()),
+			false => Err(sp_blockchain::Error::UnknownBlock(format!("{:?}", hash))),
+		}
+	}
+
+	fn unpin_block(&self, _hash: &Block::Hash) {}
+
 	fn revert(
 		&self,
 		_n: NumberFor<Block>,
@@ -24,6 +24,7 @@ pub mod call_executor;
 pub mod client;
 pub mod cht;
 pub mod execution_extensions;
+pub mod verification_pool;
 pub mod in_mem;
 pub mod light;
 pub mod leaves;
@@ -38,6 +39,7 @@ pub use client::*;
 pub use light::*;
 pub use notifications::*;
 pub use proof_provider::*;
+pub use verification_pool::VerificationPool;
 
 pub use sp_state_machine::{StorageProof, ExecutionStrategy};
 
@@ -0,0 +1,153 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Node-wide CPU pool for signature verification.
+//!
+//! Block import (through the batch verification extension) and transaction
+//! pool validation both spend most of their time checking signatures. Rather
+//! than each of them spinning up its own threads, or running the checks on the
+//! import thread, they share a single pool sized after the available cores.
+//!
+//! The pool keeps separate threads for block import, so that a burst of gossiped
+//! transactions to validate never delays the import of blocks.
+
+use futures::{future::BoxFuture, executor::{ThreadPool, ThreadPoolBuilder}};
+use sp_core::traits::SpawnNamed;
+
+/// Lower bound on the number of verification threads.
+const MIN_THREADS: usize = 2;
+
+/// Upper bound on the number of verification threads.
+///
+/// Past this point the verification work is no longer the bottleneck and
+/// additional threads only compete with networking and import.
+const MAX_THREADS: usize = 16;
+
+/// Thread pool shared by all the signature verification work of a node.
+///
+/// Cloning the pool is cheap and all clones spawn onto the same threads.
+#[derive(Clone)]
+pub struct VerificationPool {
+	/// Threads verifying the signatures of imported blocks.
+	import: ThreadPool,
+	/// Threads validating transactions, kept apart to not delay block import.
+	transactions: ThreadPool,
+}
+
+impl VerificationPool {
+	/// Create a pool with exactly `import_size` threads for block import and
+	/// `transactions_size` threads for transaction validation, named after `name_prefix`.
+	pub fn new(
+		import_size: usize,
+		transactions_size: usize,
+		name_prefix: &str,
+	) -> std::io::Result<Self> {
+		let import = ThreadPoolBuilder::new()
+			.pool_size(import_size.max(1))
+			.name_prefix(format!("{}-import", name_prefix))
+			.create()?;
+		let transactions = ThreadPoolBuilder::new()
+			.pool_size(transactions_size.max(1))
+			.name_prefix(format!("{}-txpool", name_prefix))
+			.create()?;
+
+		Ok(VerificationPool { import, transactions })
+	}
+
+	/// Create a pool sized after the number of available cores.
+	///
+	/// One core is left to the import and networking threads, within the
+	/// `[MIN_THREADS, MAX_THREADS]` bounds. Transaction validation gets half as many threads.
+	pub fn adaptive() -> std::io::Result<Self> {
+		let size = adaptive_size(num_cpus::get());
+		Self::new(size, size / 2, "signature-verifier")
+	}
+
+	/// Run the given block import verification to completion on the pool.
+	pub fn spawn_ok(&self, future: impl std::future::Future<Output = ()> + Send + 'static) {
+		self.import.spawn_ok(future)
+	}
+
+	/// Run the given transaction validation to completion on the pool.
+	///
+	/// Transaction validation runs on threads of its own, so it never waits for or delays
+	/// the verification of imported blocks.
+	pub fn spawn_transaction_validation(
+		&self,
+		future: impl std::future::Future<Output = ()> + Send + 'static,
+	) {
+		self.transactions.spawn_ok(future)
+	}
+}
+
+/// Spawns onto the block import threads, as used by the batch verification extension.
+impl SpawnNamed for VerificationPool {
+	fn spawn_blocking(&self, _: &'static str, future: BoxFuture<'static, ()>) {
+		self.import.spawn_ok(future)
+	}
+
+	fn spawn(&self, _: &'static str, future: BoxFuture<'static, ()>) {
+		self.import.spawn_ok(future)
+	}
+}
+
+impl std::fmt::Debug for VerificationPool {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("VerificationPool").finish()
+	}
+}
+
+fn adaptive_size(cpus: usize) -> usize {
+	cpus.saturating_sub(1).max(MIN_THREADS).min(MAX_THREADS)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::FutureExt;
+
+	#[test]
+	fn adaptive_size_is_bounded() {
+		assert_eq!(adaptive_size(1), MIN_THREADS);
+		assert_eq!(adaptive_size(4), 3);
+		assert_eq!(adaptive_size(64), MAX_THREADS);
+	}
+
+	#[test]
+	fn spawned_tasks_run() {
+		let pool = VerificationPool::new(2, 1, "test-verifier").unwrap();
+		let (tx, rx) = futures::channel::oneshot::channel();
+		pool.spawn("test", async move { tx.send(42).unwrap() }.boxed());
+		assert_eq!(futures::executor::block_on(rx), Ok(42));
+	}
+
+	#[test]
+	fn busy_transaction_validation_does_not_delay_import() {
+		let pool = VerificationPool::new(1, 1, "test-verifier").unwrap();
+
+		// occupy the transaction validation thread until the import task has run
+		let (release, released) = std::sync::mpsc::channel::<()>();
+		pool.spawn_transaction_validation(async move { let _ = released.recv(); });
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		pool.spawn_ok(async move { tx.send(42).unwrap() });
+		assert_eq!(futures::executor::block_on(rx), Ok(42));
+
+		release.send(()).unwrap();
+	}
+}
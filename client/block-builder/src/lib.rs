@@ -255,11 +255,16 @@ where
 	/// Create the inherents for the block.
 	///
 	/// Returns the inherents created by the runtime or an error if something failed.
+	///
+	/// The inherent data is also exposed to the runtime through the `inherent_data` host functions,
+	/// for this call and the following calls made to build the block. They are not given the data
+	/// when the block is imported, so it must not change their outcome.
 	pub fn create_inherents(
 		&mut self,
 		inherent_data: sp_inherents::InherentData,
 	) -> Result<Vec<Block::Extrinsic>, Error> {
 		let block_id = self.block_id;
+		self.api.register_extension(sp_inherents::InherentDataExt(inherent_data.clone()));
 		self.api.execute_in_transaction(move |api| {
 			// `create_inherents` should not change any state, to ensure this we always rollback
 			// the transaction.
@@ -278,7 +283,9 @@ mod tests {
 	use sp_blockchain::HeaderBackend;
 	use sp_core::Blake2Hasher;
 	use sp_state_machine::Backend;
-	use substrate_test_runtime_client::{DefaultTestClientBuilderExt, TestClientBuilderExt};
+	use substrate_test_runtime_client::{
+		DefaultTestClientBuilderExt, TestClientBuilderExt, runtime::TestAPI,
+	};
 
 	#[test]
 	fn block_building_storage_proof_does_not_include_runtime_by_default() {
@@ -308,4 +315,33 @@ mod tests {
 				.contains("Database missing expected key"),
 		);
 	}
+
+	#[test]
+	fn inherent_data_is_provided_to_the_block_construction_calls() {
+		let builder = substrate_test_runtime_client::TestClientBuilder::new();
+		let backend = builder.backend();
+		let client = builder.build();
+
+		let mut block_builder = BlockBuilder::new(
+			&client,
+			client.info().best_hash,
+			client.info().best_number,
+			RecordProof::No,
+			Default::default(),
+			&*backend,
+		).unwrap();
+
+		let identifier = *b"testinh0";
+		let get = |builder: &BlockBuilder<_, _, _>| builder.api.inherent_data_with_context(
+			&builder.block_id,
+			ExecutionContext::BlockConstruction,
+			identifier.to_vec(),
+		).unwrap();
+		assert_eq!(get(&block_builder), None);
+
+		let mut inherent_data = sp_inherents::InherentData::new();
+		inherent_data.put_data(identifier, &42u32).unwrap();
+		block_builder.create_inherents(inherent_data).unwrap();
+		assert_eq!(get(&block_builder), Some(42u32.encode()));
+	}
 }
@@ -21,7 +21,7 @@
 
 use std::{borrow::Cow, fs::File, path::PathBuf, sync::Arc, collections::HashMap};
 use serde::{Serialize, Deserialize};
-use sp_core::storage::{StorageKey, StorageData, ChildInfo, Storage, StorageChild};
+use sp_core::{Bytes, storage::{StorageKey, StorageData, ChildInfo, Storage, StorageChild}};
 use sp_runtime::BuildStorage;
 use serde_json as json;
 use crate::{RuntimeGenesis, ChainType, extension::GetExtension, Properties};
@@ -140,6 +140,25 @@ enum Genesis<G> {
 	Raw(RawGenesis),
 }
 
+/// A runtime code used instead of the on-chain code from a given block.
+///
+/// Substitutes are meant to work around a broken on-chain runtime, e.g. one that can't execute
+/// some blocks anymore, without requiring all the nodes to override their runtime by hand.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CodeSubstitute {
+	/// Hash of the first block on top of which the code is used.
+	///
+	/// Runtime calls are made on top of the state of a block: importing a block executes it on
+	/// top of the state of its parent. The code is used on top of this block and its
+	/// descendants, as long as the on-chain runtime has the same spec version as the code: the
+	/// substitution ends with the next runtime upgrade.
+	pub block_hash: Bytes,
+	/// The wasm code to use.
+	pub code: Bytes,
+}
+
 /// A configuration of a client. Does not include runtime storage initialization.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -160,6 +179,8 @@ struct ClientSpec<E> {
 	#[serde(skip_serializing)]
 	genesis: serde::de::IgnoredAny,
 	light_sync_state: Option<SerializableLightSyncState>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	code_substitutes: Vec<CodeSubstitute>,
 }
 
 /// A type denoting empty extensions.
@@ -225,6 +246,16 @@ impl<G, E> ChainSpec<G, E> {
 		&self.client_spec.extensions
 	}
 
+	/// The runtime codes used instead of the on-chain code from some blocks.
+	pub fn code_substitutes(&self) -> &[CodeSubstitute] {
+		&self.client_spec.code_substitutes
+	}
+
+	/// Add a runtime code to use instead of the on-chain code from a block.
+	pub fn add_code_substitute(&mut self, substitute: CodeSubstitute) {
+		self.client_spec.code_substitutes.push(substitute)
+	}
+
 	/// Create hardcoded spec.
 	pub fn from_genesis<F: Fn() -> G + 'static + Send + Sync>(
 		name: &str,
@@ -249,6 +280,7 @@ impl<G, E> ChainSpec<G, E> {
 			consensus_engine: (),
 			genesis: Default::default(),
 			light_sync_state: None,
+			code_substitutes: Vec::new(),
 		};
 
 		ChainSpec {
@@ -376,6 +408,10 @@ where
 		ChainSpec::extensions(self) as &dyn GetExtension
 	}
 
+	fn code_substitutes(&self) -> &[CodeSubstitute] {
+		ChainSpec::code_substitutes(self)
+	}
+
 	fn as_json(&self, raw: bool) -> Result<String, String> {
 		ChainSpec::as_json(self, raw)
 	}
@@ -500,4 +536,23 @@ mod tests {
 
 		assert_eq!(spec.extensions().my_property, "Test Extension");
 	}
+
+	#[test]
+	fn code_substitutes_are_serialized() {
+		let mut spec = TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap();
+		assert!(spec.code_substitutes().is_empty());
+		assert!(!spec.as_json(false).unwrap().contains("codeSubstitutes"));
+
+		let substitute = CodeSubstitute {
+			block_hash: vec![1; 32].into(),
+			code: vec![0, 97, 115, 109].into(),
+		};
+		spec.add_code_substitute(substitute.clone());
+
+		let json = spec.as_json(false).unwrap();
+		let spec = TestSpec::from_json_bytes(json.into_bytes()).unwrap();
+		assert_eq!(spec.code_substitutes(), &[substitute]);
+	}
 }
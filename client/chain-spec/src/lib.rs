@@ -112,6 +112,7 @@ mod extension;
 
 pub use chain_spec::{
 	ChainSpec as GenericChainSpec, NoExtension, LightSyncState, SerializableLightSyncState,
+	CodeSubstitute,
 };
 pub use extension::{Group, Fork, Forks, Extension, GetExtension, get_extension};
 pub use sc_chain_spec_derive::{ChainSpecExtension, ChainSpecGroup};
@@ -147,6 +148,10 @@ pub trait ChainSpec: BuildStorage + Send + Sync {
 	fn properties(&self) -> Properties;
 	/// Returns a reference to defined chain spec extensions.
 	fn extensions(&self) -> &dyn GetExtension;
+	/// Runtime codes used instead of the on-chain code from some blocks.
+	fn code_substitutes(&self) -> &[CodeSubstitute] {
+		&[]
+	}
 	/// Add a bootnode to the list.
 	fn add_boot_node(&mut self, addr: MultiaddrWithPeerId);
 	/// Return spec as JSON.
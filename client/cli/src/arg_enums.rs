@@ -204,6 +204,72 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// What to do with new blocks when too many offchain workers are running.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy)]
+	pub enum OffchainWorkerWhenBusy {
+		// Don't run the offchain workers for the new block.
+		Skip,
+		// Run them once one of the running executions completes.
+		Queue,
+	}
+}
+
+arg_enum! {
+	/// Which blocks offchain workers run on.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy)]
+	pub enum OffchainWorkerTrigger {
+		// New best blocks, as soon as they are imported.
+		NewBest,
+		// Finalized blocks.
+		Finalized,
+		// Both new best and finalized blocks.
+		Both,
+	}
+}
+
+impl Into<sc_service::config::OffchainTrigger> for OffchainWorkerTrigger {
+	fn into(self) -> sc_service::config::OffchainTrigger {
+		match self {
+			OffchainWorkerTrigger::NewBest => sc_service::config::OffchainTrigger::NewBest,
+			OffchainWorkerTrigger::Finalized => sc_service::config::OffchainTrigger::Finalized,
+			OffchainWorkerTrigger::Both => sc_service::config::OffchainTrigger::Both,
+		}
+	}
+}
+
+arg_enum! {
+	/// What to do with offchain storage writes past the quota.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy)]
+	pub enum OffchainStoragePruning {
+		// Evict the least recently written keys.
+		Lru,
+		// Refuse the write.
+		Reject,
+	}
+}
+
+impl Into<sc_service::config::OffchainPruningPolicy> for OffchainStoragePruning {
+	fn into(self) -> sc_service::config::OffchainPruningPolicy {
+		match self {
+			OffchainStoragePruning::Lru => sc_service::config::OffchainPruningPolicy::Lru,
+			OffchainStoragePruning::Reject => sc_service::config::OffchainPruningPolicy::Reject,
+		}
+	}
+}
+
+impl Into<sc_service::config::OffchainWhenBusy> for OffchainWorkerWhenBusy {
+	fn into(self) -> sc_service::config::OffchainWhenBusy {
+		match self {
+			OffchainWorkerWhenBusy::Skip => sc_service::config::OffchainWhenBusy::Skip,
+			OffchainWorkerWhenBusy::Queue => sc_service::config::OffchainWhenBusy::Queue,
+		}
+	}
+}
+
 /// Default value for the `--execution-syncing` parameter.
 pub const DEFAULT_EXECUTION_SYNCING: ExecutionStrategy = ExecutionStrategy::NativeElseWasm;
 /// Default value for the `--execution-import-block` parameter.
@@ -0,0 +1,236 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the `generate-session-keys` subcommand
+
+use crate::{
+	Error, KeystoreParams, SharedParams, CryptoScheme, OutputType, OutputTypeFlag, SubstrateCli,
+	utils, with_crypto_scheme,
+};
+use bip39::{MnemonicType, Mnemonic, Language};
+use serde_json::json;
+use std::{sync::Arc, convert::TryFrom, str::FromStr};
+use structopt::StructOpt;
+use sp_core::crypto::{KeyTypeId, SecretString};
+use sp_keystore::{SyncCryptoStorePtr, SyncCryptoStore};
+use sc_keystore::LocalKeystore;
+use sc_service::config::{KeystoreConfig, BasePath};
+
+/// The type of a session key, along with the crypto scheme of the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionKeyType {
+	/// The key type, e.g. `gran`.
+	pub key_type: KeyTypeId,
+	/// The crypto scheme of the key.
+	pub scheme: CryptoScheme,
+}
+
+impl FromStr for SessionKeyType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let index = s.find(':')
+			.ok_or_else(|| format!("Expected a key type as `TYPE:SCHEME`, got `{}`", s))?;
+		let (key_type, scheme) = (&s[..index], &s[index + 1..]);
+
+		Ok(SessionKeyType {
+			key_type: KeyTypeId::try_from(key_type)
+				.map_err(|_| format!("Invalid key type `{}`, expected 4 characters", key_type))?,
+			scheme: scheme.parse()?,
+		})
+	}
+}
+
+/// The `generate-session-keys` command
+#[derive(Debug, StructOpt)]
+#[structopt(
+	name = "generate-session-keys",
+	about = "Generate a set of session keys and print the session keys to register on chain."
+)]
+pub struct GenerateSessionKeysCmd {
+	/// The types of the session keys, as `TYPE:SCHEME`, e.g. `gran:ed25519`.
+	///
+	/// The keys need to be given in the order they are declared in the session keys of the
+	/// runtime, as the session keys are the concatenation of their public keys.
+	#[structopt(
+		long = "key-type",
+		value_name = "TYPE:SCHEME",
+		required = true,
+		number_of_values = 1,
+	)]
+	key_types: Vec<SessionKeyType>,
+
+	/// Insert the generated keys into the keystore of the node.
+	///
+	/// If not given, the keys can be inserted into a running node with the `author_insertKey`
+	/// RPC, using the printed parameters.
+	#[structopt(long)]
+	insert: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub output_scheme: OutputTypeFlag,
+}
+
+impl GenerateSessionKeysCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		// The keys are derived with the password of the keystore either way, so that the keys
+		// inserted through `author_insertKey` match the printed public keys.
+		let (keystore, password) = if self.insert {
+			let (keystore, password) = self.keystore(cli)?;
+			(Some(keystore), password)
+		} else {
+			(None, self.keystore_params.keystore_password()?)
+		};
+
+		let keys = self.key_types.iter().map(|key_type| {
+			let phrase = Mnemonic::new(MnemonicType::Words12, Language::English).into_phrase();
+			let public = with_crypto_scheme!(key_type.scheme, to_vec(&phrase, password.clone()))?;
+			Ok((*key_type, phrase, public))
+		}).collect::<Result<Vec<_>, Error>>()?;
+
+		if let Some(keystore) = keystore {
+			for (key_type, phrase, public) in &keys {
+				SyncCryptoStore::insert_unknown(&*keystore, key_type.key_type, phrase, public)
+					.map_err(|_| Error::KeyStoreOperation)?;
+			}
+		}
+
+		let session_keys = keys.iter()
+			.flat_map(|(_, _, public)| public.iter().cloned())
+			.collect::<Vec<_>>();
+
+		let keys = keys.into_iter().map(|(key_type, phrase, public)| {
+			let key_type_name = String::from_utf8_lossy(&key_type.key_type.0).into_owned();
+			let public = format!("0x{}", hex::encode(public));
+			(key_type_name, key_type.scheme, phrase, public)
+		});
+
+		match self.output_scheme.output_type {
+			OutputType::Json => {
+				let keys = keys.map(|(key_type, scheme, phrase, public)| json!({
+					"keyType": key_type,
+					"scheme": format!("{:?}", scheme),
+					"secretPhrase": phrase,
+					"publicKey": public,
+					"insertKeyParams": [key_type, phrase, public],
+				})).collect::<Vec<_>>();
+				let json = json!({
+					"keys": keys,
+					"inserted": self.insert,
+					"sessionKeys": format!("0x{}", hex::encode(&session_keys)),
+				});
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&json).expect("Json pretty print failed"),
+				);
+			},
+			OutputType::Text => {
+				for (key_type, scheme, phrase, public) in keys {
+					println!(
+						"Key type `{}` ({:?}):\n  \
+						Secret phrase:              `{}`\n  \
+						Public key (hex):           {}\n  \
+						`author_insertKey` params:  [\"{}\", \"{}\", \"{}\"]",
+						key_type,
+						scheme,
+						phrase,
+						public,
+						key_type,
+						phrase,
+						public,
+					);
+				}
+				if self.insert {
+					println!("The keys were inserted into the keystore of the node.");
+				}
+				println!("Session keys (hex): 0x{}", hex::encode(&session_keys));
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Open the keystore of the node, returning it with its password.
+	fn keystore<C: SubstrateCli>(
+		&self,
+		cli: &C,
+	) -> Result<(SyncCryptoStorePtr, Option<SecretString>), Error> {
+		let base_path = self.shared_params
+			.base_path()
+			.unwrap_or_else(|| BasePath::from_project("", "", &C::executable_name()));
+		let chain_id = self.shared_params.chain_id(self.shared_params.is_dev());
+		let chain_spec = cli.load_spec(&chain_id)?;
+		let config_dir = base_path.config_dir(chain_spec.id());
+
+		match self.keystore_params.keystore_config(&config_dir)? {
+			(_, KeystoreConfig::Path { path, password }) => {
+				let keystore: SyncCryptoStorePtr =
+					Arc::new(LocalKeystore::open(path, password.clone())?);
+				Ok((keystore, password))
+			},
+			_ => unreachable!("keystore_config always returns path and password; qed")
+		}
+	}
+}
+
+fn to_vec<P: sp_core::Pair>(uri: &str, pass: Option<SecretString>) -> Result<Vec<u8>, Error> {
+	let p = utils::pair_from_suri::<P>(uri, pass)?;
+	Ok(p.public().as_ref().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_session_key_types() {
+		assert_eq!(
+			"gran:ed25519".parse(),
+			Ok(SessionKeyType { key_type: KeyTypeId(*b"gran"), scheme: CryptoScheme::Ed25519 }),
+		);
+		assert_eq!(
+			"imon:Sr25519".parse(),
+			Ok(SessionKeyType { key_type: KeyTypeId(*b"imon"), scheme: CryptoScheme::Sr25519 }),
+		);
+		assert!("gran".parse::<SessionKeyType>().is_err());
+		assert!("grandpa:ed25519".parse::<SessionKeyType>().is_err());
+		assert!("gran:rsa".parse::<SessionKeyType>().is_err());
+	}
+
+	#[test]
+	fn command_requires_key_types() {
+		assert!(GenerateSessionKeysCmd::from_iter_safe(&["generate-session-keys"]).is_err());
+
+		let cmd = GenerateSessionKeysCmd::from_iter(&[
+			"generate-session-keys",
+			"--key-type", "gran:ed25519",
+			"--key-type", "babe:sr25519",
+		]);
+		assert_eq!(cmd.key_types.len(), 2);
+		assert!(!cmd.insert);
+	}
+}
@@ -24,6 +24,7 @@ use super::{
 	insert_key::InsertKeyCmd,
 	inspect_key::InspectKeyCmd,
 	generate::GenerateCmd,
+	generate_session_keys::GenerateSessionKeysCmd,
 	inspect_node_key::InspectNodeKeyCmd,
 	generate_node_key::GenerateNodeKeyCmd,
 };
@@ -46,6 +47,10 @@ pub enum KeySubcommand {
 
 	/// Insert a key to the keystore of a node.
 	Insert(InsertKeyCmd),
+
+	/// Generate a set of session keys, optionally inserting them into the keystore of a node,
+	/// and print the session keys to register on chain.
+	GenerateSessionKeys(GenerateSessionKeysCmd),
 }
 
 impl KeySubcommand {
@@ -57,6 +62,7 @@ impl KeySubcommand {
 			KeySubcommand::InspectKey(cmd) => cmd.run(),
 			KeySubcommand::Insert(cmd) => cmd.run(cli),
 			KeySubcommand::InspectNodeKey(cmd) => cmd.run(),
+			KeySubcommand::GenerateSessionKeys(cmd) => cmd.run(cli),
 		}
 	}
 }
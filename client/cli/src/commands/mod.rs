@@ -28,6 +28,7 @@ mod revert_cmd;
 mod run_cmd;
 mod generate_node_key;
 mod generate;
+mod generate_session_keys;
 mod insert_key;
 mod inspect_node_key;
 mod inspect_key;
@@ -43,6 +44,7 @@ pub use self::{
 	purge_chain_cmd::PurgeChainCmd,
 	sign::SignCmd,
 	generate::GenerateCmd,
+	generate_session_keys::{GenerateSessionKeysCmd, SessionKeyType},
 	insert_key::InsertKeyCmd,
 	inspect_key::InspectKeyCmd,
 	generate_node_key::GenerateNodeKeyCmd,
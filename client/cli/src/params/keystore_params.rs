@@ -72,6 +72,17 @@ impl KeystoreParams {
 	///
 	/// Returns a vector of remote-urls and the local Keystore configuration
 	pub fn keystore_config(&self, config_dir: &Path) -> Result<(Option<String>, KeystoreConfig)> {
+		let password = self.keystore_password()?;
+		let path = self
+			.keystore_path
+			.clone()
+			.unwrap_or_else(|| config_dir.join(DEFAULT_KEYSTORE_CONFIG_PATH));
+
+		Ok((self.keystore_uri.clone(), KeystoreConfig::Path { path, password }))
+	}
+
+	/// Get the password of the local keystore, reading it from stdin or a file if requested.
+	pub fn keystore_password(&self) -> Result<Option<SecretString>> {
 		let password = if self.password_interactive {
 			#[cfg(not(target_os = "unknown"))]
 			{
@@ -88,12 +99,7 @@ impl KeystoreParams {
 			self.password.clone()
 		};
 
-		let path = self
-			.keystore_path
-			.clone()
-			.unwrap_or_else(|| config_dir.join(DEFAULT_KEYSTORE_CONFIG_PATH));
-
-		Ok((self.keystore_uri.clone(), KeystoreConfig::Path { path, password }))
+		Ok(password)
 	}
 
 	/// helper method to fetch password from `KeyParams` or read from stdin
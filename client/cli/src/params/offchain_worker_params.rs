@@ -23,12 +23,19 @@
 //! targeted at handling input parameter parsing providing
 //! a reasonable abstraction.
 
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
-use sc_service::config::OffchainWorkerConfig;
+use sc_service::config::{
+	HttpClientConfig, OffchainEgressPolicy, OffchainExecutionLimits, OffchainHostPattern,
+	OffchainQuotaScope, OffchainRetryPolicy, OffchainStorageQuota, OffchainTlsConfig,
+	OffchainWorkerConfig,
+};
 use sc_network::config::Role;
 
 use crate::error;
-use crate::OffchainWorkerEnabled;
+use crate::{
+	OffchainStoragePruning, OffchainWorkerEnabled, OffchainWorkerTrigger, OffchainWorkerWhenBusy,
+};
 
 
 /// Offchain worker related parameters.
@@ -55,6 +62,146 @@ pub struct OffchainWorkerParams {
 		value_name = "ENABLE_OFFCHAIN_INDEXING"
 	)]
 	pub indexing_enabled: bool,
+
+	/// Which blocks offchain workers run on.
+	///
+	/// Running them on finalized blocks only guarantees their actions are never based on a
+	/// block which may be reverted, at the cost of a delay.
+	#[structopt(
+		long = "offchain-worker-trigger",
+		value_name = "TRIGGER",
+		possible_values = &OffchainWorkerTrigger::variants(),
+		case_insensitive = true,
+		default_value = "NewBest"
+	)]
+	pub trigger: OffchainWorkerTrigger,
+
+	/// Number of times a failed offchain worker execution is retried.
+	///
+	/// Executions which keep failing are recorded, and can be queried with the
+	/// `offchain_workerFailures` RPC.
+	#[structopt(long = "offchain-worker-retries", value_name = "COUNT", default_value = "0")]
+	pub retries: u32,
+
+	/// Delay in milliseconds before retrying a failed offchain worker execution, doubled for each
+	/// further retry.
+	#[structopt(
+		long = "offchain-worker-retry-backoff",
+		value_name = "MILLISECONDS",
+		default_value = "1000"
+	)]
+	pub retry_backoff: u64,
+
+	/// Maximum number of offchain worker executions running at the same time, at least 1.
+	///
+	/// Defaults to the number of CPUs.
+	#[structopt(
+		long = "offchain-worker-max-concurrent",
+		value_name = "COUNT",
+		parse(try_from_str = parse_non_zero)
+	)]
+	pub max_concurrent: Option<usize>,
+
+	/// What to do with new blocks when the maximum number of offchain workers are running.
+	#[structopt(
+		long = "offchain-worker-when-busy",
+		value_name = "POLICY",
+		possible_values = &OffchainWorkerWhenBusy::variants(),
+		case_insensitive = true,
+		default_value = "Skip"
+	)]
+	pub when_busy: OffchainWorkerWhenBusy,
+
+	/// Maximum number of offchain worker executions waiting for a running one to complete,
+	/// with the `Queue` policy. Past it, the oldest waiting execution is dropped.
+	#[structopt(long = "offchain-worker-max-queued", value_name = "COUNT")]
+	pub max_queued: Option<usize>,
+
+	/// Maximum size in bytes of the keys and values offchain workers keep in persistent storage.
+	#[structopt(long = "offchain-storage-max-bytes", value_name = "BYTES")]
+	pub storage_max_bytes: Option<u64>,
+
+	/// Maximum number of keys offchain workers keep in persistent storage.
+	#[structopt(long = "offchain-storage-max-keys", value_name = "COUNT")]
+	pub storage_max_keys: Option<u64>,
+
+	/// Apply the offchain storage quota separately to the keys sharing their first LEN bytes.
+	///
+	/// By default the quota applies to all the keys together.
+	#[structopt(long = "offchain-storage-quota-prefix-len", value_name = "LEN")]
+	pub storage_quota_prefix_len: Option<usize>,
+
+	/// What to do with offchain storage writes past the quota.
+	#[structopt(
+		long = "offchain-storage-pruning",
+		value_name = "POLICY",
+		possible_values = &OffchainStoragePruning::variants(),
+		case_insensitive = true,
+		default_value = "Lru"
+	)]
+	pub storage_pruning: OffchainStoragePruning,
+
+	/// Only allow offchain workers to send HTTP requests to the given hosts.
+	///
+	/// Accepts domain names, `*.` followed by a domain name to include its subdomains,
+	/// IP addresses and IP ranges in CIDR notation. Host names are resolved before connecting,
+	/// and only the addresses matching a pattern, or of a host matching one, are connected to,
+	/// so IP ranges also apply to the addresses of named hosts. May be passed multiple times.
+	#[structopt(long = "offchain-http-allow", value_name = "HOST")]
+	pub http_allow: Vec<OffchainHostPattern>,
+
+	/// Never allow offchain workers to send HTTP requests to the given hosts.
+	///
+	/// Takes precedence over `--offchain-http-allow`, with the same syntax. An address is never
+	/// connected to if it, or the host it was resolved from, matches one of these patterns.
+	#[structopt(long = "offchain-http-deny", value_name = "HOST")]
+	pub http_deny: Vec<OffchainHostPattern>,
+
+	/// Maximum number of HTTP requests in flight across all offchain workers.
+	///
+	/// Further requests wait until one of them completes.
+	#[structopt(long = "offchain-http-max-concurrent-requests", value_name = "COUNT")]
+	pub http_max_concurrent_requests: Option<usize>,
+
+	/// Maximum number of idle HTTP connections kept open to a single host.
+	#[structopt(long = "offchain-http-max-idle-per-host", value_name = "COUNT")]
+	pub http_max_idle_per_host: Option<usize>,
+
+	/// Timeout in seconds for offchain workers to establish an HTTP connection.
+	#[structopt(long = "offchain-http-connect-timeout", value_name = "SECONDS")]
+	pub http_connect_timeout: Option<u64>,
+
+	/// Timeout in seconds for offchain workers to receive the next bytes of an HTTP response.
+	#[structopt(long = "offchain-http-read-timeout", value_name = "SECONDS")]
+	pub http_read_timeout: Option<u64>,
+
+	/// Maximum number of raw TCP sockets an offchain worker execution keeps open at once.
+	#[structopt(long = "offchain-tcp-max-sockets", value_name = "COUNT")]
+	pub tcp_max_sockets: Option<usize>,
+
+	/// Trust the root certificates of the given PEM file for the HTTPS requests of offchain
+	/// workers, on top of the root certificates of the system.
+	///
+	/// May be passed multiple times.
+	#[structopt(long = "offchain-http-root-certificate", value_name = "PATH")]
+	pub http_root_certificates: Vec<PathBuf>,
+
+	/// PEM file with the certificate chain offchain workers present to the servers requesting
+	/// client authentication.
+	#[structopt(
+		long = "offchain-http-client-certificate",
+		value_name = "PATH",
+		requires = "http-client-key"
+	)]
+	pub http_client_certificate: Option<PathBuf>,
+
+	/// PEM file with the private key of `--offchain-http-client-certificate`.
+	#[structopt(
+		long = "offchain-http-client-key",
+		value_name = "PATH",
+		requires = "http-client-certificate"
+	)]
+	pub http_client_key: Option<PathBuf>,
 }
 
 impl OffchainWorkerParams {
@@ -73,6 +220,88 @@ impl OffchainWorkerParams {
 
 		let indexing_enabled = enabled && self.indexing_enabled;
 
-		Ok(OffchainWorkerConfig { enabled, indexing_enabled })
+		let mut http = HttpClientConfig::default();
+		if let Some(max) = self.http_max_concurrent_requests {
+			http.max_concurrent_requests = max;
+		}
+		if let Some(max) = self.http_max_idle_per_host {
+			http.max_idle_connections_per_host = max;
+		}
+		if let Some(secs) = self.http_connect_timeout {
+			http.connect_timeout = Duration::from_secs(secs);
+		}
+		if let Some(secs) = self.http_read_timeout {
+			http.read_timeout = Duration::from_secs(secs);
+		}
+		if let Some(max) = self.tcp_max_sockets {
+			http.max_tcp_sockets = max;
+		}
+		http.tls = OffchainTlsConfig::from_pem_files(
+			&self.http_root_certificates,
+			self.http_client_certificate.as_deref().zip(self.http_client_key.as_deref()),
+		)?;
+
+		let mut limits = OffchainExecutionLimits::default();
+		if let Some(max) = self.max_concurrent {
+			limits.max_concurrent = max;
+		}
+		limits.when_busy = self.when_busy.into();
+		if let Some(max) = self.max_queued {
+			limits.max_queued = max;
+		}
+
+		let storage_quota = if self.storage_max_bytes.is_some() || self.storage_max_keys.is_some() {
+			Some(OffchainStorageQuota {
+				max_bytes: self.storage_max_bytes,
+				max_keys: self.storage_max_keys,
+				scope: match self.storage_quota_prefix_len {
+					Some(len) => OffchainQuotaScope::PerPrefix { len },
+					None => OffchainQuotaScope::Global,
+				},
+				policy: self.storage_pruning.into(),
+			})
+		} else {
+			None
+		};
+
+		Ok(OffchainWorkerConfig {
+			enabled,
+			indexing_enabled,
+			http,
+			limits,
+			storage_quota,
+			trigger: self.trigger.into(),
+			egress: OffchainEgressPolicy {
+				allow: if self.http_allow.is_empty() { None } else { Some(self.http_allow.clone()) },
+				deny: self.http_deny.clone(),
+			},
+			retries: OffchainRetryPolicy {
+				max_retries: self.retries,
+				backoff: Duration::from_millis(self.retry_backoff),
+			},
+		})
+	}
+}
+
+/// checks that `count` is a positive number
+fn parse_non_zero(count: &str) -> Result<usize, String> {
+	match count.parse::<usize>() {
+		Ok(0) => Err("Must be at least 1".into()),
+		Ok(count) => Ok(count),
+		Err(e) => Err(e.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn zero_max_concurrent_is_rejected() {
+		let parse = |count| OffchainWorkerParams::from_iter_safe(
+			&["test", "--offchain-worker-max-concurrent", count],
+		);
+		assert!(parse("0").is_err());
+		assert_eq!(parse("2").unwrap().max_concurrent, Some(2));
 	}
 }
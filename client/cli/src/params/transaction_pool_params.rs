@@ -29,6 +29,15 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// How long a transaction is banned for, if it is considered invalid. Defaults to 1800s.
+	#[structopt(long = "tx-ban-seconds", value_name = "SECONDS")]
+	pub tx_ban_seconds: Option<u64>,
+
+	/// How long a transaction may wait for its dependencies before being dropped.
+	/// Future transactions are kept until promoted or evicted by default.
+	#[structopt(long = "tx-future-seconds", value_name = "SECONDS")]
+	pub tx_future_seconds: Option<u64>,
 }
 
 impl TransactionPoolParams {
@@ -45,6 +54,12 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		if let Some(ban_seconds) = self.tx_ban_seconds {
+			opts.ban_time = std::time::Duration::from_secs(ban_seconds);
+		}
+
+		opts.max_future_duration = self.tx_future_seconds.map(std::time::Duration::from_secs);
+
 		opts
 	}
 }
@@ -42,6 +42,7 @@ mod storage_cache;
 mod upgrade;
 mod utils;
 mod stats;
+mod metrics;
 #[cfg(feature = "with-parity-db")]
 mod parity_db;
 
@@ -85,7 +86,9 @@ use crate::changes_tries_storage::{DbChangesTrieStorage, DbChangesTrieStorageTra
 use sc_state_db::StateDb;
 use sp_blockchain::{CachedHeaderMetadata, HeaderMetadata, HeaderMetadataCache};
 use crate::storage_cache::{CachingState, SyncingCachingState, SharedCache, new_shared_cache};
-use crate::stats::StateUsageStats;
+use crate::stats::{StateUsageStats, TrieReadStats};
+use crate::metrics::IoMetrics;
+use prometheus_endpoint::Registry;
 
 // Re-export the Database trait so that one can pass an implementation of it.
 pub use sp_database::Database;
@@ -848,17 +851,23 @@ struct StorageDb<Block: BlockT> {
 	pub db: Arc<dyn Database<DbHash>>,
 	pub state_db: StateDb<Block::Hash, Vec<u8>>,
 	prefix_keys: bool,
+	reads: TrieReadStats,
 }
 
 impl<Block: BlockT> sp_state_machine::Storage<HashFor<Block>> for StorageDb<Block> {
 	fn get(&self, key: &Block::Hash, prefix: Prefix) -> Result<Option<DBValue>, String> {
-		if self.prefix_keys {
+		let value = if self.prefix_keys {
 			let key = prefixed_key::<HashFor<Block>>(key, prefix);
 			self.state_db.get(&key, self)
 		} else {
 			self.state_db.get(key.as_ref(), self)
 		}
-		.map_err(|e| format!("Database backend error: {:?}", e))
+		.map_err(|e| format!("Database backend error: {:?}", e))?;
+
+		if let Some(value) = &value {
+			self.reads.tally_read(value.len() as u64);
+		}
+		Ok(value)
 	}
 }
 
@@ -944,6 +953,10 @@ pub struct Backend<Block: BlockT> {
 	transaction_storage: TransactionStorageMode,
 	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo)>,
 	state_usage: Arc<StateUsageStats>,
+	io_metrics: Option<IoMetrics>,
+	/// Number of pins of the blocks pinned with `pin_block`, and whether the pruning of their body
+	/// was deferred until they are unpinned.
+	pinned_blocks: Mutex<HashMap<Block::Hash, (u32, bool)>>,
 }
 
 impl<Block: BlockT> Backend<Block> {
@@ -955,6 +968,18 @@ impl<Block: BlockT> Backend<Block> {
 		Self::from_database(db as Arc<_>, canonicalization_delay, &config)
 	}
 
+	/// Report the trie database I/O of every imported block to the given Prometheus registry.
+	///
+	/// The I/O of every block is also logged under the `db-io` target at debug level.
+	pub fn with_io_metrics(mut self, registry: Option<&Registry>) -> Self {
+		self.io_metrics = registry.and_then(|registry|
+			IoMetrics::register(registry)
+				.map_err(|err| warn!("Failed to register database I/O metrics: {}", err))
+				.ok()
+		);
+		self
+	}
+
 	/// Create new memory-backed client backend for tests.
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test(keep_blocks: u32, canonicalization_delay: u64) -> Self {
@@ -1004,6 +1029,7 @@ impl<Block: BlockT> Backend<Block> {
 			db: db.clone(),
 			state_db,
 			prefix_keys: !config.source.supports_ref_counting(),
+			reads: Default::default(),
 		};
 		let offchain_storage = offchain::LocalStorage::new(db.clone());
 		let changes_tries_storage = DbChangesTrieStorage::new(
@@ -1022,7 +1048,7 @@ impl<Block: BlockT> Backend<Block> {
 			},
 		)?;
 
-		Ok(Backend {
+		let backend = Backend {
 			storage: Arc::new(storage_db),
 			offchain_storage,
 			changes_tries_storage,
@@ -1036,9 +1062,45 @@ impl<Block: BlockT> Backend<Block> {
 			is_archive: is_archive_pruning,
 			io_stats: FrozenForDuration::new(std::time::Duration::from_secs(1)),
 			state_usage: Arc::new(StateUsageStats::new()),
+			io_metrics: None,
+			pinned_blocks: Default::default(),
 			keep_blocks: config.keep_blocks.clone(),
 			transaction_storage: config.transaction_storage.clone(),
-		})
+		};
+		backend.prune_deferred_blocks()?;
+		Ok(backend)
+	}
+
+	/// Prune the bodies of the blocks which were still pinned when the backend was last closed.
+	fn prune_deferred_blocks(&self) -> ClientResult<()> {
+		let deferred = match self.storage.db.get(columns::META, meta_keys::DEFERRED_PRUNING) {
+			Some(deferred) => Vec::<Block::Hash>::decode(&mut &deferred[..]).map_err(|err|
+				sp_blockchain::Error::Backend(format!("Error decoding deferred pruning: {}", err))
+			)?,
+			None => return Ok(()),
+		};
+
+		let mut transaction = Transaction::new();
+		for hash in deferred {
+			self.prune_block(&mut transaction, BlockId::Hash(hash))?;
+		}
+		transaction.remove(columns::META, meta_keys::DEFERRED_PRUNING);
+		self.storage.db.commit(transaction)?;
+		Ok(())
+	}
+
+	/// Record the pinned blocks whose body pruning is deferred, so that it is done on restart if
+	/// they are not unpinned before.
+	fn write_deferred_pruning(
+		transaction: &mut Transaction<DbHash>,
+		pinned_blocks: &HashMap<Block::Hash, (u32, bool)>,
+	) {
+		let deferred = pinned_blocks
+			.iter()
+			.filter(|(_, (_, deferred))| *deferred)
+			.map(|(hash, _)| *hash)
+			.collect::<Vec<_>>();
+		transaction.set_from_vec(columns::META, meta_keys::DEFERRED_PRUNING, deferred.encode());
 	}
 
 	/// Handle setting head within a transaction. `route_to` should be the last
@@ -1306,6 +1368,19 @@ impl<Block: BlockT> Backend<Block> {
 				self.state_usage.tally_writes_nodes(ops, bytes);
 				self.state_usage.tally_removed_nodes(removal, bytes_removal);
 
+				let (reads, bytes_read) = self.storage.reads.take();
+				let io = stats::BlockIoInfo {
+					reads,
+					bytes_read,
+					writes: ops,
+					bytes_written: bytes,
+					removals: removal,
+				};
+				debug!(target: "db-io", "Block #{} ({:?}) {}", number, hash, io);
+				if let Some(metrics) = &self.io_metrics {
+					metrics.report(&io);
+				}
+
 				let mut ops: u64 = 0;
 				let mut bytes: u64 = 0;
 				for (key, value) in operation.storage_updates.iter()
@@ -1557,6 +1632,22 @@ impl<Block: BlockT> Backend<Block> {
 		transaction: &mut Transaction<DbHash>,
 		id: BlockId<Block>,
 	) -> ClientResult<()> {
+		let hash = match id {
+			BlockId::Hash(hash) => Some(hash),
+			BlockId::Number(number) => self.blockchain.hash(number)?,
+		};
+		if let Some(hash) = hash {
+			let mut pinned_blocks = self.pinned_blocks.lock();
+			if let Some((_, deferred)) = pinned_blocks.get_mut(&hash) {
+				debug!(target: "db", "Deferring the removal of pinned block #{}", id);
+				if !*deferred {
+					*deferred = true;
+					Self::write_deferred_pruning(transaction, &pinned_blocks);
+				}
+				return Ok(())
+			}
+		}
+
 		match read_db(&*self.storage.db, columns::KEY_LOOKUP, columns::BODY, id)? {
 			Some(body) => {
 				debug!(target: "db", "Removing block #{}", id);
@@ -1702,6 +1793,9 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	type OffchainStorage = offchain::LocalStorage;
 
 	fn begin_operation(&self) -> ClientResult<Self::BlockImportOperation> {
+		// Only count the trie reads made from now on towards the I/O of the next imported block.
+		self.storage.reads.take();
+
 		let mut old_state = self.state_at(BlockId::Hash(Default::default()))?;
 		old_state.disable_syncing();
 
@@ -2059,6 +2153,43 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		}
 	}
 
+	fn pin_block(&self, hash: &Block::Hash) -> ClientResult<()> {
+		self.storage.state_db.pin(hash).map_err(|_| sp_blockchain::Error::UnknownBlock(
+			format!("State already discarded for {:?}", hash)
+		))?;
+		self.pinned_blocks.lock().entry(*hash).or_insert((0, false)).0 += 1;
+		Ok(())
+	}
+
+	fn unpin_block(&self, hash: &Block::Hash) {
+		let mut transaction = Transaction::new();
+		let prune_body = {
+			let mut pinned_blocks = self.pinned_blocks.lock();
+			let prune_body = match pinned_blocks.get_mut(hash) {
+				Some((pins, _)) if *pins > 1 => {
+					*pins -= 1;
+					false
+				},
+				Some(_) => pinned_blocks.remove(hash).map_or(false, |(_, deferred)| deferred),
+				// Not pinned: leave the state pinned by others alone.
+				None => return,
+			};
+			if prune_body {
+				Self::write_deferred_pruning(&mut transaction, &pinned_blocks);
+			}
+			prune_body
+		};
+		self.storage.state_db.unpin(hash);
+
+		if prune_body {
+			let result = self.prune_block(&mut transaction, BlockId::Hash(*hash))
+				.and_then(|()| self.storage.db.commit(transaction).map_err(Into::into));
+			if let Err(e) = result {
+				warn!("Failed to prune the body of unpinned block {:?}: {:?}", hash, e);
+			}
+		}
+	}
+
 	fn get_import_lock(&self) -> &RwLock<()> {
 		&*self.import_lock
 	}
@@ -2300,6 +2431,59 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn io_metrics_are_reported_on_commit() {
+		fn trie_io(registry: &Registry, op: &str) -> u64 {
+			registry.gather().iter()
+				.find(|family| family.get_name() == "sub_block_trie_io")
+				.and_then(|family| family.get_metric().iter().find(|metric|
+					metric.get_label().iter().any(|l| l.get_name() == "op" && l.get_value() == op)
+				).map(|metric| metric.get_gauge().get_value() as u64))
+				.unwrap_or_default()
+		}
+
+		let registry = Registry::new();
+		let db = Backend::<Block>::new_test(2, 0).with_io_metrics(Some(&registry));
+
+		let mut op = db.begin_operation().unwrap();
+		db.begin_state_operation(&mut op, BlockId::Hash(Default::default())).unwrap();
+		let mut header = Header {
+			number: 0,
+			parent_hash: Default::default(),
+			state_root: Default::default(),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let storage = vec![(vec![1, 3, 5], vec![2, 4, 6]), (vec![1, 2, 3], vec![9, 9, 9])];
+		header.state_root = op.old_state.storage_root(storage
+			.iter()
+			.map(|(x, y)| (&x[..], Some(&y[..])))
+		).0.into();
+		op.reset_storage(Storage {
+			top: storage.into_iter().collect(),
+			children_default: Default::default(),
+		}).unwrap();
+		op.set_block_data(header, Some(vec![]), None, NewBlockState::Best).unwrap();
+		db.commit_operation(op).unwrap();
+
+		assert!(trie_io(&registry, "writes") > 0);
+		assert!(trie_io(&registry, "bytes_written") > 0);
+
+		db.storage.reads.take();
+		let state = db.state_at(BlockId::Number(0)).unwrap();
+		assert_eq!(state.storage(&[5, 5, 5]).unwrap(), None);
+
+		let (reads, bytes_read) = db.storage.reads.take();
+		assert!(reads > 0);
+		assert!(bytes_read > 0);
+		assert_eq!(db.storage.reads.take(), (0, 0));
+
+		// reads made before a block import starts are not counted for it
+		assert_eq!(state.storage(&[7, 7, 7]).unwrap(), None);
+		let _op = db.begin_operation().unwrap();
+		assert_eq!(db.storage.reads.take(), (0, 0));
+	}
+
 	#[test]
 	fn delete_only_when_negative_rc() {
 		sp_tracing::try_init_simple();
@@ -2813,6 +2997,88 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn pinned_blocks_are_not_pruned() {
+		let backend = Backend::<Block>::new_test(2, 10);
+		let mut blocks = Vec::new();
+		let mut prev_hash = Default::default();
+		for i in 0 .. 5 {
+			let hash = insert_block(
+				&backend,
+				i,
+				prev_hash,
+				None,
+				Default::default(),
+				vec![i.into()],
+				None,
+			);
+			blocks.push(hash);
+			prev_hash = hash;
+		}
+
+		backend.pin_block(&blocks[1]).unwrap();
+		for i in 1 .. 5 {
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(blocks[4])).unwrap();
+			op.mark_finalized(BlockId::Hash(blocks[i]), None).unwrap();
+			backend.commit_operation(op).unwrap();
+		}
+		assert!(backend.have_state_at(&blocks[1], 1));
+		let bc = backend.blockchain();
+		assert_eq!(None, bc.body(BlockId::hash(blocks[0])).unwrap());
+		assert_eq!(Some(vec![1.into()]), bc.body(BlockId::hash(blocks[1])).unwrap());
+
+		backend.unpin_block(&blocks[1]);
+		assert_eq!(None, backend.blockchain().body(BlockId::hash(blocks[1])).unwrap());
+		let hash = insert_block(&backend, 5, blocks[4], None, Default::default(), vec![], None);
+		backend.finalize_block(BlockId::Hash(hash), None).unwrap();
+		assert!(!backend.have_state_at(&blocks[1], 1));
+		assert!(backend.pin_block(&blocks[1]).is_err());
+		assert!(backend.have_state_at(&hash, 5));
+	}
+
+	#[test]
+	fn deferred_pruning_is_done_on_restart() {
+		let backend = Backend::<Block>::new_test(2, 10);
+		let mut blocks = Vec::new();
+		let mut prev_hash = Default::default();
+		for i in 0 .. 5 {
+			let hash = insert_block(
+				&backend,
+				i,
+				prev_hash,
+				None,
+				Default::default(),
+				vec![i.into()],
+				None,
+			);
+			blocks.push(hash);
+			prev_hash = hash;
+		}
+
+		backend.pin_block(&blocks[1]).unwrap();
+		for i in 1 .. 5 {
+			backend.finalize_block(BlockId::Hash(blocks[i]), None).unwrap();
+		}
+		let bc = backend.blockchain();
+		assert_eq!(None, bc.body(BlockId::hash(blocks[0])).unwrap());
+		assert_eq!(Some(vec![1.into()]), bc.body(BlockId::hash(blocks[1])).unwrap());
+
+		let db = backend.storage.db.clone();
+		drop(backend);
+		let backend = Backend::<Block>::new(DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			state_pruning: PruningMode::keep_blocks(2),
+			source: DatabaseSettingsSrc::Custom(db),
+			keep_blocks: KeepBlocks::Some(2),
+			transaction_storage: TransactionStorageMode::BlockBody,
+		}, 10).unwrap();
+		let bc = backend.blockchain();
+		assert_eq!(None, bc.body(BlockId::hash(blocks[1])).unwrap());
+		assert_eq!(Some(vec![3.into()]), bc.body(BlockId::hash(blocks[3])).unwrap());
+	}
+
 	#[test]
 	fn prune_blocks_on_finalize_with_fork() {
 		let backend = Backend::<Block>::new_test_with_tx_storage(
@@ -0,0 +1,55 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Database Prometheus metrics.
+
+use prometheus_endpoint::{register, GaugeVec, Opts, PrometheusError, Registry, U64};
+
+use crate::stats::BlockIoInfo;
+
+/// Prometheus metrics of the trie database I/O of imported blocks.
+pub(crate) struct IoMetrics {
+	block_trie_io: GaugeVec<U64>,
+}
+
+impl IoMetrics {
+	/// Register the metrics at the given Prometheus registry.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			block_trie_io: register(
+				GaugeVec::new(
+					Opts::new(
+						"sub_block_trie_io",
+						"Trie database I/O of the last imported block",
+					),
+					&["op"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Report the I/O of an imported block.
+	pub fn report(&self, io: &BlockIoInfo) {
+		self.block_trie_io.with_label_values(&["reads"]).set(io.reads);
+		self.block_trie_io.with_label_values(&["bytes_read"]).set(io.bytes_read);
+		self.block_trie_io.with_label_values(&["writes"]).set(io.writes);
+		self.block_trie_io.with_label_values(&["bytes_written"]).set(io.bytes_written);
+		self.block_trie_io.with_label_values(&["removals"]).set(io.removals);
+	}
+}
@@ -79,6 +79,24 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		self.db.get(columns::OFFCHAIN, &concatenate_prefix_and_key(prefix, key))
 	}
 
+	fn apply_batch<'a>(
+		&mut self,
+		changes: impl IntoIterator<Item = (&'a [u8], &'a [u8], Option<&'a [u8]>)>,
+	) {
+		let mut tx = Transaction::new();
+		for (prefix, key, value) in changes {
+			let key = concatenate_prefix_and_key(prefix, key);
+			match value {
+				Some(value) => tx.set(columns::OFFCHAIN, &key, value),
+				None => tx.remove(columns::OFFCHAIN, &key),
+			}
+		}
+
+		if let Err(err) = self.db.commit(tx) {
+			error!("Error applying batch on local storage: {}", err)
+		}
+	}
+
 	fn compare_and_set(
 		&mut self,
 		prefix: &[u8],
@@ -156,4 +174,20 @@ mod tests {
 		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
 	}
 
+	#[test]
+	fn should_apply_batch() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+
+		storage.set(prefix, b"a", b"1");
+		storage.apply_batch(vec![
+			(&prefix[..], &b"a"[..], None),
+			(&prefix[..], &b"b"[..], Some(&b"2"[..])),
+			(&prefix[..], &b"b"[..], Some(&b"3"[..])),
+		]);
+
+		assert_eq!(storage.get(prefix, b"a"), None);
+		assert_eq!(storage.get(prefix, b"b"), Some(b"3".to_vec()));
+	}
+
 }
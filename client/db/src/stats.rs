@@ -138,3 +138,59 @@ impl StateUsageStats {
 		}
 	}
 }
+
+/// Trie node reads from the database since the last imported block.
+#[derive(Default)]
+pub struct TrieReadStats {
+	reads: AtomicU64,
+	bytes_read: AtomicU64,
+}
+
+impl TrieReadStats {
+	/// Tally one trie node read, of some length.
+	pub fn tally_read(&self, data_bytes: u64) {
+		self.reads.fetch_add(1, AtomicOrdering::Relaxed);
+		self.bytes_read.fetch_add(data_bytes, AtomicOrdering::Relaxed);
+	}
+
+	/// Returns the number of reads and bytes read, and resets them.
+	pub fn take(&self) -> (u64, u64) {
+		(
+			self.reads.swap(0, AtomicOrdering::Relaxed),
+			self.bytes_read.swap(0, AtomicOrdering::Relaxed),
+		)
+	}
+}
+
+/// Trie database I/O of an imported block.
+///
+/// Reads are all trie node reads since the import operation of the block began, which
+/// includes the ones made while executing the block. Reads made concurrently by other
+/// users of the database, e.g. RPC calls, are counted as well.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockIoInfo {
+	/// Number of trie nodes read.
+	pub reads: u64,
+	/// Total bytes of trie nodes read.
+	pub bytes_read: u64,
+	/// Number of trie nodes written.
+	pub writes: u64,
+	/// Total bytes of trie nodes written.
+	pub bytes_written: u64,
+	/// Number of trie nodes removed.
+	pub removals: u64,
+}
+
+impl std::fmt::Display for BlockIoInfo {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			f,
+			"trie reads: {} ({} bytes), trie writes: {} ({} bytes), trie removals: {}",
+			self.reads,
+			self.bytes_read,
+			self.writes,
+			self.bytes_written,
+			self.removals,
+		)
+	}
+}
@@ -34,6 +34,7 @@ use sp_state_machine::{
 	StorageCollection, ChildStorageCollection,
 };
 use log::trace;
+use sp_utils::memory_usage::MemoryUsage;
 use crate::{utils::Meta, stats::StateUsageStats};
 
 const STATE_CACHE_BLOCKS: usize = 12;
@@ -51,9 +52,12 @@ pub struct Cache<B: BlockT> {
 	/// Information on the modifications in recently committed blocks; specifically which keys
 	/// changed in which block. Ordered by block number.
 	modifications: VecDeque<BlockChanges<B::Header>>,
+	/// Memory usage metrics of the storage caches.
+	memory_usage: MemoryUsage,
 }
 
-struct LRUMap<K, V>(LinkedHashMap<K, V>, usize, usize);
+/// Map, used size, size limit and size evicted since last reported.
+struct LRUMap<K, V>(LinkedHashMap<K, V>, usize, usize, usize);
 
 /// Internal trait similar to `heapsize` but using
 /// a simply estimation.
@@ -126,8 +130,9 @@ impl<K: EstimateSize + Eq + StdHash, V: EstimateSize> LRUMap<K, V> {
 
 		while *storage_used_size > limit {
 			if let Some((k,v)) = lmap.pop_front() {
-				*storage_used_size -= k.estimate_size();
-				*storage_used_size -= v.estimate_size();
+				let size = k.estimate_size() + v.estimate_size();
+				*storage_used_size -= size;
+				self.3 += size;
 			} else {
 				// can happen fairly often as we get value from multiple lru
 				// and only remove from a single lru
@@ -145,6 +150,11 @@ impl<K: EstimateSize + Eq + StdHash, V: EstimateSize> LRUMap<K, V> {
 	fn used_size(&self) -> usize {
 		self.1
 	}
+
+	fn take_evicted(&mut self) -> usize {
+		std::mem::take(&mut self.3)
+	}
+
 	fn clear(&mut self) {
 		self.0.clear();
 		self.1 = 0;
@@ -160,6 +170,13 @@ impl<B: BlockT> Cache<B> {
 			//  ignore small hashes storage and self.lru_hashes.used_size()
 	}
 
+	/// Report memory usage and evictions of the storage cache.
+	fn report_memory(&mut self) {
+		let evicted = self.lru_storage.take_evicted() + self.lru_child_storage.take_evicted();
+		self.memory_usage.report_eviction(evicted);
+		self.memory_usage.report_usage(self.used_storage_cache_size());
+	}
+
 	/// Synchronize the shared cache with the best block state.
 	///
 	/// This function updates the shared cache by removing entries
@@ -235,13 +252,14 @@ pub fn new_shared_cache<B: BlockT>(
 		Mutex::new(
 			Cache {
 				lru_storage: LRUMap(
-					LinkedHashMap::new(), 0, shared_cache_size * top / child_ratio.1
+					LinkedHashMap::new(), 0, shared_cache_size * top / child_ratio.1, 0
 				),
-				lru_hashes: LRUMap(LinkedHashMap::new(), 0, FIX_LRU_HASH_SIZE),
+				lru_hashes: LRUMap(LinkedHashMap::new(), 0, FIX_LRU_HASH_SIZE, 0),
 				lru_child_storage: LRUMap(
-					LinkedHashMap::new(), 0, shared_cache_size * child_ratio.0 / child_ratio.1
+					LinkedHashMap::new(), 0, shared_cache_size * child_ratio.0 / child_ratio.1, 0
 				),
 				modifications: VecDeque::new(),
+				memory_usage: MemoryUsage::new("state_cache", shared_cache_size),
 			}
 		)
 	)
@@ -437,6 +455,8 @@ impl<B: BlockT> CacheChanges<B> {
 				cache.modifications.push_back(block_changes);
 			}
 		}
+
+		cache.report_memory();
 	}
 }
 
@@ -59,6 +59,8 @@ pub mod meta_keys {
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
+	/// Hashes of the pinned blocks whose body is to be pruned once they are unpinned.
+	pub const DEFERRED_PRUNING: &[u8; 8] = b"deferred";
 }
 
 /// Database metadata.
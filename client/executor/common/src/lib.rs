@@ -22,6 +22,8 @@
 #![deny(unused_crate_dependencies)]
 
 pub mod error;
+#[cfg(feature = "host-profiler")]
+pub mod profiler;
 pub mod sandbox;
 pub mod util;
 pub mod wasm_runtime;
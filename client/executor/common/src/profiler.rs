@@ -0,0 +1,166 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Host function profiler.
+//!
+//! Records the time spent in host functions while a block is executed in wasm, keyed by the
+//! stack of frames (runtime method, then host functions) that led to it. The report uses the
+//! folded stack format understood by `flamegraph.pl` and `inferno-flamegraph`, where every line
+//! is a `;`-separated stack followed by the self time of its top frame in microseconds.
+//!
+//! If the `SUBSTRATE_HOST_PROFILE` environment variable is set, the report is written to the
+//! file it points to every time the execution of a block finishes.
+
+use std::{
+	cell::RefCell, collections::HashMap, fmt::Write as _, sync::Mutex, time::{Duration, Instant},
+};
+
+/// The runtime method whose executions are profiled.
+const PROFILED_METHOD: &str = "Core_execute_block";
+
+/// Environment variable pointing to the file the report is written to.
+const OUTPUT_ENV: &str = "SUBSTRATE_HOST_PROFILE";
+
+lazy_static::lazy_static! {
+	static ref PROFILE: Mutex<HashMap<String, Duration>> = Mutex::new(HashMap::new());
+}
+
+thread_local! {
+	static STACK: RefCell<Vec<Frame>> = RefCell::new(Vec::new());
+}
+
+struct Frame {
+	name: String,
+	started: Instant,
+	children: Duration,
+}
+
+/// Guard of a profiled frame, which is recorded when the guard is dropped.
+#[must_use]
+pub struct Guard {
+	active: bool,
+}
+
+/// Enter a new frame named `name`.
+///
+/// Frames are only recorded while the [`PROFILED_METHOD`] is being executed, so this
+/// is a cheap no-op for any other runtime call.
+pub fn enter(name: &str) -> Guard {
+	let active = STACK.with(|stack| {
+		let mut stack = stack.borrow_mut();
+		if stack.is_empty() && name != PROFILED_METHOD {
+			return false;
+		}
+
+		stack.push(Frame { name: name.into(), started: Instant::now(), children: Duration::default() });
+		true
+	});
+
+	Guard { active }
+}
+
+impl Drop for Guard {
+	fn drop(&mut self) {
+		if !self.active {
+			return;
+		}
+
+		let finished = STACK.with(|stack| {
+			let mut stack = stack.borrow_mut();
+			let frame = match stack.pop() {
+				Some(frame) => frame,
+				None => return false,
+			};
+
+			let elapsed = frame.started.elapsed();
+			let mut folded = String::new();
+			for parent in stack.iter() {
+				folded.push_str(&parent.name);
+				folded.push(';');
+			}
+			folded.push_str(&frame.name);
+
+			*PROFILE.lock().expect("profiler lock is never poisoned; qed")
+				.entry(folded)
+				.or_default() += elapsed.checked_sub(frame.children).unwrap_or_default();
+
+			match stack.last_mut() {
+				Some(parent) => {
+					parent.children += elapsed;
+					false
+				},
+				None => true,
+			}
+		});
+
+		if finished {
+			if let Ok(path) = std::env::var(OUTPUT_ENV) {
+				if let Err(e) = std::fs::write(&path, report()) {
+					log::warn!(target: "wasm-profiler", "Failed to write host profile to {}: {}", path, e);
+				}
+			}
+		}
+	}
+}
+
+/// Render the profile recorded so far in the folded stack format.
+pub fn report() -> String {
+	let profile = PROFILE.lock().expect("profiler lock is never poisoned; qed");
+	let mut stacks = profile.iter().collect::<Vec<_>>();
+	stacks.sort();
+
+	let mut report = String::new();
+	for (stack, time) in stacks {
+		let _ = writeln!(report, "{} {}", stack, time.as_micros());
+	}
+	report
+}
+
+/// Discard the profile recorded so far.
+pub fn reset() {
+	PROFILE.lock().expect("profiler lock is never poisoned; qed").clear();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_records_block_execution() {
+		reset();
+
+		{
+			let _call = enter("Core_version");
+			let _host = enter("ext_storage_get_version_1");
+		}
+		assert!(report().is_empty());
+
+		{
+			let _call = enter(PROFILED_METHOD);
+			let _host = enter("ext_storage_get_version_1");
+		}
+		let report = report();
+		let stacks = report.lines()
+			.map(|line| line.rsplitn(2, ' ').nth(1).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			stacks,
+			vec!["Core_execute_block", "Core_execute_block;ext_storage_get_version_1"],
+		);
+	}
+}
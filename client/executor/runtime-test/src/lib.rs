@@ -16,7 +16,8 @@ use sp_std::{vec::Vec, vec};
 
 #[cfg(not(feature = "std"))]
 use sp_io::{
-	storage, hashing::{blake2_128, blake2_256, sha2_256, twox_128, twox_256},
+	storage,
+	hashing::{blake2_128, blake2_256, blake2_512, sha2_256, ripemd_160, twox_128, twox_256},
 	crypto::{ed25519_verify, sr25519_verify}, wasm_tracing,
 };
 #[cfg(not(feature = "std"))]
@@ -71,6 +72,20 @@ sp_core::wasm_export_functions! {
 
 	fn test_exhaust_heap() -> Vec<u8> { Vec::with_capacity(16777216) }
 
+	fn test_heap_stats() -> Vec<u32> {
+		let before = sp_io::heap::stats();
+		let data = vec![1u8; 1024];
+		let during = sp_io::heap::stats();
+		drop(data);
+		let after = sp_io::heap::stats();
+		vec![
+			during.bytes_allocated - before.bytes_allocated,
+			during.bytes_allocated - after.bytes_allocated,
+			during.total_allocations - before.total_allocations,
+			(after.bytes_allocated_peak >= during.bytes_allocated) as u32,
+		]
+	}
+
 	fn test_panic() { panic!("test panic") }
 
 	fn test_conditional_panic(input: Vec<u8>) -> Vec<u8> {
@@ -85,6 +100,10 @@ sp_core::wasm_export_functions! {
 		blake2_256(&input).to_vec()
 	}
 
+	fn test_blake2_512(input: Vec<u8>) -> Vec<u8> {
+		blake2_512(&input).to_vec()
+	}
+
 	fn test_blake2_128(input: Vec<u8>) -> Vec<u8> {
 		blake2_128(&input).to_vec()
 	}
@@ -93,6 +112,10 @@ sp_core::wasm_export_functions! {
 		sha2_256(&input).to_vec()
 	}
 
+	fn test_ripemd_160(input: Vec<u8>) -> Vec<u8> {
+		ripemd_160(&input).to_vec()
+	}
+
 	fn test_twox_256(input: Vec<u8>) -> Vec<u8> {
 		twox_256(&input).to_vec()
 	}
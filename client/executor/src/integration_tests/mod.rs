@@ -295,6 +295,38 @@ fn blake2_128_should_work(wasm_method: WasmExecutionMethod) {
 	);
 }
 
+test_wasm_execution!(blake2_512_should_work);
+fn blake2_512_should_work(wasm_method: WasmExecutionMethod) {
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+	assert_eq!(
+		call_in_wasm(
+			"test_blake2_512",
+			&[0],
+			wasm_method,
+			&mut ext,
+		)
+		.unwrap(),
+		[
+			&hex!("786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419")[..],
+			&hex!("d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce")[..],
+		].concat().encode(),
+	);
+	assert_eq!(
+		call_in_wasm(
+			"test_blake2_512",
+			&b"abc".to_vec().encode(),
+			wasm_method,
+			&mut ext,
+		)
+		.unwrap(),
+		[
+			&hex!("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1")[..],
+			&hex!("7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")[..],
+		].concat().encode(),
+	);
+}
+
 test_wasm_execution!(sha2_256_should_work);
 fn sha2_256_should_work(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
@@ -325,6 +357,32 @@ fn sha2_256_should_work(wasm_method: WasmExecutionMethod) {
 	);
 }
 
+test_wasm_execution!(ripemd_160_should_work);
+fn ripemd_160_should_work(wasm_method: WasmExecutionMethod) {
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+	assert_eq!(
+		call_in_wasm(
+			"test_ripemd_160",
+			&[0],
+			wasm_method,
+			&mut ext,
+		)
+		.unwrap(),
+		hex!("9c1185a5c5e9fc54612808977ee8f548b2258d31").to_vec().encode(),
+	);
+	assert_eq!(
+		call_in_wasm(
+			"test_ripemd_160",
+			&b"abc".to_vec().encode(),
+			wasm_method,
+			&mut ext,
+		)
+		.unwrap(),
+		hex!("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc").to_vec().encode(),
+	);
+}
+
 test_wasm_execution!(twox_256_should_work);
 fn twox_256_should_work(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
@@ -553,6 +611,26 @@ fn should_trap_when_heap_exhausted(wasm_method: WasmExecutionMethod) {
 	assert!(err.contains("Allocator ran out of space"));
 }
 
+test_wasm_execution!(heap_stats_track_allocations);
+fn heap_stats_track_allocations(wasm_method: WasmExecutionMethod) {
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+
+	let output = call_in_wasm(
+		"test_heap_stats",
+		&[0],
+		wasm_method,
+		&mut ext,
+	).unwrap();
+	let stats = <Vec<u32>>::decode(&mut &output[..]).unwrap();
+
+	// The vector of 1024 bytes has been allocated, then freed.
+	assert!(stats[0] >= 1024);
+	assert!(stats[1] >= 1024);
+	assert!(stats[2] >= 1);
+	assert_eq!(stats[3], 1);
+}
+
 test_wasm_execution!(returns_mutable_static);
 fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
@@ -54,6 +54,9 @@ pub trait RuntimeInfo {
 	/// Native runtime information.
 	fn native_version(&self) -> &NativeVersion;
 
+	/// The host functions provided to the runtime.
+	fn host_functions(&self) -> &[&'static dyn sp_wasm_interface::Function];
+
 	/// Extract [`RuntimeVersion`](sp_version::RuntimeVersion) of the given `runtime_code`.
 	fn runtime_version(
 		&self,
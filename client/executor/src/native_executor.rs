@@ -309,6 +309,10 @@ impl<D: NativeExecutionDispatch> RuntimeInfo for NativeExecutor<D> {
 		&self.native_version
 	}
 
+	fn host_functions(&self) -> &[&'static dyn Function] {
+		&self.wasm.host_functions
+	}
+
 	fn runtime_version(
 		&self,
 		ext: &mut dyn Externalities,
@@ -506,6 +510,9 @@ impl<D: NativeExecutionDispatch + 'static> CodeExecutor for NativeExecutor<D> {
 						with_externalities_safe(
 							&mut **ext,
 							move || {
+								#[cfg(feature = "host-profiler")]
+								let _guard = sc_executor_common::profiler::enter(method);
+
 								RuntimeInstanceSpawn::register_on_externalities(module.clone());
 								instance.call_export(method, data).map(NativeOrEncoded::Encoded)
 							}
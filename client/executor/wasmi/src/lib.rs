@@ -29,6 +29,7 @@ use sp_core::sandbox as sandbox_primitives;
 use log::{error, trace, debug};
 use sp_wasm_interface::{
 	FunctionContext, Pointer, WordSize, Sandbox, MemoryId, Result as WResult, Function,
+	AllocationStats,
 };
 use sp_runtime_interface::unpack_ptr_and_len;
 use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance, InvokeMethod};
@@ -121,6 +122,10 @@ impl<'a> FunctionContext for FunctionExecutor<'a> {
 		})
 	}
 
+	fn allocation_stats(&self) -> AllocationStats {
+		self.heap.stats()
+	}
+
 	fn sandbox(&mut self) -> &mut dyn Sandbox {
 		self
 	}
@@ -389,6 +394,9 @@ impl<'a> wasmi::Externals for FunctionExecutor<'a> {
 		let mut args = args.as_ref().iter().copied().map(Into::into);
 
 		if let Some(function) = self.host_functions.get(index) {
+			#[cfg(feature = "host-profiler")]
+			let _guard = sc_executor_common::profiler::enter(function.name());
+
 			function.execute(self, &mut args)
 				.map_err(|msg| Error::FunctionExecution(function.name().to_string(), msg))
 				.map_err(wasmi::Trap::from)
@@ -153,6 +153,10 @@ impl<'a> sp_wasm_interface::FunctionContext for HostContext<'a> {
 			.map_err(|e| e.to_string())
 	}
 
+	fn allocation_stats(&self) -> sp_wasm_interface::AllocationStats {
+		self.allocator.borrow().stats()
+	}
+
 	fn sandbox(&mut self) -> &mut dyn Sandbox {
 		self
 	}
@@ -195,6 +195,9 @@ fn call_static(
 		let mut params = wasmtime_params.iter().cloned().map(into_value);
 
 		std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			#[cfg(feature = "host-profiler")]
+			let _guard = sc_executor_common::profiler::enter(static_func.name());
+
 			static_func.execute(&mut host_ctx, &mut params)
 		}))
 	});
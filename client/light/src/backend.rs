@@ -238,6 +238,13 @@ impl<S, Block> ClientBackend<Block> for Backend<S, HashFor<Block>>
 		Ok(GenesisOrUnavailableState::Unavailable)
 	}
 
+	fn pin_block(&self, _hash: &Block::Hash) -> ClientResult<()> {
+		// The light client doesn't store any state that could be pruned.
+		Ok(())
+	}
+
+	fn unpin_block(&self, _hash: &Block::Hash) {}
+
 	fn revert(
 		&self,
 		_n: NumberFor<Block>,
@@ -45,7 +45,7 @@ use sc_client_api::{
 	light::RemoteCallRequest,
 	call_executor::CallExecutor,
 };
-use sc_executor::{RuntimeVersion, NativeVersion};
+use sc_executor::{RuntimeVersion, NativeVersion, sp_wasm_interface::Function};
 
 /// Call executor that is able to execute calls only on genesis state.
 ///
@@ -169,6 +169,10 @@ impl<Block, B, Local> CallExecutor<Block> for
 	fn native_runtime_version(&self) -> Option<&NativeVersion> {
 		None
 	}
+
+	fn host_functions(&self) -> &[&'static dyn Function] {
+		self.local.host_functions()
+	}
 }
 
 /// Prove contextual execution using given block header in environment.
@@ -172,15 +172,21 @@ pub type TransactionImportFuture = Pin<Box<dyn Future<Output=TransactionImport>
 /// Transaction pool interface
 pub trait TransactionPool<H: ExHashT, B: BlockT>: Send + Sync {
 	/// Get transactions from the pool that are ready to be propagated.
+	///
+	/// Transactions are expected in the order they should be propagated in, the most important
+	/// ones first: when a peer can't be sent all of them at once, the first ones are sent first.
 	fn transactions(&self) -> Vec<(H, B::Extrinsic)>;
 	/// Get hash of transaction.
 	fn hash_of(&self, transaction: &B::Extrinsic) -> H;
-	/// Import a transaction into the pool.
+	/// Get hash of transaction given its SCALE encoding.
+	fn hash_of_encoded(&self, encoded: &[u8]) -> H;
+	/// Import a transaction into the pool, along with the encoding it was received in.
 	///
 	/// This will return future.
 	fn import(
 		&self,
 		transaction: B::Extrinsic,
+		encoded: &[u8],
 	) -> TransactionImportFuture;
 	/// Notify the pool about transactions broadcast.
 	fn on_broadcasted(&self, propagations: HashMap<H, Vec<String>>);
@@ -205,9 +211,14 @@ impl<H: ExHashT + Default, B: BlockT> TransactionPool<H, B> for EmptyTransaction
 		Default::default()
 	}
 
+	fn hash_of_encoded(&self, _encoded: &[u8]) -> H {
+		Default::default()
+	}
+
 	fn import(
 		&self,
-		_transaction: B::Extrinsic
+		_transaction: B::Extrinsic,
+		_encoded: &[u8],
 	) -> TransactionImportFuture {
 		Box::pin(future::ready(TransactionImport::KnownGood))
 	}
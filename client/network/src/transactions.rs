@@ -30,7 +30,7 @@
 use crate::{
 	ExHashT, Event, ObservedRole,
 	config::{self, ProtocolId, TransactionPool, TransactionImportFuture, TransactionImport},
-	error, protocol::message, service::NetworkService, utils::{interval, LruHashSet},
+	error, service::NetworkService, utils::{interval, LruHashSet},
 };
 
 use codec::{Decode, Encode};
@@ -57,6 +57,12 @@ const MAX_KNOWN_TRANSACTIONS: usize = 10240; // ~300kb per peer + overhead.
 /// Maximum allowed size for a transactions notification.
 const MAX_TRANSACTIONS_SIZE: u64 = 16 * 1024 * 1024;
 
+/// Maximum size of the transactions sent to a peer at once.
+///
+/// The transactions that don't fit are sent to the peer the next time transactions are
+/// propagated, after the more important transactions that became ready in the meantime.
+const MAX_PROPAGATED_TRANSACTIONS_SIZE: usize = 2 * 1024 * 1024;
+
 /// Maximum number of transaction validation request we keep at any moment.
 const MAX_PENDING_TRANSACTIONS: usize = 8192;
 
@@ -337,9 +343,7 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 						continue;
 					}
 
-					if let Ok(m) = <message::Transactions<B::Extrinsic> as Decode>::decode(
-						&mut message.as_ref(),
-					) {
+					if let Ok(m) = decode_transactions::<B::Extrinsic>(&message) {
 						self.on_transactions(remote, m);
 					} else {
 						warn!(target: "sub-libp2p", "Failed to decode transactions list");
@@ -356,7 +360,7 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 	fn on_transactions(
 		&mut self,
 		who: PeerId,
-		transactions: message::Transactions<B::Extrinsic>,
+		transactions: Vec<(B::Extrinsic, &[u8])>,
 	) {
 		// sending transaction to light node is considered a bad behavior
 		if matches!(self.local_role, config::Role::Light) {
@@ -374,7 +378,7 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 
 		trace!(target: "sync", "Received {} transactions from {}", transactions.len(), who);
 		if let Some(ref mut peer) = self.peers.get_mut(&who) {
-			for t in transactions {
+			for (t, encoded) in transactions {
 				if self.pending_transactions.len() > MAX_PENDING_TRANSACTIONS {
 					debug!(
 						target: "sync",
@@ -384,7 +388,7 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 					break;
 				}
 
-				let hash = self.transaction_pool.hash_of(&t);
+				let hash = self.transaction_pool.hash_of_encoded(encoded);
 				peer.known_transactions.insert(hash.clone());
 
 				self.service.report_peer(who.clone(), rep::ANY_TRANSACTION);
@@ -392,7 +396,7 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 				match self.pending_transactions_peers.entry(hash.clone()) {
 					Entry::Vacant(entry) => {
 						self.pending_transactions.push(PendingTransaction {
-							validation: self.transaction_pool.import(t),
+							validation: self.transaction_pool.import(t, encoded),
 							tx_hash: hash,
 						});
 						entry.insert(vec![who.clone()]);
@@ -443,11 +447,11 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 				continue;
 			}
 
-			let (hashes, to_send): (Vec<_>, Vec<_>) = transactions
-				.iter()
-				.filter(|&(ref hash, _)| peer.known_transactions.insert(hash.clone()))
-				.cloned()
-				.unzip();
+			let (hashes, to_send) = select_transactions(
+				transactions,
+				&mut peer.known_transactions,
+				MAX_PROPAGATED_TRANSACTIONS_SIZE,
+			);
 
 			propagated_transactions += hashes.len();
 
@@ -486,3 +490,118 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 		self.transaction_pool.on_broadcasted(propagated_to);
 	}
 }
+
+/// Select the transactions to send to a peer, in order, among those it doesn't know yet.
+///
+/// The selection stops at the first transaction that would bring the encoded size of the
+/// selected transactions above `max_size`, so that less important transactions never overtake
+/// more important ones. The selected transactions are marked as known by the peer.
+fn select_transactions<H: ExHashT, E: Encode + Clone>(
+	transactions: &[(H, E)],
+	known_transactions: &mut LruHashSet<H>,
+	max_size: usize,
+) -> (Vec<H>, Vec<E>) {
+	let mut size = 0;
+	let mut hashes = Vec::new();
+	let mut selected = Vec::new();
+
+	for (hash, transaction) in transactions {
+		if known_transactions.contains(hash) {
+			continue
+		}
+
+		// Always send at least one transaction, no matter its size.
+		size += transaction.encoded_size();
+		if size > max_size && !selected.is_empty() {
+			break
+		}
+
+		known_transactions.insert(hash.clone());
+		hashes.push(hash.clone());
+		selected.push(transaction.clone());
+	}
+
+	(hashes, selected)
+}
+
+/// Decode a transactions notification.
+///
+/// Each transaction comes along with the slice of `message` holding its encoding, which is
+/// hashed and submitted to the pool in place of the transaction.
+///
+/// The message is rejected if any of these slices is not the canonical encoding of the decoded
+/// transaction, as hashing it would not give the hash of the transaction, and a peer could then
+/// get the same transaction into the pool under several hashes. Checking this encodes every
+/// transaction once. Without the slices, both the handler computing the hash and the pool
+/// importing the transaction would encode it, so one encoding per transaction is still spared.
+fn decode_transactions<E: Decode + Encode>(
+	message: &[u8],
+) -> Result<Vec<(E, &[u8])>, codec::Error> {
+	let mut input = message;
+	let count = codec::Compact::<u32>::decode(&mut input)?.0;
+
+	let mut transactions = Vec::new();
+	for _ in 0..count {
+		let encoded = input;
+		let transaction = E::decode(&mut input)?;
+		let encoded = &encoded[..encoded.len() - input.len()];
+		if !transaction.using_encoded(|canonical| canonical == encoded) {
+			return Err("Non-canonical transaction encoding".into())
+		}
+		transactions.push((transaction, encoded));
+	}
+
+	Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn select_transactions_respects_order_and_size() {
+		let transactions = vec![(1u64, vec![1u8; 10]), (2, vec![2; 20]), (3, vec![3; 1])];
+		let mut known = LruHashSet::new(NonZeroUsize::new(16).unwrap());
+
+		// The small third transaction is not selected before the second one.
+		let (hashes, _) = select_transactions(&transactions, &mut known, 15);
+		assert_eq!(hashes, vec![1]);
+
+		let (hashes, selected) = select_transactions(&transactions, &mut known, 15);
+		assert_eq!(hashes, vec![2]);
+		assert_eq!(selected, vec![vec![2; 20]]);
+
+		let (hashes, _) = select_transactions(&transactions, &mut known, 15);
+		assert_eq!(hashes, vec![3]);
+
+		assert!(select_transactions(&transactions, &mut known, 15).0.is_empty());
+	}
+
+	#[test]
+	fn decode_transactions_rejects_non_canonical_encoding() {
+		/// Decodes like an `UncheckedExtrinsic`, ignoring the value of its length prefix.
+		#[derive(Debug, PartialEq)]
+		struct Prefixed(u8);
+
+		impl Encode for Prefixed {
+			fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+				(codec::Compact(1u32), self.0).encode_to(dest)
+			}
+		}
+
+		impl Decode for Prefixed {
+			fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+				let _length = codec::Compact::<u32>::decode(input)?;
+				Ok(Prefixed(u8::decode(input)?))
+			}
+		}
+
+		let canonical = [4, 4, 7];
+		assert_eq!(
+			decode_transactions::<Prefixed>(&canonical).unwrap(),
+			vec![(Prefixed(7), &canonical[1..])],
+		);
+
+		assert!(decode_transactions::<Prefixed>(&[4, 8, 7]).is_err());
+	}
+}
@@ -59,6 +59,11 @@ impl<T: Hash + Eq> LruHashSet<T> {
 		}
 		false
 	}
+
+	/// Returns `true` if the set contains the given element, without updating its LRU position.
+	pub fn contains(&self, e: &T) -> bool {
+		self.set.contains(e)
+	}
 }
 
 #[cfg(test)]
@@ -22,6 +22,8 @@ use std::{
 	convert::TryFrom,
 	thread::sleep,
 	collections::HashSet,
+	time::Duration,
+	fmt,
 };
 
 use crate::NetworkProvider;
@@ -31,10 +33,12 @@ use codec::{Encode, Decode};
 use sp_core::OpaquePeerId;
 use sp_core::offchain::{
 	self, HttpRequestId, Timestamp, HttpRequestStatus, HttpError,
-	OffchainStorage, OpaqueNetworkState, OpaqueMultiaddr, StorageKind,
+	OffchainStorage, OpaqueNetworkState, OpaqueMultiaddr, StorageKind, TcpError, TcpSocketId,
 };
 pub use sp_offchain::STORAGE_PREFIX;
 pub use http::SharedClient;
+pub use quota::{PruningPolicy, QuotaScope, StorageQuota};
+pub use egress::{EgressPolicy, HostPattern, InvalidHostPattern};
 
 #[cfg(not(target_os = "unknown"))]
 mod http;
@@ -44,6 +48,9 @@ use http_dummy as http;
 #[cfg(target_os = "unknown")]
 mod http_dummy;
 
+mod egress;
+mod quota;
+mod tcp;
 mod timestamp;
 
 fn unavailable_yet<R: Default>(name: &str) -> R {
@@ -57,17 +64,89 @@ fn unavailable_yet<R: Default>(name: &str) -> R {
 
 const LOCAL_DB: &str = "LOCAL (fork-aware) DB";
 
+/// Configuration of the HTTP client shared by the offchain workers.
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+	/// Maximum number of idle connections kept open to a single host.
+	pub max_idle_connections_per_host: usize,
+	/// Time after which an idle connection is closed.
+	pub idle_connection_timeout: Duration,
+	/// Maximum number of requests in flight across all the offchain workers.
+	///
+	/// Further requests wait until one of them completes.
+	pub max_concurrent_requests: usize,
+	/// Maximum time to establish a connection, TLS handshake included.
+	pub connect_timeout: Duration,
+	/// Maximum time to wait for the response headers, or between two chunks of the body.
+	///
+	/// Also bounds each read and write of the raw TCP sockets made without a deadline, as
+	/// `connect_timeout` does for their connection.
+	pub read_timeout: Duration,
+	/// Maximum number of raw TCP sockets an offchain worker execution keeps open at once.
+	pub max_tcp_sockets: usize,
+	/// TLS settings of the connections.
+	pub tls: TlsConfig,
+}
+
+/// TLS settings of the HTTP client, on top of the root certificates of the system.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+	/// Additional trusted root certificates, DER encoded.
+	pub extra_root_certificates: Vec<Vec<u8>>,
+	/// Certificate presented to the servers requesting client authentication.
+	pub client_certificate: Option<ClientCertificate>,
+}
+
+/// Certificate of the HTTP client, with its private key.
+#[derive(Clone)]
+pub struct ClientCertificate {
+	/// Certificate chain, starting with the certificate of the client, DER encoded.
+	pub chain: Vec<Vec<u8>>,
+	/// PKCS#8 or PKCS#1 private key of the certificate, DER encoded.
+	pub key: Vec<u8>,
+}
+
+impl fmt::Debug for ClientCertificate {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ClientCertificate")
+			.field("chain", &self.chain.len())
+			.finish()
+	}
+}
+
+impl Default for HttpClientConfig {
+	fn default() -> Self {
+		HttpClientConfig {
+			max_idle_connections_per_host: 8,
+			idle_connection_timeout: Duration::from_secs(90),
+			max_concurrent_requests: 64,
+			connect_timeout: Duration::from_secs(10),
+			read_timeout: Duration::from_secs(30),
+			max_tcp_sockets: 16,
+			tls: Default::default(),
+		}
+	}
+}
+
 /// Offchain DB reference.
 #[derive(Debug, Clone)]
 pub struct Db<Storage> {
 	/// Persistent storage database.
 	persistent: Storage,
+	/// Quota enforced on the writes to the persistent storage, if any.
+	quota: Option<quota::QuotaTracker>,
 }
 
 impl<Storage: OffchainStorage> Db<Storage> {
 	/// Create new instance of Offchain DB.
 	pub fn new(persistent: Storage) -> Self {
-		Self { persistent }
+		Self { persistent, quota: None }
+	}
+
+	/// Enforce the given quota on the writes to the persistent storage.
+	pub fn with_quota(mut self, quota: StorageQuota) -> Self {
+		self.quota = Some(quota::QuotaTracker::new(quota));
+		self
 	}
 
 	/// Create new instance of Offchain DB, backed by given backend.
@@ -78,9 +157,28 @@ impl<Storage: OffchainStorage> Db<Storage> {
 		Block: sp_runtime::traits::Block,
 		Storage: 'static,
 	{
-		sc_client_api::Backend::offchain_storage(backend).map(|db|
-			Box::new(Self::new(db)) as _
-		)
+		Self::factory_from_backend_with_quota(backend, None)
+	}
+
+	/// Create new instance of Offchain DB, backed by given backend and enforcing the given quota
+	/// on the persistent storage.
+	pub fn factory_from_backend_with_quota<Backend, Block>(
+		backend: &Backend,
+		quota: Option<StorageQuota>,
+	) -> Option<
+		Box<dyn sc_client_api::execution_extensions::DbExternalitiesFactory>
+	> where
+		Backend: sc_client_api::Backend<Block, OffchainStorage = Storage>,
+		Block: sp_runtime::traits::Block,
+		Storage: 'static,
+	{
+		sc_client_api::Backend::offchain_storage(backend).map(|db| {
+			let db = Self::new(db);
+			Box::new(match quota {
+				Some(quota) => db.with_quota(quota),
+				None => db,
+			}) as _
+		})
 	}
 }
 
@@ -91,7 +189,12 @@ impl<Storage: OffchainStorage> offchain::DbExternalities for Db<Storage> {
 			"{:?}: Write: {:?} <= {:?}", kind, hex::encode(key), hex::encode(value)
 		);
 		match kind {
-			StorageKind::PERSISTENT => self.persistent.set(STORAGE_PREFIX, key, value),
+			StorageKind::PERSISTENT => match &self.quota {
+				Some(quota) => {
+					quota.set(&mut self.persistent, STORAGE_PREFIX, key, value);
+				},
+				None => self.persistent.set(STORAGE_PREFIX, key, value),
+			},
 			StorageKind::LOCAL => unavailable_yet(LOCAL_DB),
 		}
 	}
@@ -102,7 +205,10 @@ impl<Storage: OffchainStorage> offchain::DbExternalities for Db<Storage> {
 			"{:?}: Clear: {:?}", kind, hex::encode(key)
 		);
 		match kind {
-			StorageKind::PERSISTENT => self.persistent.remove(STORAGE_PREFIX, key),
+			StorageKind::PERSISTENT => match &self.quota {
+				Some(quota) => quota.remove(&mut self.persistent, STORAGE_PREFIX, key),
+				None => self.persistent.remove(STORAGE_PREFIX, key),
+			},
 			StorageKind::LOCAL => unavailable_yet(LOCAL_DB),
 		}
 	}
@@ -123,8 +229,15 @@ impl<Storage: OffchainStorage> offchain::DbExternalities for Db<Storage> {
 			old_value.as_ref().map(hex::encode),
 		);
 		match kind {
-			StorageKind::PERSISTENT => {
-				self.persistent.compare_and_set(STORAGE_PREFIX, key, old_value, new_value)
+			StorageKind::PERSISTENT => match &self.quota {
+				Some(quota) => quota.compare_and_set(
+					&mut self.persistent,
+					STORAGE_PREFIX,
+					key,
+					old_value,
+					new_value,
+				),
+				None => self.persistent.compare_and_set(STORAGE_PREFIX, key, old_value, new_value),
 			},
 			StorageKind::LOCAL => unavailable_yet(LOCAL_DB),
 		}
@@ -144,6 +257,14 @@ impl<Storage: OffchainStorage> offchain::DbExternalities for Db<Storage> {
 		);
 		result
 	}
+
+	fn node_storage_set(&mut self, key: &[u8], value: &[u8]) {
+		self.persistent.set(offchain::NODE_STORAGE_PREFIX, key, value)
+	}
+
+	fn node_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		self.persistent.get(offchain::NODE_STORAGE_PREFIX, key)
+	}
 }
 
 /// Asynchronous offchain API.
@@ -157,6 +278,8 @@ pub(crate) struct Api {
 	is_validator: bool,
 	/// Everything HTTP-related is handled by a different struct.
 	http: http::HttpApi,
+	/// Raw TCP sockets.
+	tcp: tcp::TcpApi,
 }
 
 impl offchain::Externalities for Api {
@@ -237,6 +360,36 @@ impl offchain::Externalities for Api {
 		self.http.response_read_body(request_id, buffer, deadline)
 	}
 
+	fn tcp_connect(
+		&mut self,
+		address: &str,
+		deadline: Option<Timestamp>
+	) -> Result<TcpSocketId, TcpError> {
+		self.tcp.connect(address, deadline)
+	}
+
+	fn tcp_write(
+		&mut self,
+		socket_id: TcpSocketId,
+		data: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), TcpError> {
+		self.tcp.write(socket_id, data, deadline)
+	}
+
+	fn tcp_read(
+		&mut self,
+		socket_id: TcpSocketId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, TcpError> {
+		self.tcp.read(socket_id, buffer, deadline)
+	}
+
+	fn tcp_close(&mut self, socket_id: TcpSocketId) {
+		self.tcp.close(socket_id)
+	}
+
 	fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
 		let peer_ids: HashSet<PeerId> = nodes.into_iter()
 			.filter_map(|node| PeerId::from_bytes(&node.0).ok())
@@ -327,12 +480,14 @@ impl AsyncApi {
 		is_validator: bool,
 		shared_client: SharedClient,
 	) -> (Api, Self) {
+		let tcp_api = tcp::TcpApi::new(shared_client.egress_policy(), shared_client.config());
 		let (http_api, http_worker) = http::http(shared_client);
 
 		let api = Api {
 			network_provider,
 			is_validator,
 			http: http_api,
+			tcp: tcp_api,
 		};
 
 		let async_api = Self {
@@ -0,0 +1,260 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Restrictions on the hosts offchain workers may reach.
+//!
+//! Domain patterns match the host as written in the request, while IP ranges match the addresses
+//! the host resolves to. Requests are checked when started against what is known from the host
+//! alone, and the resolved addresses are filtered when connecting, so a host name cannot be used
+//! to reach a denied address.
+
+use std::{fmt, net::IpAddr, str::FromStr};
+
+/// Pattern matching the hosts of outgoing requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostPattern {
+	/// A domain name, and optionally all its subdomains.
+	Domain {
+		/// Lowercase domain name, without trailing dot.
+		name: String,
+		/// Whether the subdomains of `name` match as well.
+		include_subdomains: bool,
+	},
+	/// A range of IP addresses.
+	IpRange {
+		/// First address of the range.
+		addr: IpAddr,
+		/// Number of leading bits of the addresses in the range equal to those of `addr`.
+		prefix_len: u8,
+	},
+}
+
+impl HostPattern {
+	/// Returns true if `host` matches this pattern.
+	fn matches(&self, host: &Host) -> bool {
+		match (self, host) {
+			(HostPattern::Domain { name, include_subdomains }, Host::Domain(host)) =>
+				host == name || (*include_subdomains && host.len() > name.len()
+					&& host.ends_with(name.as_str())
+					&& host.as_bytes()[host.len() - name.len() - 1] == b'.'),
+			(HostPattern::IpRange { addr, prefix_len }, Host::Ip(host)) =>
+				match (addr, host) {
+					(IpAddr::V4(addr), IpAddr::V4(host)) => prefix_matches(
+						u32::from(*addr).into(),
+						u32::from(*host).into(),
+						*prefix_len,
+						32,
+					),
+					(IpAddr::V6(addr), IpAddr::V6(host)) =>
+						prefix_matches(u128::from(*addr), u128::from(*host), *prefix_len, 128),
+					_ => false,
+				},
+			_ => false,
+		}
+	}
+}
+
+fn prefix_matches(range: u128, addr: u128, prefix_len: u8, bits: u8) -> bool {
+	let ignored = bits - prefix_len.min(bits);
+	if ignored >= 128 {
+		return true
+	}
+	(range >> ignored) == (addr >> ignored)
+}
+
+/// Error parsing a [`HostPattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidHostPattern(String);
+
+impl fmt::Display for InvalidHostPattern {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"Invalid host pattern `{}`, expected a domain name, `*.` followed by a domain name, \
+			an IP address or an IP range in CIDR notation",
+			self.0,
+		)
+	}
+}
+
+impl std::error::Error for InvalidHostPattern {}
+
+impl FromStr for HostPattern {
+	type Err = InvalidHostPattern;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || InvalidHostPattern(s.to_owned());
+
+		if let Some((addr, prefix_len)) = split_once(s, '/') {
+			let addr = addr.parse::<IpAddr>().map_err(|_| invalid())?;
+			let prefix_len = prefix_len.parse::<u8>().map_err(|_| invalid())?;
+			let max = if addr.is_ipv4() { 32 } else { 128 };
+			if prefix_len > max {
+				return Err(invalid())
+			}
+			return Ok(HostPattern::IpRange { addr, prefix_len })
+		}
+
+		match Host::parse(s.strip_prefix("*.").unwrap_or(s)) {
+			Host::Ip(addr) if !s.starts_with("*.") => Ok(HostPattern::IpRange {
+				addr,
+				prefix_len: if addr.is_ipv4() { 32 } else { 128 },
+			}),
+			Host::Domain(name) if is_domain_name(&name) =>
+				Ok(HostPattern::Domain { name, include_subdomains: s.starts_with("*.") }),
+			_ => Err(invalid()),
+		}
+	}
+}
+
+fn is_domain_name(name: &str) -> bool {
+	!name.is_empty() && !name.contains(|c: char| c == '*' || c == '/' || c == ':')
+}
+
+fn split_once(s: &str, delimiter: char) -> Option<(&str, &str)> {
+	let index = s.find(delimiter)?;
+	Some((&s[..index], &s[index + 1..]))
+}
+
+/// Host of an outgoing request.
+enum Host {
+	Ip(IpAddr),
+	Domain(String),
+}
+
+impl Host {
+	fn parse(host: &str) -> Self {
+		let unbracketed = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+		match unbracketed.parse::<IpAddr>() {
+			Ok(addr) => Host::Ip(addr),
+			Err(_) => Host::Domain(host.trim_end_matches('.').to_ascii_lowercase()),
+		}
+	}
+}
+
+/// Hosts offchain workers may send requests to.
+#[derive(Debug, Clone, Default)]
+pub struct EgressPolicy {
+	/// If set, only the hosts matching one of these patterns may be reached.
+	pub allow: Option<Vec<HostPattern>>,
+	/// Hosts which may never be reached, even if allowed.
+	pub deny: Vec<HostPattern>,
+}
+
+impl EgressPolicy {
+	/// Returns true if requests to `host` may connect to `addr`, one of its addresses.
+	pub fn allows(&self, host: &str, addr: IpAddr) -> bool {
+		let host = Host::parse(host);
+		let addr = Host::Ip(addr);
+		let matches = |pattern: &HostPattern| pattern.matches(&host) || pattern.matches(&addr);
+		if self.deny.iter().any(matches) {
+			return false
+		}
+		match &self.allow {
+			Some(allow) => allow.iter().any(matches),
+			None => true,
+		}
+	}
+
+	/// Returns true if requests to `host` are refused, whatever addresses it resolves to.
+	pub fn refuses(&self, host: &str) -> bool {
+		match Host::parse(host) {
+			Host::Ip(addr) => !self.allows(host, addr),
+			host => self.deny.iter().any(|pattern| pattern.matches(&host)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn patterns(patterns: &[&str]) -> Vec<HostPattern> {
+		patterns.iter().map(|p| p.parse().unwrap()).collect()
+	}
+
+	fn ip(addr: &str) -> IpAddr {
+		addr.parse().unwrap()
+	}
+
+	#[test]
+	fn parses_patterns() {
+		assert_eq!(
+			"*.Example.com".parse(),
+			Ok(HostPattern::Domain { name: "example.com".into(), include_subdomains: true }),
+		);
+		assert_eq!(
+			"10.0.0.0/8".parse(),
+			Ok(HostPattern::IpRange { addr: "10.0.0.0".parse().unwrap(), prefix_len: 8 }),
+		);
+		assert_eq!(
+			"::1".parse(),
+			Ok(HostPattern::IpRange { addr: "::1".parse().unwrap(), prefix_len: 128 }),
+		);
+		assert!("10.0.0.0/33".parse::<HostPattern>().is_err());
+		assert!("*.10.0.0.1/8".parse::<HostPattern>().is_err());
+		assert!("".parse::<HostPattern>().is_err());
+	}
+
+	#[test]
+	fn allow_list_restricts_hosts() {
+		let policy = EgressPolicy {
+			allow: Some(patterns(&["*.oracle.io", "api.example.com", "192.168.0.0/16"])),
+			deny: Vec::new(),
+		};
+
+		assert!(policy.allows("oracle.io", ip("1.2.3.4")));
+		assert!(policy.allows("prices.oracle.io", ip("1.2.3.4")));
+		assert!(!policy.allows("evil-oracle.io", ip("1.2.3.4")));
+		assert!(policy.allows("API.example.com.", ip("1.2.3.4")));
+		assert!(!policy.allows("www.example.com", ip("1.2.3.4")));
+		assert!(policy.allows("192.168.1.1", ip("192.168.1.1")));
+		assert!(policy.allows("intranet", ip("192.168.1.1")));
+		assert!(!policy.allows("10.0.0.1", ip("10.0.0.1")));
+
+		assert!(policy.refuses("10.0.0.1"));
+		assert!(!policy.refuses("192.168.1.1"));
+		assert!(!policy.refuses("www.example.com"));
+	}
+
+	#[test]
+	fn deny_list_takes_precedence() {
+		let policy = EgressPolicy {
+			allow: None,
+			deny: patterns(&["127.0.0.0/8", "::1", "*.internal"]),
+		};
+
+		assert!(!policy.allows("127.0.0.1", ip("127.0.0.1")));
+		assert!(!policy.allows("[::1]", ip("::1")));
+		assert!(!policy.allows("db.internal", ip("10.0.0.1")));
+		assert!(policy.allows("example.com", ip("93.184.216.34")));
+	}
+
+	#[test]
+	fn deny_list_applies_to_resolved_addresses() {
+		let policy = EgressPolicy {
+			allow: Some(patterns(&["*.example.com"])),
+			deny: patterns(&["127.0.0.0/8"]),
+		};
+
+		assert!(!policy.allows("localhost.example.com", ip("127.0.0.1")));
+		assert!(policy.allows("localhost.example.com", ip("93.184.216.34")));
+		assert!(!policy.refuses("localhost.example.com"));
+		assert!(policy.refuses("127.0.0.1"));
+	}
+}
@@ -26,26 +26,351 @@
 //! The reason for this design is driven by the fact that HTTP requests should continue running
 //! (i.e.: the socket should continue being processed) in the background even if the runtime isn't
 //! actively calling any function.
+//!
+//! All the workers of a node share a single [`SharedClient`], which keeps a pool of connections
+//! per host and bounds the number of requests in flight across all of them. Requests dispatched
+//! past that bound wait in their worker until a slot is released.
 
-use crate::api::timestamp;
+use crate::api::{timestamp, ClientCertificate, EgressPolicy, HttpClientConfig, TlsConfig};
 use bytes::buf::ext::{Reader, BufExt};
 use fnv::FnvHashMap;
 use futures::{prelude::*, future, channel::mpsc};
+use futures_timer::Delay;
 use log::error;
+use parking_lot::Mutex;
 use sp_core::offchain::{HttpRequestId, Timestamp, HttpRequestStatus, HttpError};
-use std::{convert::TryFrom, fmt, io::Read as _, pin::Pin, task::{Context, Poll}};
+use std::{
+	collections::VecDeque, convert::TryFrom, fmt, fs, io::{self, Read as _}, mem, net::IpAddr,
+	path::{Path, PathBuf}, pin::Pin, task::{Context, Poll, Waker}, time::Duration,
+};
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender, TracingUnboundedReceiver};
 use std::sync::Arc;
-use hyper::{Client as HyperClient, Body, client};
+use hyper::{Client as HyperClient, Body, Uri, client, service::Service};
+use hyper::client::connect::dns::{GaiResolver, Name};
 use hyper_rustls::HttpsConnector;
 
+type Connector = TimeoutConnector<HttpsConnector<client::HttpConnector<EgressResolver>>>;
+
+/// Build the HTTPS connector of the client, only connecting to the addresses allowed by `egress`.
+///
+/// Invalid certificates are skipped with a warning: they are expected to have been checked when
+/// loaded, see [`TlsConfig::from_pem_files`].
+fn https_connector(
+	tls: &TlsConfig,
+	egress: Arc<EgressPolicy>,
+) -> HttpsConnector<client::HttpConnector<EgressResolver>> {
+	let mut http = client::HttpConnector::new_with_resolver(
+		EgressResolver { inner: GaiResolver::new(), egress },
+	);
+	http.enforce_http(false);
+
+	let mut config = rustls::ClientConfig::new();
+	config.root_store = rustls_native_certs::load_native_certs().unwrap_or_else(|(store, e)| {
+		log::warn!(
+			target: "sc_offchain",
+			"Failed to load the system root certificates: {}", e,
+		);
+		store.unwrap_or_else(rustls::RootCertStore::empty)
+	});
+	for certificate in &tls.extra_root_certificates {
+		if let Err(e) = config.root_store.add(&rustls::Certificate(certificate.clone())) {
+			log::warn!(target: "sc_offchain", "Skipping invalid root certificate: {:?}", e);
+		}
+	}
+	if let Some(client) = &tls.client_certificate {
+		let chain = client.chain.iter().cloned().map(rustls::Certificate).collect();
+		let key = rustls::PrivateKey(client.key.clone());
+		if let Err(e) = config.set_single_client_cert(chain, key) {
+			log::warn!(target: "sc_offchain", "Skipping invalid client certificate: {}", e);
+		}
+	}
+
+	HttpsConnector::from((http, config))
+}
+
+/// Resolver dropping the addresses the egress policy does not allow.
+///
+/// Hosts given as IP addresses are not resolved: they are checked when the request is started.
+#[derive(Clone)]
+struct EgressResolver {
+	inner: GaiResolver,
+	egress: Arc<EgressPolicy>,
+}
+
+impl Service<Name> for EgressResolver {
+	type Response = std::vec::IntoIter<IpAddr>;
+	type Error = io::Error;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, name: Name) -> Self::Future {
+		let host = name.as_str().to_owned();
+		let egress = self.egress.clone();
+		let resolve = self.inner.call(name);
+		Box::pin(async move {
+			let addrs = resolve.await?
+				.filter(|addr| egress.allows(&host, *addr))
+				.collect::<Vec<_>>();
+			if addrs.is_empty() {
+				log::warn!(
+					target: "sc_offchain",
+					"HTTP request to {} refused by the egress policy",
+					host,
+				);
+				return Err(io::Error::new(
+					io::ErrorKind::PermissionDenied,
+					"no address allowed by the egress policy",
+				))
+			}
+			Ok(addrs.into_iter())
+		})
+	}
+}
+
+/// Returns whether `key` is a supported private key for the public key of `certificate`.
+fn key_matches_certificate(certificate: &[u8], key: &[u8]) -> bool {
+	use rustls::SignatureScheme::*;
+	let schemes = [
+		(ED25519, &webpki::ED25519),
+		(ECDSA_NISTP256_SHA256, &webpki::ECDSA_P256_SHA256),
+		(ECDSA_NISTP384_SHA384, &webpki::ECDSA_P384_SHA384),
+		(RSA_PKCS1_SHA256, &webpki::RSA_PKCS1_2048_8192_SHA256),
+	];
+
+	let key = match rustls::sign::any_supported_type(&rustls::PrivateKey(key.to_vec())) {
+		Ok(key) => key,
+		Err(()) => return false,
+	};
+	let signer = match key.choose_scheme(&schemes.iter().map(|(s, _)| *s).collect::<Vec<_>>()) {
+		Some(signer) => signer,
+		None => return false,
+	};
+	let algorithm = match schemes.iter().find(|(scheme, _)| *scheme == signer.get_scheme()) {
+		Some((_, algorithm)) => *algorithm,
+		None => return false,
+	};
+
+	// Check that a signature made with the key is verified with the public key of the certificate.
+	let message = b"offchain worker client certificate";
+	signer.sign(message)
+		.ok()
+		.and_then(|signature| webpki::EndEntityCert::from(certificate)
+			.and_then(|certificate| certificate.verify_signature(algorithm, message, &signature))
+			.ok())
+		.is_some()
+}
+
+impl TlsConfig {
+	/// Load the TLS settings from PEM files: additional trusted root certificates, and
+	/// optionally the certificate chain and private key of the client.
+	///
+	/// Fails if one of the files can't be read or has no valid certificate or key, or if the
+	/// private key of the client does not match its certificate.
+	pub fn from_pem_files(
+		root_certificates: &[PathBuf],
+		client_certificate: Option<(&Path, &Path)>,
+	) -> io::Result<Self> {
+		let invalid = |path: &Path, what: &str| io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("{} does not contain any valid {}", path.display(), what),
+		);
+		let read_certificates = |path: &Path| -> io::Result<Vec<Vec<u8>>> {
+			let pem = fs::read(path)?;
+			match rustls::internal::pemfile::certs(&mut &pem[..]) {
+				Ok(certificates) if !certificates.is_empty() =>
+					Ok(certificates.into_iter().map(|c| c.0).collect()),
+				_ => Err(invalid(path, "PEM certificate")),
+			}
+		};
+
+		let mut extra_root_certificates = Vec::new();
+		for path in root_certificates {
+			for certificate in read_certificates(path)? {
+				rustls::RootCertStore::empty()
+					.add(&rustls::Certificate(certificate.clone()))
+					.map_err(|_| invalid(path, "root certificate"))?;
+				extra_root_certificates.push(certificate);
+			}
+		}
+
+		let client_certificate = match client_certificate {
+			Some((certificate, key)) => {
+				let chain = read_certificates(certificate)?;
+				let pem = fs::read(key)?;
+				let mut keys = rustls::internal::pemfile::pkcs8_private_keys(&mut &pem[..])
+					.unwrap_or_default();
+				if keys.is_empty() {
+					keys = rustls::internal::pemfile::rsa_private_keys(&mut &pem[..])
+						.unwrap_or_default();
+				}
+				let key_path = key;
+				let key = keys.pop().ok_or_else(|| invalid(key_path, "PEM private key"))?.0;
+				if !key_matches_certificate(&chain[0], &key) {
+					return Err(invalid(key_path, "private key for the client certificate"))
+				}
+				Some(ClientCertificate { chain, key })
+			},
+			None => None,
+		};
+
+		Ok(TlsConfig { extra_root_certificates, client_certificate })
+	}
+}
+
 /// Wrapper struct used for keeping the hyper_rustls client running.
+///
+/// Clones share the same connection pool and the same limit on concurrent requests.
 #[derive(Clone)]
-pub struct SharedClient(Arc<HyperClient<HttpsConnector<client::HttpConnector>, Body>>);
+pub struct SharedClient {
+	client: Arc<HyperClient<Connector, Body>>,
+	limiter: Arc<RequestLimiter>,
+	read_timeout: Duration,
+	egress: Arc<EgressPolicy>,
+	config: HttpClientConfig,
+}
 
 impl SharedClient {
+	/// Create a client with the default configuration.
 	pub fn new() -> Self {
-		Self(Arc::new(HyperClient::builder().build(HttpsConnector::new())))
+		Self::with_config(&HttpClientConfig::default())
+	}
+
+	/// Create a client with the given configuration.
+	pub fn with_config(config: &HttpClientConfig) -> Self {
+		let egress = Arc::new(EgressPolicy::default());
+		SharedClient {
+			client: Arc::new(hyper_client(config, egress.clone())),
+			limiter: Arc::new(RequestLimiter::new(config.max_concurrent_requests)),
+			read_timeout: config.read_timeout,
+			egress,
+			config: config.clone(),
+		}
+	}
+
+	/// Only allow the requests to the hosts permitted by the given policy.
+	///
+	/// The limit on concurrent requests is still shared with `self`, but not the connections: the
+	/// policy is enforced on the resolved addresses when connecting.
+	pub fn with_egress_policy(&self, egress: EgressPolicy) -> Self {
+		let egress = Arc::new(egress);
+		SharedClient {
+			client: Arc::new(hyper_client(&self.config, egress.clone())),
+			egress,
+			.. self.clone()
+		}
+	}
+
+	/// Returns the hosts the offchain workers may reach.
+	pub(crate) fn egress_policy(&self) -> Arc<EgressPolicy> {
+		self.egress.clone()
+	}
+
+	/// Returns the configuration of the client.
+	pub(crate) fn config(&self) -> &HttpClientConfig {
+		&self.config
+	}
+}
+
+fn hyper_client(
+	config: &HttpClientConfig,
+	egress: Arc<EgressPolicy>,
+) -> HyperClient<Connector, Body> {
+	let connector = TimeoutConnector {
+		inner: https_connector(&config.tls, egress),
+		timeout: config.connect_timeout,
+	};
+	HyperClient::builder()
+		.pool_max_idle_per_host(config.max_idle_connections_per_host)
+		.pool_idle_timeout(config.idle_connection_timeout)
+		.build(connector)
+}
+
+/// Connector failing the connection attempts that take longer than `timeout`.
+#[derive(Clone)]
+struct TimeoutConnector<C> {
+	inner: C,
+	timeout: Duration,
+}
+
+impl<C> Service<Uri> for TimeoutConnector<C>
+where
+	C: Service<Uri>,
+	C::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+	C::Future: Send + 'static,
+{
+	type Response = C::Response;
+	type Error = Box<dyn std::error::Error + Send + Sync>;
+	type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx).map_err(Into::into)
+	}
+
+	fn call(&mut self, uri: Uri) -> Self::Future {
+		let connect = self.inner.call(uri);
+		let timeout = Delay::new(self.timeout);
+		Box::pin(async move {
+			futures::pin_mut!(connect);
+			match future::select(connect, timeout).await {
+				future::Either::Left((result, _)) => result.map_err(Into::into),
+				future::Either::Right(_) =>
+					Err(io::Error::new(io::ErrorKind::TimedOut, "connection timed out").into()),
+			}
+		})
+	}
+}
+
+/// Bounds the number of requests in flight across all the workers sharing a client.
+struct RequestLimiter {
+	max_in_flight: usize,
+	state: Mutex<LimiterState>,
+}
+
+struct LimiterState {
+	in_flight: usize,
+	/// Workers waiting for a slot, woken up whenever one is released.
+	waiters: Vec<Waker>,
+}
+
+impl RequestLimiter {
+	fn new(max_in_flight: usize) -> Self {
+		RequestLimiter {
+			max_in_flight: max_in_flight.max(1),
+			state: Mutex::new(LimiterState { in_flight: 0, waiters: Vec::new() }),
+		}
+	}
+
+	/// Take a slot, or register the current task to be woken up once one is released.
+	fn try_acquire(self: &Arc<Self>, cx: &mut Context) -> Option<RequestSlot> {
+		let mut state = self.state.lock();
+		if state.in_flight < self.max_in_flight {
+			state.in_flight += 1;
+			return Some(RequestSlot(self.clone()))
+		}
+
+		if !state.waiters.iter().any(|waker| waker.will_wake(cx.waker())) {
+			state.waiters.push(cx.waker().clone());
+		}
+		None
+	}
+}
+
+/// A request in flight. The slot is released when dropped.
+struct RequestSlot(Arc<RequestLimiter>);
+
+impl Drop for RequestSlot {
+	fn drop(&mut self) {
+		let waiters = {
+			let mut state = self.0.state.lock();
+			state.in_flight -= 1;
+			mem::take(&mut state.waiters)
+		};
+		for waker in waiters {
+			waker.wake();
+		}
 	}
 }
 
@@ -61,12 +386,16 @@ pub fn http(shared_client: SharedClient) -> (HttpApi, HttpWorker) {
 		// writing runtime code with hardcoded IDs.
 		next_id: HttpRequestId(rand::random::<u16>() % 2000),
 		requests: FnvHashMap::default(),
+		egress: shared_client.egress,
 	};
 
 	let engine = HttpWorker {
 		to_api,
 		from_api,
-		http_client: shared_client.0,
+		http_client: shared_client.client,
+		limiter: shared_client.limiter,
+		read_timeout: shared_client.read_timeout,
+		queued: VecDeque::new(),
 		requests: Vec::new(),
 	};
 
@@ -87,6 +416,8 @@ pub struct HttpApi {
 	next_id: HttpRequestId,
 	/// List of HTTP requests in preparation or in progress.
 	requests: FnvHashMap<HttpRequestId, HttpApiRequest>,
+	/// Hosts requests may be sent to.
+	egress: Arc<EgressPolicy>,
 }
 
 /// One active request within `HttpApi`.
@@ -101,7 +432,7 @@ enum HttpApiRequest {
 	/// A request has been dispatched but the worker notified us of an error. We report this
 	/// failure to the user as an `IoError` and remove the request from the list as soon as
 	/// possible.
-	Fail(hyper::Error),
+	Fail(WorkerError),
 }
 
 /// A request within `HttpApi` that has received a response.
@@ -119,7 +450,7 @@ struct HttpApiRequestRp {
 	/// Elements extracted from the channel are first put into `current_read_chunk`.
 	/// If the channel produces an error, then that is translated into an `IoError` and the request
 	/// is removed from the list.
-	body: stream::Fuse<mpsc::Receiver<Result<hyper::body::Bytes, hyper::Error>>>,
+	body: stream::Fuse<mpsc::Receiver<Result<hyper::body::Bytes, WorkerError>>>,
 	/// Chunk that has been extracted from the channel and that is currently being read.
 	/// Reading data from the response should read from this field in priority.
 	current_read_chunk: Option<Reader<hyper::body::Bytes>>,
@@ -139,6 +470,16 @@ impl HttpApi {
 		let mut request = hyper::Request::new(body);
 		*request.method_mut() = hyper::Method::from_bytes(method.as_bytes()).map_err(|_| ())?;
 		*request.uri_mut() = hyper::Uri::from_maybe_shared(uri.to_owned()).map_err(|_| ())?;
+		if let Some(host) = request.uri().host() {
+			if self.egress.refuses(host) {
+				log::warn!(
+					target: "sc_offchain",
+					"HTTP request to {} refused by the egress policy",
+					host,
+				);
+				return Err(())
+			}
+		}
 
 		let new_id = self.next_id;
 		debug_assert!(!self.requests.contains_key(&new_id));
@@ -531,6 +872,15 @@ enum ApiToWorker {
 	}
 }
 
+/// Error of a request processed by the worker.
+#[derive(Debug)]
+enum WorkerError {
+	/// The HTTP client failed.
+	Hyper(hyper::Error),
+	/// Nothing was received from the connection for longer than the read timeout.
+	Timeout,
+}
+
 /// Message send from the API to the worker.
 enum WorkerToApi {
 	/// A request has succeeded.
@@ -548,14 +898,14 @@ enum WorkerToApi {
 		/// the next item.
 		/// Can also be used to send an error, in case an error happend on the HTTP socket. After
 		/// an error is sent, the channel will close.
-		body: mpsc::Receiver<Result<hyper::body::Bytes, hyper::Error>>,
+		body: mpsc::Receiver<Result<hyper::body::Bytes, WorkerError>>,
 	},
 	/// A request has failed because of an error. The request is then no longer valid.
 	Fail {
 		/// The ID that was passed to the worker.
 		id: HttpRequestId,
 		/// Error that happened.
-		error: hyper::Error,
+		error: WorkerError,
 	},
 }
 
@@ -566,7 +916,13 @@ pub struct HttpWorker {
 	/// Used to receive messages from the `HttpApi`.
 	from_api: TracingUnboundedReceiver<ApiToWorker>,
 	/// The engine that runs HTTP requests.
-	http_client: Arc<HyperClient<HttpsConnector<client::HttpConnector>, Body>>,
+	http_client: Arc<HyperClient<Connector, Body>>,
+	/// Limit on the requests in flight, shared with the other workers.
+	limiter: Arc<RequestLimiter>,
+	/// Maximum time to wait for the next bytes of a response.
+	read_timeout: Duration,
+	/// Requests received from the `HttpApi` and waiting for a slot in the `limiter`.
+	queued: VecDeque<(HttpRequestId, hyper::Request<hyper::Body>)>,
 	/// HTTP requests that are being worked on by the engine.
 	requests: Vec<(HttpRequestId, HttpWorkerRequest)>,
 }
@@ -574,13 +930,21 @@ pub struct HttpWorker {
 /// HTTP request being processed by the worker.
 enum HttpWorkerRequest {
 	/// Request has been dispatched and is waiting for a response from the Internet.
-	Dispatched(hyper::client::ResponseFuture),
+	Dispatched {
+		future: hyper::client::ResponseFuture,
+		/// Fires if the response doesn't come within the read timeout.
+		timeout: Delay,
+		_slot: RequestSlot,
+	},
 	/// Progressively reading the body of the response and sending it to the channel.
 	ReadBody {
 		/// Body to read `Chunk`s from. Only used if the channel is ready to accept data.
 		body: hyper::Body,
 		/// Channel to the [`HttpApi`] where we send the chunks to.
-		tx: mpsc::Sender<Result<hyper::body::Bytes, hyper::Error>>,
+		tx: mpsc::Sender<Result<hyper::body::Bytes, WorkerError>>,
+		/// Fires if no chunk comes within the read timeout.
+		timeout: Delay,
+		_slot: RequestSlot,
 	},
 }
 
@@ -598,15 +962,23 @@ impl Future for HttpWorker {
 		for n in (0..me.requests.len()).rev() {
 			let (id, request) = me.requests.swap_remove(n);
 			match request {
-				HttpWorkerRequest::Dispatched(mut future) => {
+				HttpWorkerRequest::Dispatched { mut future, mut timeout, _slot } => {
 					// Check for an HTTP response from the Internet.
 					let response = match Future::poll(Pin::new(&mut future), cx) {
 						Poll::Pending => {
-							me.requests.push((id, HttpWorkerRequest::Dispatched(future)));
+							if let Poll::Ready(()) = Future::poll(Pin::new(&mut timeout), cx) {
+								let error = WorkerError::Timeout;
+								let _ = me.to_api.unbounded_send(WorkerToApi::Fail { id, error });
+								continue;		// don't insert the request back
+							}
+							me.requests.push((id, HttpWorkerRequest::Dispatched {
+								future, timeout, _slot,
+							}));
 							continue
 						},
 						Poll::Ready(Ok(response)) => response,
 						Poll::Ready(Err(error)) => {
+							let error = WorkerError::Hyper(error);
 							let _ = me.to_api.unbounded_send(WorkerToApi::Fail { id, error });
 							continue;		// don't insert the request back
 						}
@@ -624,19 +996,26 @@ impl Future for HttpWorker {
 						body: body_rx,
 					});
 
-					me.requests.push((id, HttpWorkerRequest::ReadBody { body, tx: body_tx }));
+					timeout.reset(me.read_timeout);
+					me.requests.push((id, HttpWorkerRequest::ReadBody {
+						body, tx: body_tx, timeout, _slot,
+					}));
 					cx.waker().wake_by_ref();	// reschedule in order to poll the new future
 					continue
 				}
 
-				HttpWorkerRequest::ReadBody { mut body, mut tx } => {
+				HttpWorkerRequest::ReadBody { mut body, mut tx, mut timeout, _slot } => {
 					// Before reading from the HTTP response, check that `tx` is ready to accept
 					// a new chunk.
 					match tx.poll_ready(cx) {
 						Poll::Ready(Ok(())) => {}
 						Poll::Ready(Err(_)) => continue,  // don't insert the request back
 						Poll::Pending => {
-							me.requests.push((id, HttpWorkerRequest::ReadBody { body, tx }));
+							// The API isn't reading; this doesn't count against the read timeout.
+							timeout.reset(me.read_timeout);
+							me.requests.push((id, HttpWorkerRequest::ReadBody {
+								body, tx, timeout, _slot,
+							}));
 							continue
 						}
 					}
@@ -645,16 +1024,25 @@ impl Future for HttpWorker {
 					match Stream::poll_next(Pin::new(&mut body), cx) {
 						Poll::Ready(Some(Ok(chunk))) => {
 							let _ = tx.start_send(Ok(chunk));
-							me.requests.push((id, HttpWorkerRequest::ReadBody { body, tx }));
+							timeout.reset(me.read_timeout);
+							me.requests.push((id, HttpWorkerRequest::ReadBody {
+								body, tx, timeout, _slot,
+							}));
 							cx.waker().wake_by_ref();	// reschedule in order to continue reading
 						}
 						Poll::Ready(Some(Err(err))) => {
-							let _ = tx.start_send(Err(err));
+							let _ = tx.start_send(Err(WorkerError::Hyper(err)));
 							// don't insert the request back
 						},
 						Poll::Ready(None) => {}		// EOF; don't insert the request back
 						Poll::Pending => {
-							me.requests.push((id, HttpWorkerRequest::ReadBody { body, tx }));
+							if let Poll::Ready(()) = Future::poll(Pin::new(&mut timeout), cx) {
+								let _ = tx.start_send(Err(WorkerError::Timeout));
+								continue;		// don't insert the request back
+							}
+							me.requests.push((id, HttpWorkerRequest::ReadBody {
+								body, tx, timeout, _slot,
+							}));
 						},
 					}
 				}
@@ -662,17 +1050,32 @@ impl Future for HttpWorker {
 		}
 
 		// Check for messages coming from the [`HttpApi`].
-		match Stream::poll_next(Pin::new(&mut me.from_api), cx) {
-			Poll::Pending => {},
-			Poll::Ready(None) => return Poll::Ready(()),	// stops the worker
-			Poll::Ready(Some(ApiToWorker::Dispatch { id, request })) => {
-				let future = me.http_client.request(request);
-				debug_assert!(me.requests.iter().all(|(i, _)| *i != id));
-				me.requests.push((id, HttpWorkerRequest::Dispatched(future)));
-				cx.waker().wake_by_ref();	// reschedule the task to poll the request
+		loop {
+			match Stream::poll_next(Pin::new(&mut me.from_api), cx) {
+				Poll::Pending => break,
+				Poll::Ready(None) => return Poll::Ready(()),	// stops the worker
+				Poll::Ready(Some(ApiToWorker::Dispatch { id, request })) => {
+					debug_assert!(me.requests.iter().all(|(i, _)| *i != id));
+					me.queued.push_back((id, request));
+				}
 			}
 		}
 
+		// Dispatch the queued requests for which a slot is available.
+		while !me.queued.is_empty() {
+			let slot = match me.limiter.try_acquire(cx) {
+				Some(slot) => slot,
+				None => break,	// woken up once a slot is released
+			};
+			let (id, request) = me.queued.pop_front().expect("queue is not empty; qed");
+			let future = me.http_client.request(request);
+			let timeout = Delay::new(me.read_timeout);
+			me.requests.push((id, HttpWorkerRequest::Dispatched {
+				future, timeout, _slot: slot,
+			}));
+			cx.waker().wake_by_ref();	// reschedule the task to poll the request
+		}
+
 		Poll::Pending
 	}
 }
@@ -688,7 +1091,7 @@ impl fmt::Debug for HttpWorker {
 impl fmt::Debug for HttpWorkerRequest {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
-			HttpWorkerRequest::Dispatched(_) =>
+			HttpWorkerRequest::Dispatched { .. } =>
 				f.debug_tuple("HttpWorkerRequest::Dispatched").finish(),
 			HttpWorkerRequest::ReadBody { .. } =>
 				f.debug_tuple("HttpWorkerRequest::Response").finish(),
@@ -700,7 +1103,9 @@ impl fmt::Debug for HttpWorkerRequest {
 mod tests {
 	use core::convert::Infallible;
 	use crate::api::timestamp;
-	use super::{http, SharedClient};
+	use super::{http, SharedClient, RequestLimiter};
+	use crate::api::{HttpClientConfig, TlsConfig};
+	use std::{sync::Arc, task::Context};
 	use sp_core::offchain::{HttpError, HttpRequestId, HttpRequestStatus, Duration};
 	use futures::future;
 	use lazy_static::lazy_static;
@@ -714,9 +1119,11 @@ mod tests {
 	// Returns an `HttpApi` whose worker is ran in the background, and a `SocketAddr` to an HTTP
 	// server that runs in the background as well.
 	macro_rules! build_api_server {
-		() => {{
-			let hyper_client = SHARED_CLIENT.clone();
-			let (api, worker) = http(hyper_client.clone());
+		() => {
+			build_api_server!(SHARED_CLIENT.clone())
+		};
+		($client:expr) => {{
+			let (api, worker) = http($client);
 
 			let (addr_tx, addr_rx) = std::sync::mpsc::channel();
 			std::thread::spawn(move || {
@@ -963,6 +1370,216 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn request_start_refused_by_egress_policy() {
+		let client = SHARED_CLIENT.with_egress_policy(crate::api::EgressPolicy {
+			allow: None,
+			deny: vec!["127.0.0.0/8".parse().unwrap()],
+		});
+		let (mut api, _worker) = http(client);
+
+		assert!(api.request_start("GET", "http://127.0.0.1:9933").is_err());
+	}
+
+	#[test]
+	fn egress_policy_applies_to_resolved_addresses() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+		let client = SHARED_CLIENT.with_egress_policy(crate::api::EgressPolicy {
+			allow: None,
+			deny: vec!["127.0.0.0/8".parse().unwrap(), "::1".parse().unwrap()],
+		});
+		let (mut api, addr) = build_api_server!(client);
+
+		// The host name is only refused once resolved to a loopback address.
+		let id = api.request_start("GET", &format!("http://localhost:{}", addr.port())).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+
+		match api.response_wait(&[id], Some(deadline))[0] {
+			HttpRequestStatus::IoError => {},
+			v => panic!("Connecting to a denied address should fail, got {:?}", v),
+		}
+	}
+
+	#[test]
+	fn limiter_bounds_requests_in_flight() {
+		let limiter = Arc::new(RequestLimiter::new(2));
+		let waker = futures::task::noop_waker();
+		let mut cx = Context::from_waker(&waker);
+
+		let first = limiter.try_acquire(&mut cx).unwrap();
+		let _second = limiter.try_acquire(&mut cx).unwrap();
+		assert!(limiter.try_acquire(&mut cx).is_none());
+
+		drop(first);
+		assert!(limiter.try_acquire(&mut cx).is_some());
+	}
+
+	#[test]
+	fn invalid_pem_files_are_rejected() {
+		let dir = tempfile::tempdir().unwrap();
+		let not_pem = dir.path().join("not-pem");
+		std::fs::write(&not_pem, "not a certificate").unwrap();
+
+		assert!(TlsConfig::from_pem_files(&[], None).is_ok());
+		assert!(TlsConfig::from_pem_files(&[dir.path().join("missing")], None).is_err());
+		assert!(TlsConfig::from_pem_files(&[not_pem.clone()], None).is_err());
+		assert!(TlsConfig::from_pem_files(&[], Some((&not_pem, &not_pem))).is_err());
+	}
+
+	/// Returns the path of a file of the test certificate authority, see `res/tls`.
+	fn tls_file(name: &str) -> std::path::PathBuf {
+		std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("res/tls").join(name)
+	}
+
+	#[test]
+	fn client_key_must_match_its_certificate() {
+		let certificate = tls_file("localhost.pem");
+		assert!(TlsConfig::from_pem_files(
+			&[],
+			Some((&certificate, &tls_file("localhost.key"))),
+		).is_ok());
+		assert!(TlsConfig::from_pem_files(
+			&[],
+			Some((&certificate, &tls_file("root.key"))),
+		).is_err());
+	}
+
+	/// Spawn an HTTPS server for `localhost`, with a certificate signed by `res/tls/root.pem`,
+	/// answering all the requests with an empty response. Returns its port.
+	fn spawn_https_server() -> u16 {
+		use std::io::{Read, Write};
+
+		let tls = TlsConfig::from_pem_files(
+			&[],
+			Some((&tls_file("localhost.pem"), &tls_file("localhost.key"))),
+		).unwrap().client_certificate.unwrap();
+		let mut config = rustls::ServerConfig::new(rustls::NoClientAuth::new());
+		config.set_single_cert(
+			tls.chain.into_iter().map(rustls::Certificate).collect(),
+			rustls::PrivateKey(tls.key),
+		).unwrap();
+		let config = Arc::new(config);
+
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		std::thread::spawn(move || for socket in listener.incoming() {
+			let session = rustls::ServerSession::new(&config);
+			let mut stream = rustls::StreamOwned::new(session, socket.unwrap());
+
+			// Read the whole request, whose body is empty.
+			let mut request = Vec::new();
+			let complete = |request: &[u8]| {
+				let request = String::from_utf8_lossy(request).to_ascii_lowercase();
+				request.ends_with("\r\n\r\n") && (!request.contains("transfer-encoding: chunked")
+					|| request.ends_with("\r\n0\r\n\r\n"))
+			};
+			let mut byte = [0];
+			while !complete(&request) {
+				match stream.read(&mut byte) {
+					Ok(1) => request.push(byte[0]),
+					_ => break,
+				}
+			}
+			if complete(&request) {
+				let _ = stream.write_all(
+					b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+				);
+				stream.sess.send_close_notify();
+				let _ = stream.flush();
+			}
+		});
+		port
+	}
+
+	#[test]
+	fn https_with_extra_root_certificates_works() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+		let client = SharedClient::with_config(&HttpClientConfig {
+			tls: TlsConfig::from_pem_files(&[tls_file("root.pem")], None).unwrap(),
+			.. Default::default()
+		});
+		let (mut api, worker) = http(client);
+		std::thread::spawn(move || tokio::runtime::Runtime::new().unwrap().block_on(worker));
+		let port = spawn_https_server();
+
+		let id = api.request_start("GET", &format!("https://localhost:{}", port)).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+		match api.response_wait(&[id], Some(deadline))[0] {
+			HttpRequestStatus::Finished(200) => {},
+			v => panic!("HTTPS request to localhost failed: {:?}", v)
+		}
+	}
+
+	#[test]
+	fn https_without_the_root_certificate_fails() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+		let (mut api, worker) = http(SharedClient::new());
+		std::thread::spawn(move || tokio::runtime::Runtime::new().unwrap().block_on(worker));
+		let port = spawn_https_server();
+
+		let id = api.request_start("GET", &format!("https://localhost:{}", port)).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+		match api.response_wait(&[id], Some(deadline))[0] {
+			HttpRequestStatus::IoError => {},
+			v => panic!("Expected the untrusted certificate to be rejected: {:?}", v)
+		}
+	}
+
+	#[test]
+	fn client_with_extra_root_certificates_works() {
+		let client = SharedClient::with_config(&HttpClientConfig {
+			tls: TlsConfig {
+				// Invalid certificates are skipped.
+				extra_root_certificates: vec![vec![1, 2, 3]],
+				client_certificate: None,
+			},
+			.. Default::default()
+		});
+		let (mut api, addr) = build_api_server!(client);
+		let id = api.request_start("GET", &format!("http://{}", addr)).unwrap();
+		api.request_write_body(id, &[], None).unwrap();
+		match api.response_wait(&[id], None)[0] {
+			HttpRequestStatus::Finished(200) => {},
+			v => panic!("Connecting to localhost failed: {:?}", v)
+		}
+	}
+
+	#[test]
+	fn read_timeout_fails_request() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+		let client = SharedClient::with_config(&HttpClientConfig {
+			read_timeout: std::time::Duration::from_millis(100),
+			.. Default::default()
+		});
+		let (mut api, worker) = http(client);
+
+		// A server that never answers in time.
+		let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+		std::thread::spawn(move || {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			let worker = rt.spawn(worker);
+			let server = rt.spawn(async move {
+				let server = hyper::Server::bind(&"127.0.0.1:0".parse().unwrap())
+					.serve(hyper::service::make_service_fn(|_| { async move {
+						Ok::<_, Infallible>(hyper::service::service_fn(move |_req| async move {
+							futures_timer::Delay::new(std::time::Duration::from_secs(5)).await;
+							Ok::<_, Infallible>(hyper::Response::new(hyper::Body::empty()))
+						}))
+					}}));
+				let _ = addr_tx.send(server.local_addr());
+				server.await.map_err(drop)
+			});
+			let _ = rt.block_on(future::join(worker, server));
+		});
+		let addr = addr_rx.recv().unwrap();
+
+		let id = api.request_start("GET", &format!("http://{}", addr)).unwrap();
+		match api.response_wait(&[id], Some(deadline))[0] {
+			HttpRequestStatus::IoError => {},
+			v => panic!("Expected the request to time out: {:?}", v)
+		}
+	}
+
 	#[test]
 	fn fuzzing() {
 		// Uses the API in random ways to try to trigger panics.
@@ -18,6 +18,7 @@
 
 //! Contains the same API as the `http` module, except that everything returns an error.
 
+use crate::api::{EgressPolicy, HttpClientConfig};
 use sp_core::offchain::{HttpRequestId, Timestamp, HttpRequestStatus, HttpError};
 use std::{future::Future, pin::Pin, task::Context, task::Poll};
 
@@ -29,6 +30,18 @@ impl SharedClient {
 	pub fn new() -> Self {
 		Self
 	}
+
+	pub fn with_config(_: &HttpClientConfig) -> Self {
+		Self
+	}
+
+	pub fn with_egress_policy(&self, _: EgressPolicy) -> Self {
+		Self
+	}
+
+	pub fn egress_policy(&self) -> std::sync::Arc<EgressPolicy> {
+		Default::default()
+	}
 }
 
 /// Creates a pair of [`HttpApi`] and [`HttpWorker`].
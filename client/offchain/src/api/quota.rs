@@ -0,0 +1,389 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Quotas on the persistent offchain storage.
+//!
+//! The storage can't be iterated, so the accounting is kept next to the data, under
+//! [`QUOTA_PREFIX`]. Each group of keys sharing a quota has a usage record, every tracked key
+//! remembers its size and the sequence number of its last write, and a log maps sequence numbers
+//! back to keys. Rewriting a key leaves its previous log entry stale; stale entries are dropped
+//! when the eviction reaches them.
+//!
+//! Keys written before the quota was enabled, or by block import through the offchain indexing
+//! API, are not accounted for.
+//!
+//! A write and its accounting are applied to the storage at once, see
+//! [`OffchainStorage::apply_batch`].
+
+use std::sync::Arc;
+
+use codec::{Encode, Decode};
+use parking_lot::Mutex;
+use sp_core::offchain::OffchainStorage;
+
+/// Prefix under which the quota accounting is stored.
+///
+/// It must not start with [`sp_core::offchain::STORAGE_PREFIX`], otherwise offchain workers
+/// could overwrite the accounting through their own keys.
+pub const QUOTA_PREFIX: &[u8] = b"quota";
+
+/// What to do with a write that doesn't fit in its quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruningPolicy {
+	/// Evict the least recently written keys of the quota until the write fits.
+	Lru,
+	/// Refuse the write.
+	Reject,
+}
+
+/// Which keys share a quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaScope {
+	/// All the keys share a single quota.
+	Global,
+	/// Keys starting with the same `len` bytes share a quota, each prefix getting its own.
+	PerPrefix {
+		/// Length of the prefix.
+		len: usize,
+	},
+}
+
+/// Limits on the persistent offchain storage written by offchain workers.
+#[derive(Debug, Clone)]
+pub struct StorageQuota {
+	/// Maximum size of the keys and values, in bytes.
+	pub max_bytes: Option<u64>,
+	/// Maximum number of keys.
+	pub max_keys: Option<u64>,
+	/// Which keys share a quota.
+	pub scope: QuotaScope,
+	/// What to do with a write that doesn't fit.
+	pub policy: PruningPolicy,
+}
+
+/// Usage of a single quota.
+#[derive(Debug, Default, Encode, Decode)]
+struct Usage {
+	bytes: u64,
+	keys: u64,
+	/// Sequence number of the next write.
+	next_seq: u64,
+	/// Sequence number of the oldest log entry which may not have been dropped yet.
+	oldest_seq: u64,
+}
+
+impl Usage {
+	/// Release the share of the quota of a key.
+	///
+	/// Saturates, as the accounting may not match the storage if it was modified by other means.
+	fn release(&mut self, meta: &KeyMeta) {
+		self.bytes = self.bytes.saturating_sub(meta.size);
+		self.keys = self.keys.saturating_sub(1);
+	}
+}
+
+/// Size and sequence number of the last write of a key.
+#[derive(Encode, Decode)]
+struct KeyMeta {
+	seq: u64,
+	size: u64,
+}
+
+/// Changes to the storage, applied at once with [`OffchainStorage::apply_batch`].
+#[derive(Default)]
+struct Changes(Vec<(Vec<u8>, Vec<u8>, Option<Vec<u8>>)>);
+
+impl Changes {
+	fn set(&mut self, prefix: &[u8], key: &[u8], value: &[u8]) {
+		self.0.push((prefix.to_vec(), key.to_vec(), Some(value.to_vec())));
+	}
+
+	fn remove(&mut self, prefix: &[u8], key: &[u8]) {
+		self.0.push((prefix.to_vec(), key.to_vec(), None));
+	}
+
+	fn apply<S: OffchainStorage>(self, storage: &mut S) {
+		storage.apply_batch(
+			self.0.iter().map(|(prefix, key, value)| (&prefix[..], &key[..], value.as_deref()))
+		)
+	}
+}
+
+/// Enforces a [`StorageQuota`] on the writes to an offchain storage.
+///
+/// Clones share the same lock, which serializes all the accounted writes.
+#[derive(Debug, Clone)]
+pub(crate) struct QuotaTracker {
+	quota: StorageQuota,
+	lock: Arc<Mutex<()>>,
+}
+
+impl QuotaTracker {
+	pub fn new(quota: StorageQuota) -> Self {
+		QuotaTracker { quota, lock: Default::default() }
+	}
+
+	/// Write `value` under `prefix` and `key`, pruning as configured.
+	///
+	/// Returns false if the write was refused.
+	pub fn set<S: OffchainStorage>(
+		&self,
+		storage: &mut S,
+		prefix: &[u8],
+		key: &[u8],
+		value: &[u8],
+	) -> bool {
+		let _guard = self.lock.lock();
+		self.set_locked(storage, prefix, key, value)
+	}
+
+	/// Remove `key` under `prefix`, releasing its share of the quota.
+	pub fn remove<S: OffchainStorage>(&self, storage: &mut S, prefix: &[u8], key: &[u8]) {
+		let _guard = self.lock.lock();
+		let group = self.group(key);
+		let mut usage = load_usage(storage, group);
+		let mut changes = Changes::default();
+		if let Some(meta) = load_meta(storage, key) {
+			usage.release(&meta);
+			changes.remove(QUOTA_PREFIX, &meta_key(key));
+			changes.set(QUOTA_PREFIX, &usage_key(group), &usage.encode());
+		}
+		changes.remove(prefix, key);
+		changes.apply(storage);
+	}
+
+	/// Write `new_value` if the current value is `old_value`, pruning as configured.
+	///
+	/// Returns false if the value didn't match or the write was refused.
+	pub fn compare_and_set<S: OffchainStorage>(
+		&self,
+		storage: &mut S,
+		prefix: &[u8],
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool {
+		let _guard = self.lock.lock();
+		if storage.get(prefix, key).as_ref().map(|v| &v[..]) != old_value {
+			return false
+		}
+		self.set_locked(storage, prefix, key, new_value)
+	}
+
+	fn set_locked<S: OffchainStorage>(
+		&self,
+		storage: &mut S,
+		prefix: &[u8],
+		key: &[u8],
+		value: &[u8],
+	) -> bool {
+		let size = (key.len() + value.len()) as u64;
+		if !self.fits(&Usage::default(), size) {
+			log::warn!(
+				target: "sc_offchain",
+				"Offchain storage value larger than its quota, refusing to write {:?}",
+				hex::encode(key),
+			);
+			return false
+		}
+
+		let group = self.group(key);
+		let mut usage = load_usage(storage, group);
+		if let Some(meta) = load_meta(storage, key) {
+			usage.release(&meta);
+		}
+
+		let mut changes = Changes::default();
+		if !self.fits(&usage, size) {
+			match self.quota.policy {
+				PruningPolicy::Lru => {
+					self.evict(storage, &mut changes, prefix, group, key, &mut usage, size)
+				},
+				PruningPolicy::Reject => {
+					log::warn!(
+						target: "sc_offchain",
+						"Offchain storage quota exceeded, refusing to write {:?}",
+						hex::encode(key),
+					);
+					return false
+				},
+			}
+		}
+
+		let seq = usage.next_seq;
+		changes.set(prefix, key, value);
+		changes.set(QUOTA_PREFIX, &meta_key(key), &KeyMeta { seq, size }.encode());
+		changes.set(QUOTA_PREFIX, &log_key(group, seq), key);
+		usage.next_seq += 1;
+		usage.bytes += size;
+		usage.keys += 1;
+		changes.set(QUOTA_PREFIX, &usage_key(group), &usage.encode());
+		changes.apply(storage);
+		true
+	}
+
+	/// Evict the least recently written keys of `group` until `size` more bytes fit, or no
+	/// other key is left.
+	///
+	/// The evictions are added to `changes`, the storage is only read.
+	fn evict<S: OffchainStorage>(
+		&self,
+		storage: &S,
+		changes: &mut Changes,
+		prefix: &[u8],
+		group: &[u8],
+		key: &[u8],
+		usage: &mut Usage,
+		size: u64,
+	) {
+		while !self.fits(usage, size) && usage.oldest_seq < usage.next_seq {
+			let seq = usage.oldest_seq;
+			usage.oldest_seq += 1;
+
+			let entry = log_key(group, seq);
+			let logged = storage.get(QUOTA_PREFIX, &entry);
+			changes.remove(QUOTA_PREFIX, &entry);
+			let evicted = match logged {
+				Some(evicted) if evicted != key => evicted,
+				_ => continue,
+			};
+			match load_meta(storage, &evicted) {
+				Some(meta) if meta.seq == seq => {
+					log::debug!(
+						target: "sc_offchain",
+						"Offchain storage quota exceeded, evicting {:?}",
+						hex::encode(&evicted),
+					);
+					changes.remove(prefix, &evicted);
+					changes.remove(QUOTA_PREFIX, &meta_key(&evicted));
+					usage.release(&meta);
+				},
+				// Rewritten or removed since.
+				_ => {},
+			}
+		}
+	}
+
+	fn fits(&self, usage: &Usage, size: u64) -> bool {
+		self.quota.max_bytes.map_or(true, |max| usage.bytes + size <= max)
+			&& self.quota.max_keys.map_or(true, |max| usage.keys + 1 <= max)
+	}
+
+	fn group<'a>(&self, key: &'a [u8]) -> &'a [u8] {
+		match self.quota.scope {
+			QuotaScope::Global => &[],
+			QuotaScope::PerPrefix { len } => &key[..len.min(key.len())],
+		}
+	}
+}
+
+fn usage_key(group: &[u8]) -> Vec<u8> {
+	(&b"usage"[..], group).encode()
+}
+
+fn meta_key(key: &[u8]) -> Vec<u8> {
+	(&b"meta"[..], key).encode()
+}
+
+fn log_key(group: &[u8], seq: u64) -> Vec<u8> {
+	(&b"log"[..], group, seq).encode()
+}
+
+fn load_usage<S: OffchainStorage>(storage: &S, group: &[u8]) -> Usage {
+	storage.get(QUOTA_PREFIX, &usage_key(group))
+		.and_then(|encoded| Decode::decode(&mut &encoded[..]).ok())
+		.unwrap_or_default()
+}
+
+fn load_meta<S: OffchainStorage>(storage: &S, key: &[u8]) -> Option<KeyMeta> {
+	storage.get(QUOTA_PREFIX, &meta_key(key))
+		.and_then(|encoded| Decode::decode(&mut &encoded[..]).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::offchain::storage::InMemOffchainStorage;
+
+	const PREFIX: &[u8] = b"storage";
+
+	fn tracker(max_keys: Option<u64>, scope: QuotaScope, policy: PruningPolicy) -> QuotaTracker {
+		QuotaTracker::new(StorageQuota { max_bytes: None, max_keys, scope, policy })
+	}
+
+	#[test]
+	fn lru_evicts_least_recently_written_keys() {
+		let mut storage = InMemOffchainStorage::default();
+		let quota = tracker(Some(2), QuotaScope::Global, PruningPolicy::Lru);
+
+		assert!(quota.set(&mut storage, PREFIX, b"a", b"1"));
+		assert!(quota.set(&mut storage, PREFIX, b"b", b"2"));
+		// rewriting `a` makes `b` the least recently written key
+		assert!(quota.set(&mut storage, PREFIX, b"a", b"3"));
+		assert!(quota.set(&mut storage, PREFIX, b"c", b"4"));
+
+		assert_eq!(storage.get(PREFIX, b"a"), Some(b"3".to_vec()));
+		assert_eq!(storage.get(PREFIX, b"b"), None);
+		assert_eq!(storage.get(PREFIX, b"c"), Some(b"4".to_vec()));
+	}
+
+	#[test]
+	fn reject_keeps_existing_keys() {
+		let mut storage = InMemOffchainStorage::default();
+		let quota = QuotaTracker::new(StorageQuota {
+			max_bytes: Some(4),
+			max_keys: None,
+			scope: QuotaScope::Global,
+			policy: PruningPolicy::Reject,
+		});
+
+		assert!(quota.set(&mut storage, PREFIX, b"a", b"12"));
+		assert!(!quota.set(&mut storage, PREFIX, b"b", b"12"));
+		// removing frees the quota
+		quota.remove(&mut storage, PREFIX, b"a");
+		assert!(quota.set(&mut storage, PREFIX, b"b", b"12"));
+
+		assert_eq!(storage.get(PREFIX, b"a"), None);
+		assert_eq!(storage.get(PREFIX, b"b"), Some(b"12".to_vec()));
+	}
+
+	#[test]
+	fn per_prefix_quotas_are_independent() {
+		let mut storage = InMemOffchainStorage::default();
+		let quota = tracker(Some(1), QuotaScope::PerPrefix { len: 2 }, PruningPolicy::Lru);
+
+		assert!(quota.set(&mut storage, PREFIX, b"x:a", b"1"));
+		assert!(quota.set(&mut storage, PREFIX, b"y:a", b"2"));
+		assert!(quota.set(&mut storage, PREFIX, b"x:b", b"3"));
+
+		assert_eq!(storage.get(PREFIX, b"x:a"), None);
+		assert_eq!(storage.get(PREFIX, b"x:b"), Some(b"3".to_vec()));
+		assert_eq!(storage.get(PREFIX, b"y:a"), Some(b"2".to_vec()));
+	}
+
+	#[test]
+	fn compare_and_set_is_accounted() {
+		let mut storage = InMemOffchainStorage::default();
+		let quota = tracker(Some(1), QuotaScope::Global, PruningPolicy::Reject);
+
+		assert!(quota.compare_and_set(&mut storage, PREFIX, b"a", None, b"1"));
+		assert!(!quota.compare_and_set(&mut storage, PREFIX, b"a", None, b"2"));
+		assert!(quota.compare_and_set(&mut storage, PREFIX, b"a", Some(b"1"), b"2"));
+		assert!(!quota.compare_and_set(&mut storage, PREFIX, b"b", None, b"1"));
+	}
+}
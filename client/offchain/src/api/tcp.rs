@@ -0,0 +1,345 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Raw TCP sockets of the offchain workers.
+//!
+//! Unlike HTTP, sockets are driven synchronously from the thread of the offchain worker: every
+//! call blocks until it completes or its deadline is reached. Calls without a deadline are bounded
+//! by the timeouts of the [`HttpClientConfig`], and fail with `TcpError::IoError` when they expire.
+//! Host names are resolved with the resolver of the system, which isn't bound by the deadline.
+
+use crate::api::{EgressPolicy, HttpClientConfig, timestamp};
+use fnv::FnvHashMap;
+use sp_core::offchain::{TcpError, TcpSocketId, Timestamp};
+use std::{
+	io::{self, Read, Write},
+	net::{TcpStream, ToSocketAddrs},
+	sync::Arc,
+	time::Duration,
+};
+
+/// Raw TCP sockets opened by an offchain worker.
+pub struct TcpApi {
+	/// Hosts the sockets may connect to.
+	egress: Arc<EgressPolicy>,
+	/// Maximum number of open sockets.
+	max_sockets: usize,
+	/// Maximum time to connect without a deadline.
+	connect_timeout: Duration,
+	/// Maximum time of a read or write without a deadline.
+	io_timeout: Duration,
+	/// Identifier of the next socket.
+	next_id: TcpSocketId,
+	/// Open sockets.
+	sockets: FnvHashMap<TcpSocketId, TcpStream>,
+}
+
+impl TcpApi {
+	/// Creates a new `TcpApi`, connecting only to the hosts allowed by `egress`, with the socket
+	/// limit and timeouts of `config`.
+	pub fn new(egress: Arc<EgressPolicy>, config: &HttpClientConfig) -> Self {
+		TcpApi {
+			egress,
+			max_sockets: config.max_tcp_sockets,
+			connect_timeout: config.connect_timeout,
+			io_timeout: config.read_timeout,
+			next_id: TcpSocketId(rand::random::<u16>() % 2000),
+			sockets: FnvHashMap::default(),
+		}
+	}
+
+	/// Mimics the corresponding method in the offchain API.
+	///
+	/// Addresses refused by the egress policy fail with `TcpError::Invalid`, like malformed ones,
+	/// while going past the maximum number of open sockets fails with `TcpError::IoError`.
+	pub fn connect(
+		&mut self,
+		address: &str,
+		deadline: Option<Timestamp>,
+	) -> Result<TcpSocketId, TcpError> {
+		if self.sockets.len() >= self.max_sockets {
+			log::warn!(
+				target: "sc_offchain",
+				"TCP connection to {} refused: {} sockets already open",
+				address,
+				self.max_sockets,
+			);
+			return Err(TcpError::IoError)
+		}
+
+		let host = match address.rfind(':') {
+			Some(index) if index > 0 => &address[..index],
+			_ => return Err(TcpError::Invalid),
+		};
+		if self.egress.refuses(host) {
+			log::warn!(
+				target: "sc_offchain",
+				"TCP connection to {} refused by the egress policy",
+				host,
+			);
+			return Err(TcpError::Invalid)
+		}
+
+		let addresses = address.to_socket_addrs().map_err(|err| match err.kind() {
+			io::ErrorKind::InvalidInput => TcpError::Invalid,
+			_ => TcpError::IoError,
+		})?;
+
+		let mut result = Err(TcpError::Invalid);
+		for addr in addresses {
+			if !self.egress.allows(host, addr.ip()) {
+				log::debug!(
+					target: "sc_offchain",
+					"Skipping address {} of {}, refused by the egress policy",
+					addr,
+					host,
+				);
+				continue
+			}
+			let timeout = remaining(deadline, self.connect_timeout)?;
+			result = TcpStream::connect_timeout(&addr, timeout)
+				.map_err(|err| to_tcp_error(err, deadline));
+			if result.is_ok() {
+				break
+			}
+		}
+		let stream = result?;
+
+		let new_id = self.next_id;
+		debug_assert!(!self.sockets.contains_key(&new_id));
+		match self.next_id.0.checked_add(1) {
+			Some(new_id) => self.next_id.0 = new_id,
+			None => {
+				log::error!(
+					target: "sc_offchain",
+					"Overflow in offchain worker TCP socket ID assignment",
+				);
+				return Err(TcpError::IoError)
+			}
+		};
+		self.sockets.insert(new_id, stream);
+
+		Ok(new_id)
+	}
+
+	/// Mimics the corresponding method in the offchain API.
+	pub fn write(
+		&mut self,
+		socket_id: TcpSocketId,
+		data: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), TcpError> {
+		let stream = self.sockets.get_mut(&socket_id).ok_or(TcpError::Invalid)?;
+		let result = write_all(stream, data, deadline, self.io_timeout);
+		if let Err(TcpError::IoError) = result {
+			self.sockets.remove(&socket_id);
+		}
+		result
+	}
+
+	/// Mimics the corresponding method in the offchain API.
+	pub fn read(
+		&mut self,
+		socket_id: TcpSocketId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<usize, TcpError> {
+		let stream = self.sockets.get_mut(&socket_id).ok_or(TcpError::Invalid)?;
+		if buffer.is_empty() {
+			return Ok(0)
+		}
+
+		let result = read_some(stream, buffer, deadline, self.io_timeout);
+		if let Ok(0) | Err(TcpError::IoError) = result {
+			self.sockets.remove(&socket_id);
+		}
+		result
+	}
+
+	/// Mimics the corresponding method in the offchain API.
+	pub fn close(&mut self, socket_id: TcpSocketId) {
+		self.sockets.remove(&socket_id);
+	}
+}
+
+fn write_all(
+	stream: &mut TcpStream,
+	mut data: &[u8],
+	deadline: Option<Timestamp>,
+	timeout: Duration,
+) -> Result<(), TcpError> {
+	let error = |err| to_tcp_error(err, deadline);
+	while !data.is_empty() {
+		stream.set_write_timeout(Some(remaining(deadline, timeout)?)).map_err(error)?;
+		match stream.write(data) {
+			Ok(0) => return Err(TcpError::IoError),
+			Ok(n) => data = &data[n..],
+			Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+			Err(err) => return Err(error(err)),
+		}
+	}
+	Ok(())
+}
+
+fn read_some(
+	stream: &mut TcpStream,
+	buffer: &mut [u8],
+	deadline: Option<Timestamp>,
+	timeout: Duration,
+) -> Result<usize, TcpError> {
+	let error = |err| to_tcp_error(err, deadline);
+	loop {
+		stream.set_read_timeout(Some(remaining(deadline, timeout)?)).map_err(error)?;
+		match stream.read(buffer) {
+			Ok(n) => return Ok(n),
+			Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+			Err(err) => return Err(error(err)),
+		}
+	}
+}
+
+/// Returns the time left before `deadline`, or an error if it has been reached.
+///
+/// Without a deadline, calls are bounded by `timeout`.
+fn remaining(deadline: Option<Timestamp>, timeout: Duration) -> Result<Duration, TcpError> {
+	match deadline.map(timestamp::timestamp_from_now) {
+		None => Ok(timeout),
+		Some(duration) if duration == Duration::from_secs(0) => Err(TcpError::DeadlineReached),
+		Some(duration) => Ok(duration),
+	}
+}
+
+/// Timeouts are reported as `TcpError::DeadlineReached` if there is a `deadline`, as an I/O error
+/// otherwise.
+fn to_tcp_error(err: io::Error, deadline: Option<Timestamp>) -> TcpError {
+	match err.kind() {
+		io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut if deadline.is_some() =>
+			TcpError::DeadlineReached,
+		_ => TcpError::IoError,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{net::TcpListener, thread};
+
+	fn deadline_in(millis: u64) -> Option<Timestamp> {
+		Some(timestamp::now().add(sp_core::offchain::Duration::from_millis(millis)))
+	}
+
+	#[test]
+	fn echo_through_socket() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+		let server = thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut buffer = [0; 5];
+			stream.read_exact(&mut buffer).unwrap();
+			stream.write_all(&buffer).unwrap();
+		});
+
+		let mut api = TcpApi::new(Default::default(), &Default::default());
+		let id = api.connect(&address, deadline_in(5_000)).unwrap();
+		api.write(id, b"hello", deadline_in(5_000)).unwrap();
+
+		let mut buffer = [0; 16];
+		let mut received = Vec::new();
+		loop {
+			match api.read(id, &mut buffer, deadline_in(5_000)).unwrap() {
+				0 => break,
+				n => received.extend_from_slice(&buffer[..n]),
+			}
+		}
+		assert_eq!(received, b"hello");
+		server.join().unwrap();
+
+		// The socket has been closed by the remote.
+		assert_eq!(api.read(id, &mut buffer, None), Err(TcpError::Invalid));
+	}
+
+	#[test]
+	fn read_reaches_deadline() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+
+		let mut api = TcpApi::new(Default::default(), &Default::default());
+		let id = api.connect(&address, deadline_in(5_000)).unwrap();
+		let _accepted = listener.accept().unwrap();
+
+		let mut buffer = [0; 16];
+		assert_eq!(api.read(id, &mut buffer, deadline_in(50)), Err(TcpError::DeadlineReached));
+		api.close(id);
+		assert_eq!(api.write(id, b"hello", None), Err(TcpError::Invalid));
+	}
+
+	#[test]
+	fn calls_without_deadline_are_bounded() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+
+		let mut api = TcpApi::new(Default::default(), &HttpClientConfig {
+			read_timeout: Duration::from_millis(50),
+			.. Default::default()
+		});
+		let id = api.connect(&address, None).unwrap();
+		let _accepted = listener.accept().unwrap();
+
+		let mut buffer = [0; 16];
+		assert_eq!(api.read(id, &mut buffer, None), Err(TcpError::IoError));
+		// the socket is closed after an I/O error
+		assert_eq!(api.read(id, &mut buffer, None), Err(TcpError::Invalid));
+	}
+
+	#[test]
+	fn open_sockets_are_limited() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+
+		let mut api = TcpApi::new(Default::default(), &HttpClientConfig {
+			max_tcp_sockets: 2,
+			.. Default::default()
+		});
+		let first = api.connect(&address, deadline_in(5_000)).unwrap();
+		api.connect(&address, deadline_in(5_000)).unwrap();
+		assert_eq!(api.connect(&address, deadline_in(5_000)), Err(TcpError::IoError));
+
+		api.close(first);
+		assert!(api.connect(&address, deadline_in(5_000)).is_ok());
+	}
+
+	#[test]
+	fn connect_refused_by_egress_policy() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+
+		let mut api = TcpApi::new(
+			Arc::new(EgressPolicy {
+				allow: None,
+				deny: vec!["127.0.0.0/8".parse().unwrap(), "::1".parse().unwrap()],
+			}),
+			&Default::default(),
+		);
+		assert_eq!(api.connect(&address, None), Err(TcpError::Invalid));
+		assert_eq!(api.connect("no-port", None), Err(TcpError::Invalid));
+
+		// host names are checked against the addresses they resolve to
+		let port = listener.local_addr().unwrap().port();
+		assert_eq!(api.connect(&format!("localhost:{}", port), None), Err(TcpError::Invalid));
+	}
+}
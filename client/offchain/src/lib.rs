@@ -36,8 +36,8 @@
 #![warn(missing_docs)]
 
 use std::{
-	fmt, marker::PhantomData, sync::Arc,
-	collections::HashSet,
+	fmt, marker::PhantomData, sync::{Arc, atomic::{AtomicUsize, Ordering}},
+	collections::{HashSet, VecDeque}, time::Duration,
 };
 
 use parking_lot::Mutex;
@@ -46,13 +46,20 @@ use sp_api::{ApiExt, ProvideRuntimeApi};
 use futures::future::Future;
 use log::{debug, warn};
 use sc_network::{ExHashT, NetworkService, NetworkStateInfo, PeerId};
+use codec::{Encode, Decode};
 use sp_core::{offchain, ExecutionContext, traits::SpawnNamed};
 use sp_runtime::{generic::BlockId, traits::{self, Header}};
-use futures::{prelude::*, future::ready};
+use futures::{prelude::*, future::ready, channel::oneshot};
+use prometheus_endpoint::Registry;
 
 mod api;
+mod metrics;
 
-pub use api::Db as OffchainDb;
+pub use api::{
+	Db as OffchainDb, ClientCertificate, EgressPolicy, HostPattern, HttpClientConfig,
+	InvalidHostPattern, PruningPolicy, QuotaScope, StorageQuota, TlsConfig,
+};
+pub use sp_core::offchain::WorkerFailure;
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
 /// NetworkProvider provides [`OffchainWorkers`] with all necessary hooks into the
@@ -79,24 +86,249 @@ where
 	}
 }
 
+/// Which blocks offchain workers run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+	/// New best blocks, as soon as they are imported.
+	NewBest,
+	/// Finalized blocks, for workers which must never act upon a block that may be reverted.
+	Finalized,
+	/// Both new best blocks and finalized blocks.
+	Both,
+}
+
+impl Default for Trigger {
+	fn default() -> Self {
+		Trigger::NewBest
+	}
+}
+
+/// What to do with a new block when the maximum number of offchain worker executions are
+/// already running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhenBusy {
+	/// Don't run the offchain workers for that block.
+	Skip,
+	/// Run them once one of the running executions completes.
+	Queue,
+}
+
+/// Limits on the offchain worker executions.
+#[derive(Debug, Clone)]
+pub struct ExecutionLimits {
+	/// Maximum number of offchain worker executions running at the same time, at least 1.
+	pub max_concurrent: usize,
+	/// What to do with new blocks once `max_concurrent` executions are running.
+	pub when_busy: WhenBusy,
+	/// Maximum number of executions waiting for a running one to complete, with
+	/// [`WhenBusy::Queue`]. Past it, the oldest waiting execution is dropped.
+	pub max_queued: usize,
+}
+
+impl Default for ExecutionLimits {
+	fn default() -> Self {
+		ExecutionLimits {
+			max_concurrent: num_cpus::get(),
+			when_busy: WhenBusy::Skip,
+			max_queued: 16,
+		}
+	}
+}
+
+/// Retries of the offchain worker executions which fail.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	/// Number of times a failed execution is retried.
+	pub max_retries: u32,
+	/// Delay before the first retry, doubled for each of the following ones.
+	pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		RetryPolicy {
+			max_retries: 0,
+			backoff: Duration::from_secs(1),
+		}
+	}
+}
+
+/// Maximum number of failed executions kept in the failure store.
+const MAX_RECORDED_FAILURES: usize = 128;
+
+/// Records the executions which kept failing after all their retries in the node part of the
+/// persistent offchain storage, under [`offchain::WORKER_FAILURES_KEY`].
+struct FailureStore(Mutex<Box<dyn offchain::DbExternalities>>);
+
+impl FailureStore {
+	fn record(&self, failure: offchain::WorkerFailure) {
+		let mut db = self.0.lock();
+		let mut failures = db.node_storage_get(offchain::WORKER_FAILURES_KEY)
+			.and_then(|encoded| <Vec<offchain::WorkerFailure>>::decode(&mut &encoded[..]).ok())
+			.unwrap_or_default();
+		failures.push(failure);
+		let excess = failures.len().saturating_sub(MAX_RECORDED_FAILURES);
+		failures.drain(..excess);
+		db.node_storage_set(offchain::WORKER_FAILURES_KEY, &failures.encode());
+	}
+}
+
+/// An offchain worker execution.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Threads dedicated to offchain worker executions, with a bounded queue of the executions
+/// waiting for one of them.
+struct WorkerPool {
+	threads: Mutex<ThreadPool>,
+	size: usize,
+	max_queued: usize,
+	state: Mutex<WorkerPoolState>,
+}
+
+#[derive(Default)]
+struct WorkerPoolState {
+	/// Number of threads running executions.
+	running: usize,
+	/// Executions waiting for a thread, oldest first.
+	queued: VecDeque<Job>,
+}
+
+impl WorkerPool {
+	fn new(limits: &ExecutionLimits) -> Self {
+		let size = limits.max_concurrent.max(1);
+		WorkerPool {
+			threads: Mutex::new(threadpool::Builder::new()
+				.num_threads(size)
+				.thread_name("offchain-worker".into())
+				.build()),
+			size,
+			max_queued: limits.max_queued,
+			state: Default::default(),
+		}
+	}
+
+	/// Run the given job on one of the threads, or queue it until one is free.
+	///
+	/// Returns whether the oldest queued job was dropped to stay within `max_queued`.
+	fn execute(self: &Arc<Self>, job: Job) -> bool {
+		let mut state = self.state.lock();
+		if state.running < self.size {
+			state.running += 1;
+			drop(state);
+			let pool = self.clone();
+			self.threads.lock().execute(move || pool.run(job));
+			return false
+		}
+
+		state.queued.push_back(job);
+		state.queued.len() > self.max_queued && state.queued.pop_front().is_some()
+	}
+
+	/// Run the given job, then the queued ones until there are none left.
+	fn run(&self, mut job: Job) {
+		loop {
+			job();
+			let mut state = self.state.lock();
+			match state.queued.pop_front() {
+				Some(next) => job = next,
+				None => {
+					state.running -= 1;
+					return
+				},
+			}
+		}
+	}
+}
+
+/// An offchain worker execution, counted as in flight until dropped.
+struct InFlight(Arc<AtomicUsize>);
+
+impl Drop for InFlight {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
 /// An offchain workers manager.
 pub struct OffchainWorkers<Client, Block: traits::Block> {
 	client: Arc<Client>,
 	_block: PhantomData<Block>,
-	thread_pool: Mutex<ThreadPool>,
+	worker_pool: Arc<WorkerPool>,
 	shared_client: api::SharedClient,
+	metrics: Option<Arc<metrics::Metrics>>,
+	limits: ExecutionLimits,
+	/// Number of executions running or queued.
+	in_flight: Arc<AtomicUsize>,
+	retries: RetryPolicy,
+	failures: Option<Arc<FailureStore>>,
 }
 
 impl<Client, Block: traits::Block> OffchainWorkers<Client, Block> {
 	/// Creates new `OffchainWorkers`.
-	pub fn new(client: Arc<Client>) -> Self {
-		let shared_client = api::SharedClient::new();
+	pub fn new(client: Arc<Client>, prometheus: Option<&Registry>) -> Self {
+		Self::with_http_config(client, prometheus, &HttpClientConfig::default())
+	}
+
+	/// Creates new `OffchainWorkers` whose HTTP requests use the given configuration.
+	pub fn with_http_config(
+		client: Arc<Client>,
+		prometheus: Option<&Registry>,
+		http_config: &HttpClientConfig,
+	) -> Self {
+		let shared_client = api::SharedClient::with_config(http_config);
+		let metrics = prometheus.and_then(|registry|
+			metrics::Metrics::register(registry)
+				.map_err(|err| log::warn!("Failed to register offchain workers metrics: {}", err))
+				.ok()
+				.map(Arc::new)
+		);
+		let limits = ExecutionLimits::default();
 		Self {
 			client,
 			_block: PhantomData,
-			thread_pool: Mutex::new(ThreadPool::new(num_cpus::get())),
+			worker_pool: Arc::new(WorkerPool::new(&limits)),
 			shared_client,
+			metrics,
+			limits,
+			in_flight: Arc::new(AtomicUsize::new(0)),
+			retries: RetryPolicy::default(),
+			failures: None,
+		}
+	}
+
+	/// Use the given limits on the offchain worker executions.
+	pub fn with_execution_limits(mut self, limits: ExecutionLimits) -> Self {
+		self.worker_pool = Arc::new(WorkerPool::new(&limits));
+		self.limits = limits;
+		self
+	}
+
+	/// Retry the failed executions according to the given policy.
+	pub fn with_retries(mut self, retries: RetryPolicy) -> Self {
+		self.retries = retries;
+		self
+	}
+
+	/// Only allow offchain workers to reach the hosts permitted by the given policy.
+	pub fn with_egress_policy(mut self, egress: EgressPolicy) -> Self {
+		self.shared_client = self.shared_client.with_egress_policy(egress);
+		self
+	}
+
+	/// Record the executions which kept failing after all their retries in the given database.
+	pub fn with_failure_store(mut self, db: Box<dyn offchain::DbExternalities>) -> Self {
+		self.failures = Some(Arc::new(FailureStore(Mutex::new(db))));
+		self
+	}
+
+	/// Account for a new execution, unless it has to be skipped.
+	fn start_execution(&self) -> Option<InFlight> {
+		let running = self.in_flight.fetch_add(1, Ordering::SeqCst);
+		let in_flight = InFlight(self.in_flight.clone());
+		if running >= self.limits.max_concurrent && self.limits.when_busy == WhenBusy::Skip {
+			return None
 		}
+		Some(in_flight)
 	}
 }
 
@@ -125,78 +357,155 @@ impl<Client, Block> OffchainWorkers<
 		network_provider: Arc<dyn NetworkProvider + Send + Sync>,
 		is_validator: bool,
 	) -> impl Future<Output = ()> {
-		let runtime = self.client.runtime_api();
 		let at = BlockId::hash(header.hash());
-		let has_api_v1 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(
-			&at, |v| v == 1
-		);
-		let has_api_v2 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(
-			&at, |v| v == 2
-		);
-		let version = match (has_api_v1, has_api_v2) {
-			(_, Ok(true)) => 2,
-			(Ok(true), _) => 1,
-			err => {
-				let help = "Consider turning off offchain workers if they are not part of your runtime.";
-				log::error!("Unsupported Offchain Worker API version: {:?}. {}.", err, help);
-				0
+		let in_flight = match self.start_execution() {
+			Some(in_flight) => in_flight,
+			None => {
+				debug!(
+					"Skipping offchain workers at {:?}: {} executions already running",
+					at,
+					self.limits.max_concurrent,
+				);
+				if let Some(metrics) = &self.metrics {
+					metrics.workers_skipped.inc();
+				}
+				return futures::future::Either::Right(futures::future::ready(()))
 			}
 		};
-		debug!("Checking offchain workers at {:?}: version:{}", at, version);
-		if version > 0 {
-			let (api, runner) = api::AsyncApi::new(
-				network_provider,
-				is_validator,
-				self.shared_client.clone(),
-			);
-			debug!("Spawning offchain workers at {:?}", at);
-			let header = header.clone();
-			let client = self.client.clone();
-			self.spawn_worker(move || {
-				let runtime = client.runtime_api();
-				let api = Box::new(api);
-				debug!("Running offchain workers at {:?}", at);
-				let context = ExecutionContext::OffchainCall(Some(
-					(api, offchain::Capabilities::all())
-				));
-				let run = if version == 2 {
-					runtime.offchain_worker_with_context(&at, context, &header)
-				} else {
-					#[allow(deprecated)]
-					runtime.offchain_worker_before_version_2_with_context(
-						&at, context, *header.number()
-					)
+		let header = header.clone();
+		let client = self.client.clone();
+		let metrics = self.metrics.clone();
+		let shared_client = self.shared_client.clone();
+		let worker_pool = self.worker_pool.clone();
+		let retries = self.retries.clone();
+		let failures = self.failures.clone();
+		futures::future::Either::Left(async move {
+			let _in_flight = in_flight;
+			let mut attempts = 0;
+			loop {
+				attempts += 1;
+				let (api, runner) = api::AsyncApi::new(
+					network_provider.clone(),
+					is_validator,
+					shared_client.clone(),
+				);
+				let (result_tx, result_rx) = oneshot::channel();
+				let (client, header, metrics) = (client.clone(), header.clone(), metrics.clone());
+				let at = at.clone();
+				// The runtime is queried on the worker thread, not to delay import notifications.
+				// Without the API, the execution completes right away and releases its slot.
+				let dropped = worker_pool.execute(Box::new(move || {
+					let result = match api_version(&*client, &at) {
+						Some(version) => run_worker(&*client, at, &header, version, api, metrics),
+						None => Ok(()),
+					};
+					let _ = result_tx.send(result);
+				}));
+				if dropped {
+					debug!("Dropped the oldest queued offchain worker execution");
+					if let Some(metrics) = &metrics {
+						metrics.workers_dropped.inc();
+					}
+				}
+				// `runner` completes once the execution is over and `api` is dropped.
+				let error = match future::join(result_rx, runner.process()).await {
+					(Ok(Err(error)), ()) => error,
+					_ => return,
 				};
-				if let Err(e) =	run {
-					log::error!("Error running offchain workers at {:?}: {:?}", at, e);
+
+				if attempts > retries.max_retries {
+					if let Some(failures) = failures {
+						failures.record(offchain::WorkerFailure {
+							block_hash: header.hash().encode(),
+							attempts,
+							error: error.into_bytes(),
+						});
+					}
+					return
 				}
-			});
-			futures::future::Either::Left(runner.process())
-		} else {
-			futures::future::Either::Right(futures::future::ready(()))
+
+				let backoff = retries.backoff * 2u32.pow((attempts - 1).min(10));
+				warn!("Retrying offchain workers at {:?} in {:?}", at, backoff);
+				futures_timer::Delay::new(backoff).await;
+			}
+		})
+	}
+}
+
+/// Returns the version of the offchain worker API of the runtime at the given block, if supported.
+fn api_version<Client, Block>(client: &Client, at: &BlockId<Block>) -> Option<u32> where
+	Block: traits::Block,
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: OffchainWorkerApi<Block>,
+{
+	let runtime = client.runtime_api();
+	let has_api_v1 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(
+		at, |v| v == 1
+	);
+	let has_api_v2 = runtime.has_api_with::<dyn OffchainWorkerApi<Block>, _>(
+		at, |v| v == 2
+	);
+	match (has_api_v1, has_api_v2) {
+		(_, Ok(true)) => Some(2),
+		(Ok(true), _) => Some(1),
+		err => {
+			let help = "Consider turning off offchain workers if they are not part of your runtime.";
+			log::error!("Unsupported Offchain Worker API version: {:?}. {}.", err, help);
+			None
 		}
 	}
+}
 
-	/// Spawns a new offchain worker.
-	///
-	/// We spawn offchain workers for each block in a separate thread,
-	/// since they can run for a significant amount of time
-	/// in a blocking fashion and we don't want to block the runtime.
-	///
-	/// Note that we should avoid that if we switch to future-based runtime in the future,
-	/// alternatively:
-	fn spawn_worker(&self, f: impl FnOnce() -> () + Send + 'static) {
-		self.thread_pool.lock().execute(f);
+/// Run the offchain workers at the given block, blocking the current thread.
+///
+/// Running the workers may require instantiating the runtime, so this is only ever called on
+/// the dedicated worker threads.
+fn run_worker<Client, Block>(
+	client: &Client,
+	at: BlockId<Block>,
+	header: &Block::Header,
+	version: u32,
+	api: api::Api,
+	metrics: Option<Arc<metrics::Metrics>>,
+) -> Result<(), String> where
+	Block: traits::Block,
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: OffchainWorkerApi<Block>,
+{
+	let runtime = client.runtime_api();
+	debug!("Running offchain workers at {:?}: version:{}", at, version);
+	if let Some(metrics) = &metrics {
+		metrics.workers_spawned.inc();
 	}
+	let api = Box::new(api);
+	let context = ExecutionContext::OffchainCall(Some(
+		(api, offchain::Capabilities::all())
+	));
+	let run = if version == 2 {
+		runtime.offchain_worker_with_context(&at, context, header)
+	} else {
+		#[allow(deprecated)]
+		runtime.offchain_worker_before_version_2_with_context(
+			&at, context, *header.number()
+		)
+	};
+	run.map_err(|e| {
+		log::error!("Error running offchain workers at {:?}: {:?}", at, e);
+		if let Some(metrics) = metrics {
+			metrics.workers_failed.inc();
+		}
+		format!("{:?}", e)
+	})
 }
 
-/// Inform the offchain worker about new imported blocks
+/// Inform the offchain worker about new imported or finalized blocks, depending on `trigger`.
 pub async fn notification_future<Client, Block, Spawner>(
 	is_validator: bool,
 	client: Arc<Client>,
 	offchain: Arc<OffchainWorkers<Client, Block>>,
 	spawner: Spawner,
 	network_provider: Arc<dyn NetworkProvider + Send + Sync>,
+	trigger: Trigger,
 )
 	where
 		Block: traits::Block,
@@ -204,23 +513,34 @@ pub async fn notification_future<Client, Block, Spawner>(
 		Client::Api: OffchainWorkerApi<Block>,
 		Spawner: SpawnNamed
 {
-	client.import_notification_stream().for_each(move |n| {
+	let new_best = || client.import_notification_stream().filter_map(|n| ready(
 		if n.is_new_best {
-			spawner.spawn(
-				"offchain-on-block",
-				offchain.on_block_imported(
-					&n.header,
-					network_provider.clone(),
-					is_validator,
-				).boxed(),
-			);
+			Some(n.header)
 		} else {
 			log::debug!(
 				target: "sc_offchain",
 				"Skipping offchain workers for non-canon block: {:?}",
 				n.header,
-			)
+			);
+			None
 		}
+	));
+	let finalized = || client.finality_notification_stream().map(|n| n.header);
+	let headers = match trigger {
+		Trigger::NewBest => new_best().boxed(),
+		Trigger::Finalized => finalized().boxed(),
+		Trigger::Both => stream::select(new_best(), finalized()).boxed(),
+	};
+
+	headers.for_each(move |header| {
+		spawner.spawn(
+			"offchain-on-block",
+			offchain.on_block_imported(
+				&header,
+				network_provider.clone(),
+				is_validator,
+			).boxed(),
+		);
 
 		ready(())
 	}).await;
@@ -298,7 +618,7 @@ mod tests {
 		let header = client.header(&BlockId::number(0)).unwrap().unwrap();
 
 		// when
-		let offchain = OffchainWorkers::new(client);
+		let offchain = OffchainWorkers::new(client, None);
 		futures::executor::block_on(
 			offchain.on_block_imported(&header, network, false)
 		);
@@ -308,6 +628,87 @@ mod tests {
 		assert_eq!(pool.0.ready().next().unwrap().is_propagable(), false);
 	}
 
+	#[test]
+	fn should_skip_executions_past_the_limit() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let offchain = OffchainWorkers::<_, Block>::new(client, None)
+			.with_execution_limits(ExecutionLimits {
+				max_concurrent: 1,
+				when_busy: WhenBusy::Skip,
+				max_queued: 0,
+			});
+
+		let running = offchain.start_execution().unwrap();
+		assert!(offchain.start_execution().is_none());
+		drop(running);
+		assert!(offchain.start_execution().is_some());
+
+		let offchain = offchain.with_execution_limits(ExecutionLimits {
+			max_concurrent: 1,
+			when_busy: WhenBusy::Queue,
+			max_queued: 1,
+		});
+		let _running = offchain.start_execution().unwrap();
+		assert!(offchain.start_execution().is_some());
+	}
+
+	#[test]
+	fn should_drop_the_oldest_queued_executions() {
+		let pool = Arc::new(WorkerPool::new(&ExecutionLimits {
+			max_concurrent: 1,
+			when_busy: WhenBusy::Queue,
+			max_queued: 1,
+		}));
+		let (release, released) = std::sync::mpsc::channel::<()>();
+		let (done, finished) = std::sync::mpsc::channel();
+
+		// occupy the only thread until released
+		assert!(!pool.execute(Box::new(move || { let _ = released.recv(); })));
+
+		let job = |n: u32| -> Job {
+			let done = done.clone();
+			Box::new(move || done.send(n).unwrap())
+		};
+		assert!(!pool.execute(job(1)));
+		assert!(pool.execute(job(2)));
+		assert!(pool.execute(job(3)));
+
+		release.send(()).unwrap();
+		assert_eq!(finished.recv(), Ok(3));
+		drop(done);
+		assert!(finished.recv().is_err());
+	}
+
+	#[test]
+	fn should_keep_the_latest_failures() {
+		let db = OffchainDb::new(sc_client_db::offchain::LocalStorage::new_test());
+		let store = FailureStore(Mutex::new(Box::new(db.clone())));
+		let failure = |n: usize| WorkerFailure {
+			block_hash: n.encode(),
+			attempts: 1,
+			error: b"trapped".to_vec(),
+		};
+
+		for n in 0..MAX_RECORDED_FAILURES + 2 {
+			store.record(failure(n));
+		}
+
+		let mut db = db;
+		assert!(offchain::DbExternalities::local_storage_get(
+			&mut db,
+			offchain::StorageKind::PERSISTENT,
+			offchain::WORKER_FAILURES_KEY,
+		).is_none());
+		let encoded = offchain::DbExternalities::node_storage_get(
+			&mut db,
+			offchain::WORKER_FAILURES_KEY,
+		).unwrap();
+		let failures = <Vec<WorkerFailure>>::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(failures.len(), MAX_RECORDED_FAILURES);
+		assert_eq!(failures[0], failure(2));
+		assert_eq!(failures[MAX_RECORDED_FAILURES - 1], failure(MAX_RECORDED_FAILURES + 1));
+	}
+
 	#[test]
 	fn offchain_index_set_and_clear_works() {
 		use sp_core::offchain::OffchainStorage;
@@ -0,0 +1,67 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Offchain workers Prometheus metrics.
+
+use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+
+/// Offchain workers Prometheus metrics.
+pub(crate) struct Metrics {
+	pub workers_spawned: Counter<U64>,
+	pub workers_failed: Counter<U64>,
+	pub workers_skipped: Counter<U64>,
+	pub workers_dropped: Counter<U64>,
+}
+
+impl Metrics {
+	/// Register the metrics at the given Prometheus registry.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			workers_spawned: register(
+				Counter::new(
+					"sub_offchain_workers_spawned",
+					"Total number of offchain worker executions spawned",
+				)?,
+				registry,
+			)?,
+			workers_failed: register(
+				Counter::new(
+					"sub_offchain_workers_failed",
+					"Total number of offchain worker executions that returned an error",
+				)?,
+				registry,
+			)?,
+			workers_skipped: register(
+				Counter::new(
+					"sub_offchain_workers_skipped",
+					"Total number of blocks for which offchain workers were skipped because too many \
+					were already running",
+				)?,
+				registry,
+			)?,
+			workers_dropped: register(
+				Counter::new(
+					"sub_offchain_workers_dropped",
+					"Total number of queued offchain worker executions dropped to make room for \
+					newer ones",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
@@ -24,7 +24,7 @@ pub mod hash;
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use sp_core::Bytes;
-use sp_transaction_pool::TransactionStatus;
+use sp_transaction_pool::{TransactionDiagnostics, TransactionStatus};
 use self::error::{FutureResult, Result};
 
 pub use self::gen_client::Client as AuthorClient;
@@ -70,6 +70,13 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[rpc(name = "author_pendingExtrinsics")]
 	fn pending_extrinsics(&self) -> Result<Vec<Bytes>>;
 
+	/// Returns diagnostics about all transactions in the pool, ready ones first.
+	///
+	/// The ready transactions come in the same order as in `author_pendingExtrinsics`. Unlike
+	/// there, future transactions are included.
+	#[rpc(name = "author_inspectPool")]
+	fn inspect_pool(&self) -> Result<Vec<TransactionDiagnostics<Hash>>>;
+
 	/// Remove given extrinsic from the pool and temporarily ban it to prevent reimporting.
 	#[rpc(name = "author_removeExtrinsic")]
 	fn remove_extrinsic(&self,
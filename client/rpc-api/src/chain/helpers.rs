@@ -0,0 +1,42 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate blockchain API helpers.
+
+use serde::{Serialize, Deserialize};
+
+/// Finalized head notification that also carries the blocks finalized since the previous one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalizedHeads<Hash, Header> {
+	/// Header of the newly finalized head.
+	pub header: Header,
+	/// Hashes of all blocks finalized since the previous notification, in ascending
+	/// order. The last entry is always the hash of `header`.
+	pub finalized: Vec<Hash>,
+}
+
+/// Notification of the finalized head subscription.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FinalizedHeadNotification<Hash, Header> {
+	/// Header of the newly finalized head, sent unless the range was requested.
+	Header(Header),
+	/// Newly finalized head along with the blocks finalized since the previous notification.
+	WithRange(FinalizedHeads<Hash, Header>),
+}
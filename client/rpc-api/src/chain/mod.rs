@@ -19,14 +19,20 @@
 //! Substrate blockchain API.
 
 pub mod error;
+pub mod helpers;
 
 use jsonrpc_core::Result as RpcResult;
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use sp_core::Bytes;
 use sp_rpc::{number::NumberOrHex, list::ListOrValue};
 use self::error::{FutureResult, Result};
 
 pub use self::gen_client::Client as ChainClient;
+pub use self::helpers::{FinalizedHeads, FinalizedHeadNotification};
+
+/// Maximum number of blocks streamed by a single `chain_subscribeBlocksRange` subscription.
+pub const MAX_BLOCKS_RANGE: u32 = 1024;
 
 /// Substrate blockchain API
 #[rpc]
@@ -89,14 +95,23 @@ pub trait ChainApi<Number, Hash, Header, SignedBlock> {
 		id: SubscriptionId,
 	) -> RpcResult<bool>;
 
-	/// Finalized head subscription
+	/// Finalized head subscription.
+	///
+	/// With `with_range` set, every notification also carries the hashes of all the blocks
+	/// finalized since the previous one, so subscribers don't need to walk the ancestry of the
+	/// new head themselves after a finality jump.
 	#[pubsub(
 		subscription = "chain_finalizedHead",
 		subscribe,
 		name = "chain_subscribeFinalizedHeads",
 		alias("chain_subscribeFinalisedHeads")
 	)]
-	fn subscribe_finalized_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Header>);
+	fn subscribe_finalized_heads(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<FinalizedHeadNotification<Hash, Header>>,
+		with_range: Option<bool>,
+	);
 
 	/// Unsubscribe from finalized head subscription.
 	#[pubsub(
@@ -110,4 +125,29 @@ pub trait ChainApi<Number, Hash, Header, SignedBlock> {
 		metadata: Option<Self::Metadata>,
 		id: SubscriptionId,
 	) -> RpcResult<bool>;
+
+	/// Stream the SCALE-encoded blocks, including their justifications, of the canon chain
+	/// in the range `from..=to`.
+	///
+	/// By default the range ends at the latest block. Blocks are only read once the
+	/// subscriber is ready to receive them, and the subscription ends after the last one.
+	///
+	/// A range spans at most [`MAX_BLOCKS_RANGE`] blocks: longer ranges are rejected, and a
+	/// range without an end stops after that many blocks.
+	#[pubsub(subscription = "chain_blocksRange", subscribe, name = "chain_subscribeBlocksRange")]
+	fn subscribe_blocks_range(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<Bytes>,
+		from: NumberOrHex,
+		to: Option<NumberOrHex>,
+	);
+
+	/// Unsubscribe from blocks range subscription.
+	#[pubsub(subscription = "chain_blocksRange", unsubscribe, name = "chain_unsubscribeBlocksRange")]
+	fn unsubscribe_blocks_range(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
 }
@@ -22,10 +22,33 @@ pub mod error;
 
 use jsonrpc_derive::rpc;
 use self::error::Result;
+use serde::{Serialize, Deserialize};
 use sp_core::{Bytes, offchain::StorageKind};
 
 pub use self::gen_client::Client as OffchainClient;
 
+/// An offchain worker execution which kept failing after all its retries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerFailure {
+	/// Hash of the block the offchain workers ran on.
+	pub block_hash: Bytes,
+	/// Number of executions attempted.
+	pub attempts: u32,
+	/// Last error.
+	pub error: String,
+}
+
+impl From<sp_core::offchain::WorkerFailure> for WorkerFailure {
+	fn from(failure: sp_core::offchain::WorkerFailure) -> Self {
+		WorkerFailure {
+			block_hash: failure.block_hash.into(),
+			attempts: failure.attempts,
+			error: String::from_utf8_lossy(&failure.error).into_owned(),
+		}
+	}
+}
+
 /// Substrate offchain RPC API
 #[rpc]
 pub trait OffchainApi {
@@ -36,4 +59,8 @@ pub trait OffchainApi {
 	/// Get offchain local storage under given key and prefix.
 	#[rpc(name = "offchain_localStorageGet")]
 	fn get_local_storage(&self, kind: StorageKind, key: Bytes) -> Result<Option<Bytes>>;
+
+	/// Get the offchain worker executions which kept failing, oldest first.
+	#[rpc(name = "offchain_workerFailures")]
+	fn worker_failures(&self) -> Result<Vec<WorkerFailure>>;
 }
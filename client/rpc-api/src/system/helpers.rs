@@ -35,6 +35,22 @@ pub struct SystemInfo {
 	pub properties: Properties,
 	/// The type of this chain.
 	pub chain_type: ChainType,
+	/// The host functions provided by the node's executor.
+	pub host_functions: Vec<HostFunction>,
+}
+
+/// Host function provided by the node to the runtime.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostFunction {
+	/// Name of the function, without the version suffix.
+	pub name: String,
+	/// Version of the function.
+	pub version: u32,
+	/// Wasm value types of the arguments.
+	pub args: Vec<String>,
+	/// Wasm value type of the return value, if any.
+	pub return_value: Option<String>,
 }
 
 /// Health struct returned by the RPC
@@ -27,7 +27,7 @@ use futures::{future::BoxFuture, compat::Compat};
 
 use self::error::Result as SystemResult;
 
-pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState};
+pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState, HostFunction};
 pub use self::gen_client::Client as SystemClient;
 
 /// Substrate system RPC API
@@ -53,6 +53,13 @@ pub trait SystemApi<Hash, Number> {
 	#[rpc(name = "system_properties")]
 	fn system_properties(&self) -> SystemResult<sp_chain_spec::Properties>;
 
+	/// Get the manifest of host functions provided by the node to the runtime.
+	///
+	/// This can be used to check that a runtime's host function requirements are
+	/// satisfied by the node before it is enacted.
+	#[rpc(name = "system_hostFunctions")]
+	fn system_host_functions(&self) -> SystemResult<Vec<HostFunction>>;
+
 	/// Return health status of the node.
 	///
 	/// Node is considered healthy if it is:
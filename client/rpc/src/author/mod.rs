@@ -38,7 +38,7 @@ use sp_api::ProvideRuntimeApi;
 use sp_runtime::generic;
 use sp_transaction_pool::{
 	TransactionPool, InPoolTransaction, TransactionStatus, TransactionSource,
-	BlockHash, TxHash, TransactionFor, error::IntoPoolError,
+	BlockHash, TxHash, TransactionFor, TransactionDiagnostics, error::IntoPoolError,
 };
 use sp_session::SessionKeys;
 
@@ -157,6 +157,12 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 		Ok(self.pool.ready().map(|tx| tx.data().encode().into()).collect())
 	}
 
+	fn inspect_pool(&self) -> Result<Vec<TransactionDiagnostics<TxHash<P>>>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.pool.inspect())
+	}
+
 	fn remove_extrinsic(
 		&self,
 		bytes_or_hash: Vec<hash::ExtrinsicOrHash<TxHash<P>>>,
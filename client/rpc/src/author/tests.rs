@@ -201,6 +201,28 @@ fn should_return_pending_extrinsics() {
 	);
 }
 
+#[test]
+fn should_inspect_pool() {
+	let p = TestSetup::default().author();
+
+	let ex1 = uxt(AccountKeyring::Alice, 0);
+	let hash1 = p.submit_extrinsic(ex1.encode().into()).wait().unwrap();
+	let ex2 = uxt(AccountKeyring::Alice, 2);
+	let hash2 = p.submit_extrinsic(ex2.encode().into()).wait().unwrap();
+
+	let inspected = p.inspect_pool().unwrap();
+	assert_eq!(inspected.len(), 2);
+	assert_eq!(inspected[0].hash, hash1);
+	assert_eq!(inspected[0].queue, sp_transaction_pool::TransactionQueue::Ready);
+	assert_eq!(inspected[0].bytes, ex1.encode().len());
+	assert_eq!(inspected[1].hash, hash2);
+	assert_eq!(inspected[1].queue, sp_transaction_pool::TransactionQueue::Future);
+
+	let json = serde_json::to_value(&inspected[0]).unwrap();
+	assert_eq!(json["priority"], inspected[0].priority.to_string());
+	assert_eq!(json["validTill"], inspected[0].valid_till.to_string());
+}
+
 #[test]
 fn should_remove_extrinsics() {
 	let setup = TestSetup::default();
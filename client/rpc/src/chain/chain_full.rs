@@ -19,13 +19,22 @@
 //! Blockchain API backend for full nodes.
 
 use std::sync::Arc;
-use rpc::futures::future::result;
-use jsonrpc_pubsub::manager::SubscriptionManager;
+use codec::Encode;
+use futures::{future, StreamExt, TryStreamExt};
+use log::warn;
+use rpc::futures::{future::result, Future, Sink};
+use jsonrpc_pubsub::{typed::Subscriber, manager::SubscriptionManager};
 
 use sc_client_api::{BlockchainEvents, BlockBackend};
-use sp_runtime::{generic::{BlockId, SignedBlock}, traits::{Block as BlockT}};
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{
+	generic::{BlockId, SignedBlock}, traits::{Block as BlockT, NumberFor, One, Saturating},
+};
 
-use super::{ChainBackend, client_err, error::FutureResult};
+use super::{
+	ChainBackend, MAX_BLOCKS_RANGE, block_number, client_err, error::{Error, FutureResult},
+};
 use std::marker::PhantomData;
 use sp_blockchain::HeaderBackend;
 
@@ -77,4 +86,65 @@ impl<Block, Client> ChainBackend<Client, Block> for FullChain<Block, Client> whe
 			.map_err(client_err)
 		))
 	}
+
+	fn subscribe_blocks_range(
+		&self,
+		_metadata: crate::Metadata,
+		subscriber: Subscriber<Bytes>,
+		from: NumberOrHex,
+		to: Option<NumberOrHex>,
+	) {
+		let max_len: NumberFor<Block> = MAX_BLOCKS_RANGE.into();
+		let range = block_number::<Block>(from).and_then(|from| match to {
+			Some(to) => block_number::<Block>(to).map(|to| (from, to)),
+			None => {
+				let last = from.saturating_add(max_len - One::one());
+				Ok((from, last.min(self.client.info().best_number)))
+			},
+		});
+		let (from, to) = match range {
+			Ok((from, to)) if from <= to && to - from < max_len => (from, to),
+			Ok((from, to)) if from <= to => {
+				let _ = subscriber.reject(Error::Other(format!(
+					"Block range is longer than the maximum of {} blocks",
+					MAX_BLOCKS_RANGE,
+				)).into());
+				return;
+			},
+			Ok(_) => {
+				let _ = subscriber.reject(Error::Other("Invalid block range specified".into()).into());
+				return;
+			},
+			Err(err) => {
+				let _ = subscriber.reject(err.into());
+				return;
+			},
+		};
+
+		let client = self.client.clone();
+		self.subscriptions.add(subscriber, move |sink| {
+			// Blocks are only read from the database when the sink is ready to accept them,
+			// so a slow subscriber throttles the export instead of it being buffered.
+			let stream = futures::stream::unfold(Some(from), move |next| {
+				let item = next.and_then(|number| match client.block(&BlockId::number(number)) {
+					Ok(Some(block)) => {
+						let next = if number < to { Some(number + One::one()) } else { None };
+						Some((Ok(Bytes(block.encode())), next))
+					},
+					// Reached end of the chain.
+					Ok(None) => None,
+					Err(err) => Some((Err(rpc::Error::from(client_err(err))), None)),
+				});
+				future::ready(item)
+			})
+				.map(|res| Ok::<_, ()>(res))
+				.compat();
+
+			sink
+				.sink_map_err(|e| warn!("Error sending blocks: {:?}", e))
+				.send_all(stream)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
 }
@@ -21,7 +21,7 @@
 use std::sync::Arc;
 use futures::{future::ready, FutureExt, TryFutureExt};
 use rpc::futures::future::{result, Future, Either};
-use jsonrpc_pubsub::manager::SubscriptionManager;
+use jsonrpc_pubsub::{typed::Subscriber, manager::SubscriptionManager};
 
 use sc_client_api::light::{Fetcher, RemoteBodyRequest, RemoteBlockchain};
 use sp_runtime::{
@@ -29,7 +29,10 @@ use sp_runtime::{
 	traits::{Block as BlockT},
 };
 
-use super::{ChainBackend, client_err, error::FutureResult};
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+
+use super::{ChainBackend, client_err, error::{Error, FutureResult}};
 use sp_blockchain::HeaderBackend;
 use sc_client_api::BlockchainEvents;
 
@@ -115,4 +118,16 @@ impl<Block, Client, F> ChainBackend<Client, Block> for LightChain<Block, Client,
 
 		Box::new(block)
 	}
+
+	fn subscribe_blocks_range(
+		&self,
+		_metadata: crate::Metadata,
+		subscriber: Subscriber<Bytes>,
+		_from: NumberOrHex,
+		_to: Option<NumberOrHex>,
+	) {
+		let _ = subscriber.reject(
+			Error::Other("Streaming blocks is not supported by light clients".into()).into(),
+		);
+	}
 }
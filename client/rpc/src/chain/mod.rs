@@ -34,10 +34,11 @@ use rpc::{
 
 use sc_client_api::{BlockchainEvents, light::{Fetcher, RemoteBlockchain}};
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId, manager::SubscriptionManager};
+use sp_core::Bytes;
 use sp_rpc::{number::NumberOrHex, list::ListOrValue};
 use sp_runtime::{
 	generic::{BlockId, SignedBlock},
-	traits::{Block as BlockT, Header, NumberFor},
+	traits::{Block as BlockT, Header, NumberFor, One},
 };
 
 use self::error::{Result, Error, FutureResult};
@@ -79,16 +80,7 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 		match number {
 			None => Ok(Some(self.client().info().best_hash)),
 			Some(num_or_hex) => {
-				use std::convert::TryInto;
-
-				// FIXME <2329>: Database seems to limit the block number to u32 for no reason
-				let block_num: u32 = num_or_hex.try_into().map_err(|_| {
-					Error::from(format!(
-						"`{:?}` > u32::max_value(), the max block number is u32.",
-						num_or_hex
-					))
-				})?;
-				let block_num = <NumberFor<Block>>::from(block_num);
+				let block_num = block_number::<Block>(num_or_hex)?;
 				Ok(self
 					.client()
 					.header(BlockId::number(block_num))
@@ -156,21 +148,71 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 		Ok(self.subscriptions().cancel(id))
 	}
 
-	/// Finalized head subscription
+	/// Finalized head subscription, optionally reporting the range of newly finalized blocks.
 	fn subscribe_finalized_heads(
 		&self,
 		_metadata: crate::Metadata,
-		subscriber: Subscriber<Block::Header>,
+		subscriber: Subscriber<FinalizedHeadNotification<Block::Hash, Block::Header>>,
+		with_range: Option<bool>,
 	) {
-		subscribe_headers(
-			self.client(),
-			self.subscriptions(),
-			subscriber,
-			|| self.client().info().finalized_hash,
-			|| self.client().finality_notification_stream()
-				.map(|notification| Ok::<_, ()>(notification.header))
-				.compat(),
-		)
+		let with_range = with_range.unwrap_or(false);
+		let client = self.client().clone();
+		self.subscriptions().add(subscriber, move |sink| {
+			let info = client.info();
+			let mut last_finalized = info.finalized_number;
+
+			// send current finalized head right at the start.
+			let heads = client.header(BlockId::Hash(info.finalized_hash))
+				.map_err(client_err)
+				.and_then(|header| {
+					header.ok_or_else(|| "Finalized header missing.".to_owned().into())
+				})
+				.map(|header| {
+					finalized_head_notification(header, vec![info.finalized_hash], with_range)
+				})
+				.map_err(Into::into);
+
+			// send further finalized heads, skipping the ones that were already reported.
+			let stream = client.finality_notification_stream()
+				.filter_map(move |notification| {
+					if *notification.header.number() <= last_finalized {
+						return future::ready(None);
+					}
+
+					let finalized = if with_range {
+						finalized_range(&*client, last_finalized, &notification.header)
+					} else {
+						Ok(Vec::new())
+					};
+					match finalized {
+						Ok(finalized) => {
+							last_finalized = *notification.header.number();
+							future::ready(Some(finalized_head_notification(
+								notification.header,
+								finalized,
+								with_range,
+							)))
+						},
+						Err(e) => {
+							warn!("Failed to compute finalized range for {:?}: {:?}", notification.hash, e);
+							future::ready(None)
+						},
+					}
+				})
+				.map(|heads| Ok::<_, ()>(heads))
+				.compat()
+				.map(|res| Ok(res))
+				.map_err(|e| warn!("Finality notification stream error: {:?}", e));
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(
+					stream::iter_result(vec![Ok(heads)])
+						.chain(stream)
+				)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
 	}
 
 	/// Unsubscribe from finalized head subscription.
@@ -181,6 +223,24 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 	) -> RpcResult<bool> {
 		Ok(self.subscriptions().cancel(id))
 	}
+
+	/// Blocks range subscription.
+	fn subscribe_blocks_range(
+		&self,
+		metadata: crate::Metadata,
+		subscriber: Subscriber<Bytes>,
+		from: NumberOrHex,
+		to: Option<NumberOrHex>,
+	);
+
+	/// Unsubscribe from blocks range subscription.
+	fn unsubscribe_blocks_range(
+		&self,
+		_metadata: Option<crate::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions().cancel(id))
+	}
 }
 
 /// Create new state API that works on full node.
@@ -276,13 +336,32 @@ impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Signe
 		self.backend.unsubscribe_new_heads(metadata, id)
 	}
 
-	fn subscribe_finalized_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Block::Header>) {
-		self.backend.subscribe_finalized_heads(metadata, subscriber)
+	fn subscribe_finalized_heads(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<FinalizedHeadNotification<Block::Hash, Block::Header>>,
+		with_range: Option<bool>,
+	) {
+		self.backend.subscribe_finalized_heads(metadata, subscriber, with_range)
 	}
 
 	fn unsubscribe_finalized_heads(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
 		self.backend.unsubscribe_finalized_heads(metadata, id)
 	}
+
+	fn subscribe_blocks_range(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<Bytes>,
+		from: NumberOrHex,
+		to: Option<NumberOrHex>,
+	) {
+		self.backend.subscribe_blocks_range(metadata, subscriber, from, to)
+	}
+
+	fn unsubscribe_blocks_range(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
+		self.backend.unsubscribe_blocks_range(metadata, id)
+	}
 }
 
 /// Subscribe to new headers.
@@ -325,6 +404,59 @@ fn subscribe_headers<Block, Client, F, G, S, ERR>(
 	});
 }
 
+/// Build the finalized head notification, with the range of finalized blocks if requested.
+fn finalized_head_notification<Hash, Header>(
+	header: Header,
+	finalized: Vec<Hash>,
+	with_range: bool,
+) -> FinalizedHeadNotification<Hash, Header> {
+	if with_range {
+		FinalizedHeadNotification::WithRange(FinalizedHeads { header, finalized })
+	} else {
+		FinalizedHeadNotification::Header(header)
+	}
+}
+
+/// Collect the hashes of the blocks between `last_finalized` (exclusive) and `header`
+/// (inclusive), in ascending order.
+fn finalized_range<Block, Client>(
+	client: &Client,
+	last_finalized: NumberFor<Block>,
+	header: &Block::Header,
+) -> sp_blockchain::Result<Vec<Block::Hash>> where
+	Block: BlockT,
+	Client: HeaderBackend<Block>,
+{
+	let mut finalized = vec![header.hash()];
+	let mut parent_hash = *header.parent_hash();
+	let mut number = *header.number();
+	while number > last_finalized + One::one() {
+		let parent = client.header(BlockId::Hash(parent_hash))?
+			.ok_or_else(|| sp_blockchain::Error::UnknownBlock(format!("{:?}", parent_hash)))?;
+		finalized.push(parent_hash);
+		parent_hash = *parent.parent_hash();
+		number = *parent.number();
+	}
+	finalized.reverse();
+
+	Ok(finalized)
+}
+
+/// Convert an RPC block number into the block number type of the chain.
+fn block_number<Block: BlockT>(num_or_hex: NumberOrHex) -> Result<NumberFor<Block>> {
+	use std::convert::TryInto;
+
+	// FIXME <2329>: Database seems to limit the block number to u32 for no reason
+	let block_num: u32 = num_or_hex.try_into().map_err(|_| {
+		Error::from(format!(
+			"`{:?}` > u32::max_value(), the max block number is u32.",
+			num_or_hex
+		))
+	})?;
+
+	Ok(<NumberFor<Block>>::from(block_num))
+}
+
 fn client_err(err: sp_blockchain::Error) -> Error {
 	Error::Client(Box::new(err))
 }
@@ -250,7 +250,7 @@ fn should_notify_about_finalized_block() {
 		let mut client = Arc::new(substrate_test_runtime_client::new());
 		let api = new_full(client.clone(), SubscriptionManager::new(Arc::new(TaskExecutor)));
 
-		api.subscribe_finalized_heads(Default::default(), subscriber);
+		api.subscribe_finalized_heads(Default::default(), subscriber, None);
 
 		// assert id assigned
 		assert!(matches!(
@@ -272,3 +272,112 @@ fn should_notify_about_finalized_block() {
 	// no more notifications on this channel
 	assert_eq!(executor::block_on(next.into_future().compat()).unwrap().0, None);
 }
+
+#[test]
+fn should_notify_about_finalized_block_with_range() {
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+
+	{
+		let mut client = Arc::new(substrate_test_runtime_client::new());
+		let api = new_full(client.clone(), SubscriptionManager::new(Arc::new(TaskExecutor)));
+
+		api.subscribe_finalized_heads(Default::default(), subscriber, Some(true));
+
+		// assert id assigned
+		assert!(matches!(
+			executor::block_on(id.compat()),
+			Ok(Ok(SubscriptionId::String(_)))
+		));
+
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		client.finalize_block(BlockId::number(1), None).unwrap();
+	}
+
+	// assert initial head sent.
+	let (notification, next) = executor::block_on(transport.into_future().compat()).unwrap();
+	assert!(notification.is_some());
+	// assert notification sent to transport, with the range of finalized blocks
+	let (notification, next) = executor::block_on(next.into_future().compat()).unwrap();
+	assert!(notification.unwrap().contains("\"finalized\""));
+	// no more notifications on this channel
+	assert_eq!(executor::block_on(next.into_future().compat()).unwrap().0, None);
+}
+
+#[test]
+fn should_return_range_of_finalized_blocks() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+
+	let mut hashes = Vec::new();
+	for _ in 0..3 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		hashes.push(block.header.hash());
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+	}
+
+	let header = client.header(&BlockId::Hash(hashes[2])).unwrap().unwrap();
+	assert_eq!(
+		finalized_range::<Block, _>(&*client, 0, &header).unwrap(),
+		hashes,
+	);
+	assert_eq!(
+		finalized_range::<Block, _>(&*client, 2, &header).unwrap(),
+		vec![hashes[2]],
+	);
+}
+
+#[test]
+fn should_stream_blocks_range() {
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+
+	{
+		let mut client = Arc::new(substrate_test_runtime_client::new());
+		for _ in 0..2 {
+			let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+			executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+
+		let api = new_full(client.clone(), SubscriptionManager::new(Arc::new(TaskExecutor)));
+
+		api.subscribe_blocks_range(Default::default(), subscriber, 1u64.into(), None);
+
+		// assert id assigned
+		assert!(matches!(
+			executor::block_on(id.compat()),
+			Ok(Ok(SubscriptionId::String(_)))
+		));
+	}
+
+	// assert both blocks sent.
+	let (notification, next) = executor::block_on(transport.into_future().compat()).unwrap();
+	assert!(notification.is_some());
+	let (notification, next) = executor::block_on(next.into_future().compat()).unwrap();
+	assert!(notification.is_some());
+	// no more notifications on this channel
+	assert_eq!(executor::block_on(next.into_future().compat()).unwrap().0, None);
+}
+
+#[test]
+fn should_reject_invalid_blocks_range() {
+	let (subscriber, id, _transport) = Subscriber::new_test("test");
+
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client, SubscriptionManager::new(Arc::new(TaskExecutor)));
+
+	api.subscribe_blocks_range(Default::default(), subscriber, 2u64.into(), Some(1u64.into()));
+
+	assert!(matches!(executor::block_on(id.compat()), Ok(Err(_))));
+}
+
+#[test]
+fn should_reject_too_long_blocks_range() {
+	let (subscriber, id, _transport) = Subscriber::new_test("test");
+
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client, SubscriptionManager::new(Arc::new(TaskExecutor)));
+
+	let to = u64::from(MAX_BLOCKS_RANGE);
+	api.subscribe_blocks_range(Default::default(), subscriber, 0u64.into(), Some(to.into()));
+
+	assert!(matches!(executor::block_on(id.compat()), Ok(Err(_))));
+}
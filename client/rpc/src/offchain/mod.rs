@@ -25,9 +25,10 @@ mod tests;
 pub use sc_rpc_api::offchain::*;
 use sc_rpc_api::DenyUnsafe;
 use self::error::{Error, Result};
+use codec::Decode;
 use sp_core::{
 	Bytes,
-	offchain::{OffchainStorage, StorageKind},
+	offchain::{OffchainStorage, StorageKind, NODE_STORAGE_PREFIX, WORKER_FAILURES_KEY},
 };
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -73,4 +74,16 @@ impl<T: OffchainStorage + 'static> OffchainApi for Offchain<T> {
 		};
 		Ok(self.storage.read().get(prefix, &*key).map(Into::into))
 	}
+
+	/// Get the offchain worker executions which kept failing, oldest first.
+	fn worker_failures(&self) -> Result<Vec<WorkerFailure>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let failures = self.storage.read().get(NODE_STORAGE_PREFIX, WORKER_FAILURES_KEY)
+			.and_then(|encoded| {
+				<Vec<sp_core::offchain::WorkerFailure>>::decode(&mut &encoded[..]).ok()
+			})
+			.unwrap_or_default();
+		Ok(failures.into_iter().map(Into::into).collect())
+	}
 }
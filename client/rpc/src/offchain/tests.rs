@@ -53,3 +53,26 @@ fn offchain_calls_considered_unsafe() {
 		Err(Error::UnsafeRpcCalled(_))
 	);
 }
+
+#[test]
+fn worker_failures_should_be_decoded() {
+	use codec::Encode;
+
+	let mut storage = InMemOffchainStorage::default();
+	let failure = sp_core::offchain::WorkerFailure {
+		block_hash: vec![1, 2, 3],
+		attempts: 2,
+		error: b"trapped".to_vec(),
+	};
+	storage.set(NODE_STORAGE_PREFIX, WORKER_FAILURES_KEY, &vec![failure].encode());
+	let offchain = Offchain::new(storage, DenyUnsafe::No);
+
+	assert_eq!(
+		offchain.worker_failures().unwrap(),
+		vec![WorkerFailure {
+			block_hash: Bytes(vec![1, 2, 3]),
+			attempts: 2,
+			error: "trapped".into(),
+		}],
+	);
+}
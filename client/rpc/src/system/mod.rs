@@ -24,6 +24,7 @@ mod tests;
 use futures::{future::BoxFuture, FutureExt, TryFutureExt};
 use futures::{channel::oneshot, compat::Compat};
 use sc_rpc_api::{DenyUnsafe, Receiver};
+use sc_executor::sp_wasm_interface::{Function, ValueType};
 use sc_tracing::logging;
 use sp_utils::mpsc::TracingUnboundedSender;
 use sp_runtime::traits::{self, Header as HeaderT};
@@ -31,7 +32,7 @@ use sp_runtime::traits::{self, Header as HeaderT};
 use self::error::Result;
 
 pub use sc_rpc_api::system::*;
-pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState};
+pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState, HostFunction};
 pub use self::gen_client::Client as SystemClient;
 
 macro_rules! bail_if_unsafe {
@@ -90,6 +91,40 @@ impl<B: traits::Block> System<B> {
 	}
 }
 
+/// Build the host function manifest exposed by `system_hostFunctions`.
+///
+/// Function names generated by `#[runtime_interface]` carry a `_version_N` suffix, which is
+/// split off into the version of the function. Functions without it are reported as version 1.
+pub fn host_functions_manifest(functions: &[&'static dyn Function]) -> Vec<HostFunction> {
+	fn value_type(value: ValueType) -> String {
+		match value {
+			ValueType::I32 => "i32",
+			ValueType::I64 => "i64",
+			ValueType::F32 => "f32",
+			ValueType::F64 => "f64",
+		}.into()
+	}
+
+	functions.iter().map(|function| {
+		let name = function.name();
+		let (name, version) = match name.rfind("_version_") {
+			Some(pos) => match name[pos + "_version_".len()..].parse() {
+				Ok(version) => (&name[..pos], version),
+				Err(_) => (name, 1),
+			},
+			None => (name, 1),
+		};
+		let signature = function.signature();
+
+		HostFunction {
+			name: name.into(),
+			version,
+			args: signature.args.iter().cloned().map(value_type).collect(),
+			return_value: signature.return_value.map(value_type),
+		}
+	}).collect()
+}
+
 impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for System<B> {
 	fn system_name(&self) -> Result<String> {
 		Ok(self.info.impl_name.clone())
@@ -111,6 +146,10 @@ impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for Sy
 		Ok(self.info.properties.clone())
 	}
 
+	fn system_host_functions(&self) -> Result<Vec<HostFunction>> {
+		Ok(self.info.host_functions.clone())
+	}
+
 	fn system_health(&self) -> Receiver<Health> {
 		let (tx, rx) = oneshot::channel();
 		let _ = self.send_back.unbounded_send(Request::Health(tx));
@@ -126,6 +126,12 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 			chain_name: "testchain".into(),
 			properties: Default::default(),
 			chain_type: Default::default(),
+			host_functions: vec![HostFunction {
+				name: "ext_test".into(),
+				version: 2,
+				args: vec!["i32".into()],
+				return_value: None,
+			}],
 		},
 		tx,
 		sc_rpc_api::DenyUnsafe::No
@@ -177,6 +183,32 @@ fn system_type_works() {
 	);
 }
 
+#[test]
+fn system_host_functions_works() {
+	assert_eq!(
+		api(None).system_host_functions().unwrap(),
+		vec![HostFunction {
+			name: "ext_test".into(),
+			version: 2,
+			args: vec!["i32".into()],
+			return_value: None,
+		}],
+	);
+}
+
+#[test]
+fn host_functions_manifest_splits_versions() {
+	use sc_executor::sp_wasm_interface::HostFunctions;
+
+	let manifest = host_functions_manifest(&sp_io::SubstrateHostFunctions::host_functions());
+	assert!(manifest.contains(&HostFunction {
+		name: "ext_storage_get".into(),
+		version: 1,
+		args: vec!["i64".into()],
+		return_value: Some("i64".into()),
+	}));
+}
+
 #[test]
 fn system_health() {
 	assert_matches!(
@@ -24,7 +24,7 @@ use crate::{
 	config::{Configuration, KeystoreConfig, PrometheusConfig},
 };
 use sc_client_api::{
-	light::RemoteBlockchain, ForkBlocks, BadBlocks, UsageProvider, ExecutorProvider,
+	light::RemoteBlockchain, ForkBlocks, BadBlocks, UsageProvider, ExecutorProvider, CallExecutor,
 };
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sc_chain_spec::get_extension;
@@ -51,6 +51,7 @@ use sp_runtime::traits::{
 use sp_api::{ProvideRuntimeApi, CallApiAt};
 use sc_executor::{NativeExecutor, NativeExecutionDispatch, RuntimeInfo};
 use std::sync::Arc;
+use codec::Encode;
 use wasm_timer::SystemTime;
 use sc_telemetry::{
 	telemetry,
@@ -59,7 +60,7 @@ use sc_telemetry::{
 	TelemetryHandle,
 	SUBSTRATE_INFO,
 };
-use sp_transaction_pool::MaintainedTransactionPool;
+use sp_transaction_pool::{MaintainedTransactionPool, TransactionPoolMaintainer};
 use prometheus_endpoint::Registry;
 use sc_client_db::{Backend, DatabaseSettings};
 use sp_core::traits::{
@@ -341,13 +342,21 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		};
 
 
-		let backend = new_db_backend(db_config)?;
+		let backend = new_db_backend(db_config, config.prometheus_registry())?;
+
+		let verification_pool = sc_client_api::VerificationPool::adaptive()
+			.map_err(|e| Error::Other(format!("Failed to spawn signature verification threads: {}", e)))?;
 
 		let extensions = sc_client_api::execution_extensions::ExecutionExtensions::new(
 			config.execution_strategies.clone(),
 			Some(keystore_container.sync_keystore()),
-			sc_offchain::OffchainDb::factory_from_backend(&*backend),
-		);
+			sc_offchain::OffchainDb::factory_from_backend_with_quota(
+				&*backend,
+				config.offchain_worker.storage_quota.clone(),
+			),
+		)
+			.with_verification_pool(verification_pool)
+			.with_chain_properties(encoded_chain_properties(config));
 
 		let client = new_client(
 			backend.clone(),
@@ -363,6 +372,7 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 				offchain_worker_enabled : config.offchain_worker.enabled,
 				offchain_indexing_api: config.offchain_worker.indexing_enabled,
 				wasm_runtime_overrides: config.wasm_runtime_overrides.clone(),
+				wasm_runtime_substitutes: chain_spec.code_substitutes().to_vec(),
 			},
 		)?;
 
@@ -419,10 +429,13 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 	);
 	let on_demand = Arc::new(sc_network::config::OnDemand::new(fetch_checker));
 	let backend = sc_light::new_light_backend(light_blockchain);
+	let extensions = sc_client_api::execution_extensions::ExecutionExtensions::default()
+		.with_chain_properties(encoded_chain_properties(config));
 	let client = Arc::new(light::new_light(
 		backend.clone(),
 		config.chain_spec.as_storage_builder(),
 		executor,
+		extensions,
 		Box::new(task_manager.spawn_handle()),
 		config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 		telemetry,
@@ -431,15 +444,27 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 	Ok((client, backend, keystore_container, task_manager, on_demand))
 }
 
+/// SCALE-encode the properties of the configured chain spec for `ChainPropertiesExt`.
+fn encoded_chain_properties(config: &Configuration) -> Vec<u8> {
+	config.chain_spec.properties()
+		.into_iter()
+		.map(|(name, value)| (name, value.to_string()))
+		.collect::<Vec<_>>()
+		.encode()
+}
+
 /// Create an instance of default DB-backend backend.
 pub fn new_db_backend<Block>(
 	settings: DatabaseSettings,
+	prometheus_registry: Option<&Registry>,
 ) -> Result<Arc<Backend<Block>>, sp_blockchain::Error> where
 	Block: BlockT,
 {
 	const CANONICALIZATION_DELAY: u64 = 4096;
 
-	Ok(Arc::new(Backend::new(settings, CANONICALIZATION_DELAY)?))
+	Ok(Arc::new(
+		Backend::new(settings, CANONICALIZATION_DELAY)?.with_io_metrics(prometheus_registry)
+	))
 }
 
 /// Create an instance of client backed by given backend.
@@ -467,7 +492,13 @@ pub fn new_client<E, Block, RA>(
 		Block: BlockT,
 		E: CodeExecutor + RuntimeInfo,
 {
-	let executor = crate::client::LocalCallExecutor::new(backend.clone(), executor, spawn_handle, config.clone())?;
+	let executor = crate::client::LocalCallExecutor::new(
+		backend.clone(),
+		executor,
+		spawn_handle,
+		config.clone(),
+		prometheus_registry.as_ref(),
+	)?;
 	Ok(crate::client::Client::new(
 		backend,
 		executor,
@@ -497,6 +528,11 @@ pub struct SpawnTasksParams<'a, TBl: BlockT, TCl, TExPool, TRpc, Backend> {
 	pub on_demand: Option<Arc<OnDemand<TBl>>>,
 	/// A shared transaction pool.
 	pub transaction_pool: Arc<TExPool>,
+	/// An optional maintainer replacing the maintenance of `transaction_pool` on chain events.
+	///
+	/// Use a `ChainedMaintainer` starting with the transaction pool to extend its maintenance
+	/// instead.
+	pub transaction_pool_maintainer: Option<Box<dyn TransactionPoolMaintainer<Block = TBl>>>,
 	/// A RPC extension builder. Use `NoopRpcExtensionBuilder` if you just want to pass in the
 	/// extensions directly.
 	pub rpc_extensions_builder: Box<dyn RpcExtensionBuilder<Output = TRpc> + Send>,
@@ -521,10 +557,22 @@ pub fn build_offchain_workers<TBl, TCl>(
 ) -> Option<Arc<sc_offchain::OffchainWorkers<TCl, TBl>>>
 	where
 		TBl: BlockT,
-		TCl: Send + Sync + ProvideRuntimeApi<TBl> + BlockchainEvents<TBl> + 'static,
+		TCl: Send + Sync + ProvideRuntimeApi<TBl> + BlockchainEvents<TBl> + ExecutorProvider<TBl>
+			+ 'static,
 		<TCl as ProvideRuntimeApi<TBl>>::Api: sc_offchain::OffchainWorkerApi<TBl>,
 {
-	let offchain_workers = Some(Arc::new(sc_offchain::OffchainWorkers::new(client.clone())));
+	let mut offchain_workers = sc_offchain::OffchainWorkers::with_http_config(
+		client.clone(),
+		config.prometheus_registry(),
+		&config.offchain_worker.http,
+	)
+		.with_execution_limits(config.offchain_worker.limits.clone())
+		.with_retries(config.offchain_worker.retries.clone())
+		.with_egress_policy(config.offchain_worker.egress.clone());
+	if let Some(db) = client.execution_extensions().offchain_db() {
+		offchain_workers = offchain_workers.with_failure_store(db);
+	}
+	let offchain_workers = Some(Arc::new(offchain_workers));
 
 	// Inform the offchain worker about new imported blocks
 	if let Some(offchain) = offchain_workers.clone() {
@@ -536,6 +584,7 @@ pub fn build_offchain_workers<TBl, TCl>(
 				offchain,
 				Clone::clone(&spawn_handle),
 				network.clone(),
+				config.offchain_worker.trigger,
 			)
 		);
 	}
@@ -572,6 +621,7 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 		backend,
 		keystore,
 		transaction_pool,
+		transaction_pool_maintainer,
 		rpc_extensions_builder,
 		remote_blockchain,
 		network,
@@ -604,9 +654,14 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 	let spawn_handle = task_manager.spawn_handle();
 
 	// Inform the tx pool about imported and finalized blocks.
+	let transaction_pool_maintainer: Box<dyn TransactionPoolMaintainer<Block = TBl>> =
+		match transaction_pool_maintainer {
+			Some(maintainer) => maintainer,
+			None => Box::new(transaction_pool.clone()),
+		};
 	spawn_handle.spawn(
 		"txpool-notifications",
-		sc_transaction_pool::notification_future(client.clone(), transaction_pool.clone()),
+		sc_transaction_pool::notification_future(client.clone(), transaction_pool_maintainer),
 	);
 
 	spawn_handle.spawn(
@@ -762,6 +817,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 		impl_version: config.impl_version.clone(),
 		properties: config.chain_spec.properties(),
 		chain_type: config.chain_spec.chain_type(),
+		host_functions: system::host_functions_manifest(client.executor().host_functions()),
 	};
 
 	let task_executor = sc_rpc::SubscriptionTaskExecutor::new(spawn_handle);
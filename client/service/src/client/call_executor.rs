@@ -25,14 +25,21 @@ use sp_state_machine::{
 	self, OverlayedChanges, Ext, ExecutionManager, StateMachine, ExecutionStrategy,
 	backend::Backend as _, StorageProof,
 };
-use sc_executor::{RuntimeVersion, RuntimeInfo, NativeVersion};
+use sc_executor::{RuntimeVersion, RuntimeInfo, NativeVersion, sp_wasm_interface::Function};
 use sp_externalities::Extensions;
 use sp_core::{
 	NativeOrEncoded, NeverNativeValue, traits::{CodeExecutor, SpawnNamed, RuntimeCode},
 };
 use sp_api::{ProofRecorder, InitializeBlock, StorageTransactionCache};
 use sc_client_api::{backend, call_executor::CallExecutor};
-use super::{client::ClientConfig, wasm_override::WasmOverride};
+use prometheus_endpoint::{
+	register, exponential_buckets, prometheus::HistogramTimer, CounterVec, HistogramOpts,
+	HistogramVec, Opts, PrometheusError, Registry, U64,
+};
+use super::{
+	client::ClientConfig, wasm_override::WasmOverride,
+	wasm_substitutes::{WasmSubstitute, WasmSubstitutes},
+};
 
 /// Call executor that executes methods locally, querying all required
 /// data from local backend.
@@ -40,8 +47,48 @@ pub struct LocalCallExecutor<B, E> {
 	backend: Arc<B>,
 	executor: E,
 	wasm_override: Option<WasmOverride<E>>,
+	wasm_substitutes: WasmSubstitutes,
 	spawn_handle: Box<dyn SpawnNamed>,
 	client_config: ClientConfig,
+	metrics: Option<Metrics>,
+}
+
+/// Prometheus metrics of the [`LocalCallExecutor`].
+#[derive(Clone)]
+struct Metrics {
+	runtime_calls: CounterVec<U64>,
+	runtime_call_time: HistogramVec,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			runtime_calls: register(
+				CounterVec::new(
+					Opts::new(
+						"sub_runtime_calls_total",
+						"Total number of runtime calls made by the call executor",
+					),
+					&["method"],
+				)?,
+				registry,
+			)?,
+			runtime_call_time: register(
+				HistogramVec::new(
+					HistogramOpts {
+						common_opts: Opts::new(
+							"sub_runtime_call_time_seconds",
+							"Time taken to execute a runtime call, by runtime API method",
+						),
+						buckets: exponential_buckets(0.001, 4.0, 9)
+							.expect("function parameters are constant and always valid; qed"),
+					},
+					&["method"],
+				)?,
+				registry,
+			)?,
+		})
+	}
 }
 
 impl<B, E> LocalCallExecutor<B, E>
@@ -54,24 +101,114 @@ where
 		executor: E,
 		spawn_handle: Box<dyn SpawnNamed>,
 		client_config: ClientConfig,
+		prometheus_registry: Option<&Registry>,
 	) -> sp_blockchain::Result<Self> {
 		let wasm_override = client_config.wasm_runtime_overrides
 			.as_ref()
 			.map(|p| WasmOverride::new(p.clone(), executor.clone()))
 			.transpose()?;
+		let wasm_substitutes = WasmSubstitutes::new(
+			client_config.wasm_runtime_substitutes.iter().cloned(),
+			&executor,
+		)?;
+
+		let metrics = prometheus_registry.and_then(|registry|
+			Metrics::register(registry)
+				.map_err(|err| log::warn!("Failed to register call executor metrics: {}", err))
+				.ok()
+		);
 
 		Ok(LocalCallExecutor {
 			backend,
 			executor,
 			wasm_override,
+			wasm_substitutes,
 			spawn_handle,
 			client_config,
+			metrics,
+		})
+	}
+
+	/// Report a runtime call of `method` to the metrics, if enabled.
+	///
+	/// The returned timer records the execution time of the call when dropped.
+	fn report_call(&self, method: &str) -> Option<HistogramTimer> {
+		self.metrics.as_ref().map(|metrics| {
+			metrics.runtime_calls.with_label_values(&[method]).inc();
+			metrics.runtime_call_time.with_label_values(&[method]).start_timer()
+		})
+	}
+
+	/// Returns the code substitute of the chain spec applying to the given `BlockId`, if any.
+	///
+	/// A substitute applies to the block it is declared for and its descendants, as long as the
+	/// on-chain runtime has the same spec version as the substitute.
+	fn code_substitute<Block>(
+		&self,
+		id: &BlockId<Block>,
+	) -> sp_blockchain::Result<Option<&WasmSubstitute>>
+	where
+		Block: BlockT,
+		B: backend::Backend<Block>,
+	{
+		if self.wasm_substitutes.is_empty() {
+			return Ok(None)
+		}
+
+		let blockchain = self.backend.blockchain();
+		let hash = blockchain.expect_block_hash_from_id(id)?;
+		let spec_version = || Ok(self.onchain_runtime_version(id)?.spec_version);
+		self.wasm_substitutes.get(hash.as_ref(), spec_version, |from| {
+			let from = match Block::Hash::decode(&mut &from[..]) {
+				Ok(from) => from,
+				Err(_) => return Ok(false),
+			};
+			if from == hash {
+				return Ok(true)
+			}
+			if blockchain.number(from)?.is_none() {
+				return Ok(false)
+			}
+			let ancestor = sp_blockchain::lowest_common_ancestor(blockchain, from, hash)?;
+			Ok(ancestor.hash == from)
 		})
 	}
 
+	/// Returns the version of the on-chain runtime at the given `BlockId`.
+	fn onchain_runtime_version<Block>(
+		&self,
+		id: &BlockId<Block>,
+	) -> sp_blockchain::Result<RuntimeVersion>
+	where
+		Block: BlockT,
+		B: backend::Backend<Block>,
+	{
+		let mut overlay = OverlayedChanges::default();
+		let changes_trie_state = backend::changes_tries_state_at_block(
+			id,
+			self.backend.changes_trie_storage(),
+		)?;
+		let state = self.backend.state_at(*id)?;
+		let mut cache = StorageTransactionCache::<Block, B::State>::default();
+		let mut ext = Ext::new(
+			&mut overlay,
+			&mut cache,
+			&state,
+			changes_trie_state,
+			None,
+		);
+		let state_runtime_code = sp_state_machine::backend::BackendRuntimeCode::new(&state);
+		let runtime_code = state_runtime_code.runtime_code()
+			.map_err(sp_blockchain::Error::RuntimeCode)?;
+		self.executor.runtime_version(&mut ext, &runtime_code)
+			.map_err(|e| sp_blockchain::Error::VersionInvalid(format!("{:?}", e)).into())
+	}
+
 	/// Check if local runtime code overrides are enabled and one is available
 	/// for the given `BlockId`. If yes, return it; otherwise return the same
 	/// `RuntimeCode` instance that was passed.
+	///
+	/// Code substitutes of the chain spec take precedence over local overrides.
 	fn check_override<'a, Block>(
 		&'a self,
 		onchain_code: RuntimeCode<'a>,
@@ -81,10 +218,14 @@ where
 		Block: BlockT,
 		B: backend::Backend<Block>,
 	{
+		if let Some(substitute) = self.code_substitute(id)? {
+			return Ok(substitute.runtime_code(onchain_code.heap_pages))
+		}
+
 		let code = self.wasm_override
 			.as_ref()
 			.map::<sp_blockchain::Result<Option<RuntimeCode>>, _>(|o| {
-				let spec = self.runtime_version(id)?.spec_version;
+				let spec = self.onchain_runtime_version(id)?.spec_version;
 				Ok(o.get(&spec, onchain_code.heap_pages))
 			})
 			.transpose()?
@@ -101,8 +242,10 @@ impl<B, E> Clone for LocalCallExecutor<B, E> where E: Clone {
 			backend: self.backend.clone(),
 			executor: self.executor.clone(),
 			wasm_override: self.wasm_override.clone(),
+			wasm_substitutes: self.wasm_substitutes.clone(),
 			spawn_handle: self.spawn_handle.clone(),
 			client_config: self.client_config.clone(),
+			metrics: self.metrics.clone(),
 		}
 	}
 }
@@ -125,6 +268,8 @@ where
 		strategy: ExecutionStrategy,
 		extensions: Option<Extensions>,
 	) -> sp_blockchain::Result<Vec<u8>> {
+		let _timer = self.report_call(method);
+
 		let mut changes = OverlayedChanges::default();
 		let changes_trie = backend::changes_tries_state_at_block(
 			id, self.backend.changes_trie_storage()
@@ -178,6 +323,8 @@ where
 		recorder: &Option<ProofRecorder<Block>>,
 		extensions: Option<Extensions>,
 	) -> Result<NativeOrEncoded<R>, sp_blockchain::Error> where ExecutionManager<EM>: Clone {
+		let _timer = self.report_call(method);
+
 		match initialize_block {
 			InitializeBlock::Do(ref init_block)
 				if init_block.borrow().as_ref().map(|id| id != at).unwrap_or(true) => {
@@ -257,25 +404,10 @@ where
 	}
 
 	fn runtime_version(&self, id: &BlockId<Block>) -> sp_blockchain::Result<RuntimeVersion> {
-		let mut overlay = OverlayedChanges::default();
-		let changes_trie_state = backend::changes_tries_state_at_block(
-			id,
-			self.backend.changes_trie_storage(),
-		)?;
-		let state = self.backend.state_at(*id)?;
-		let mut cache = StorageTransactionCache::<Block, B::State>::default();
-		let mut ext = Ext::new(
-			&mut overlay,
-			&mut cache,
-			&state,
-			changes_trie_state,
-			None,
-		);
-		let state_runtime_code = sp_state_machine::backend::BackendRuntimeCode::new(&state);
-		let runtime_code = state_runtime_code.runtime_code()
-			.map_err(sp_blockchain::Error::RuntimeCode)?;
-		self.executor.runtime_version(&mut ext, &runtime_code)
-			.map_err(|e| sp_blockchain::Error::VersionInvalid(format!("{:?}", e)).into())
+		match self.code_substitute(id)? {
+			Some(substitute) => Ok(substitute.version().clone()),
+			None => self.onchain_runtime_version(id),
+		}
 	}
 
 	fn prove_at_trie_state<S: sp_state_machine::TrieBackendStorage<HashFor<Block>>>(
@@ -303,6 +435,10 @@ where
 	fn native_runtime_version(&self) -> Option<&NativeVersion> {
 		Some(self.executor.native_version())
 	}
+
+	fn host_functions(&self) -> &[&'static dyn Function] {
+		self.executor.host_functions()
+	}
 }
 
 impl<B, E, Block> sp_version::GetRuntimeVersion<Block> for LocalCallExecutor<B, E>
@@ -352,6 +488,7 @@ mod tests {
 			offchain_worker_enabled: false,
 			offchain_indexing_api: false,
 			wasm_runtime_overrides: None,
+			wasm_runtime_substitutes: Vec::new(),
 		};
 
 		// client is used for the convenience of creating and inserting the genesis block.
@@ -376,8 +513,10 @@ mod tests {
 			backend: backend.clone(),
 			executor,
 			wasm_override: Some(overrides),
+			wasm_substitutes: Default::default(),
 			spawn_handle: Box::new(TaskExecutor::new()),
 			client_config,
+			metrics: None,
 		};
 
 		let check = call_executor.check_override(onchain_code, &BlockId::Number(Default::default()))
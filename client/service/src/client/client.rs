@@ -191,6 +191,8 @@ pub struct ClientConfig {
 	pub offchain_indexing_api: bool,
 	/// Path where WASM files exist to override the on-chain WASM.
 	pub wasm_runtime_overrides: Option<PathBuf>,
+	/// Runtime codes used instead of the on-chain code from some blocks.
+	pub wasm_runtime_substitutes: Vec<sc_chain_spec::CodeSubstitute>,
 }
 
 /// Create a client with the explicitly provided backend.
@@ -212,7 +214,13 @@ pub fn new_with_backend<B, E, Block, S, RA>(
 		Block: BlockT,
 		B: backend::LocalBackend<Block> + 'static,
 {
-	let call_executor = LocalCallExecutor::new(backend.clone(), executor, spawn_handle, config.clone())?;
+	let call_executor = LocalCallExecutor::new(
+		backend.clone(),
+		executor,
+		spawn_handle,
+		config.clone(),
+		prometheus_registry.as_ref(),
+	)?;
 	let extensions = ExecutionExtensions::new(
 		Default::default(),
 		keystore,
@@ -1665,10 +1673,15 @@ impl<B, E, Block, RA> CallApiAt<Block> for Client<B, E, Block, RA> where
 		let core_api = params.core_api;
 		let at = params.at;
 
-		let (manager, extensions) = self.execution_extensions.manager_and_extensions(
+		let (manager, mut extensions) = self.execution_extensions.manager_and_extensions(
 			at,
 			params.context,
 		);
+		params.extensions.copy_into(&mut extensions);
+		let manager = match params.execution_strategy {
+			Some(strategy) => strategy.get_manager(),
+			None => manager,
+		};
 
 		self.executor.contextual_call::<_, fn(_,_) -> _,_,_>(
 			|| core_api
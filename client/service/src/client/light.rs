@@ -29,7 +29,7 @@ use sp_blockchain::Result as ClientResult;
 use prometheus_endpoint::Registry;
 
 use super::{call_executor::LocalCallExecutor, client::{Client, ClientConfig}};
-use sc_client_api::light::Storage as BlockchainStorage;
+use sc_client_api::{execution_extensions::ExecutionExtensions, light::Storage as BlockchainStorage};
 use sc_light::{Backend, GenesisCallExecutor};
 
 /// Create an instance of light client.
@@ -37,6 +37,7 @@ pub fn new_light<B, S, RA, E>(
 	backend: Arc<Backend<S, HashFor<B>>>,
 	genesis_storage: &dyn BuildStorage,
 	code_executor: E,
+	execution_extensions: ExecutionExtensions<B>,
 	spawn_handle: Box<dyn SpawnNamed>,
 	prometheus_registry: Option<Registry>,
 	telemetry: Option<TelemetryHandle>,
@@ -60,7 +61,8 @@ pub fn new_light<B, S, RA, E>(
 		backend.clone(),
 		code_executor,
 		spawn_handle.clone(),
-		ClientConfig::default()
+		ClientConfig::default(),
+		prometheus_registry.as_ref(),
 	)?;
 	let executor = GenesisCallExecutor::new(backend.clone(), local_executor);
 	Client::new(
@@ -69,7 +71,7 @@ pub fn new_light<B, S, RA, E>(
 		genesis_storage,
 		Default::default(),
 		Default::default(),
-		Default::default(),
+		execution_extensions,
 		prometheus_registry,
 		telemetry,
 		ClientConfig::default(),
@@ -71,7 +71,7 @@ impl WasmBlob {
 }
 
 /// Make a hash out of a byte string using the default rust hasher
-fn make_hash<K: std::hash::Hash + ?Sized>(val: &K) -> Vec<u8> {
+pub(crate) fn make_hash<K: std::hash::Hash + ?Sized>(val: &K) -> Vec<u8> {
 	let mut state = DefaultHasher::new();
 	val.hash(&mut state);
 	state.finish().to_le_bytes().to_vec()
@@ -0,0 +1,223 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # WASM substitutes
+//!
+//! Runtime codes declared in the chain spec that are used instead of the on-chain code, e.g. to
+//! work around a runtime that can't execute some blocks anymore.
+//!
+//! A substitute applies to the runtime calls made on top of the state of a given block and of its
+//! descendants, as long as the on-chain runtime has the spec version of the substitute. As a block
+//! is imported on top of the state of its parent, the children of the given block are the first
+//! blocks executed with the substitute, and the first runtime upgrade ends the substitution.
+
+use std::{borrow::Cow, sync::Arc};
+use lru::LruCache;
+use parking_lot::Mutex;
+use sc_chain_spec::CodeSubstitute;
+use sc_executor::RuntimeInfo;
+use sp_blockchain::Result;
+use sp_core::{hexdisplay::HexDisplay, traits::{FetchRuntimeCode, RuntimeCode}};
+use sp_state_machine::BasicExternalities;
+use sp_version::RuntimeVersion;
+use super::wasm_override::make_hash;
+
+/// Number of blocks for which the substitute to use is remembered.
+const DECISIONS_CACHE_SIZE: usize = 256;
+
+/// A substitute along with the hash and the version of its code.
+#[derive(Debug)]
+pub struct WasmSubstitute {
+	code: Vec<u8>,
+	hash: Vec<u8>,
+	/// Encoded hash of the first block on top of which the code is used.
+	block_hash: Vec<u8>,
+	version: RuntimeVersion,
+}
+
+impl WasmSubstitute {
+	/// Returns the code of the substitute.
+	pub fn runtime_code(&self, heap_pages: Option<u64>) -> RuntimeCode {
+		RuntimeCode {
+			code_fetcher: self,
+			hash: self.hash.clone(),
+			heap_pages,
+		}
+	}
+
+	/// Returns the version of the runtime of the substitute.
+	pub fn version(&self) -> &RuntimeVersion {
+		&self.version
+	}
+}
+
+impl FetchRuntimeCode for WasmSubstitute {
+	fn fetch_runtime_code<'a>(&'a self) -> Option<Cow<'a, [u8]>> {
+		Some(self.code.as_slice().into())
+	}
+}
+
+/// The WASM substitutes of a chain.
+#[derive(Clone)]
+pub struct WasmSubstitutes {
+	substitutes: Arc<Vec<WasmSubstitute>>,
+	/// Index of the substitute used on top of the most recently used blocks, by encoded hash.
+	decisions: Arc<Mutex<LruCache<Vec<u8>, Option<usize>>>>,
+}
+
+impl WasmSubstitutes {
+	/// Create the substitutes from the ones declared in the chain spec.
+	///
+	/// Fails if the version of one of the substitutes can't be read.
+	pub fn new<E: RuntimeInfo>(
+		substitutes: impl IntoIterator<Item = CodeSubstitute>,
+		executor: &E,
+	) -> Result<Self> {
+		let substitutes = substitutes.into_iter()
+			.map(|substitute| {
+				let code = substitute.code.0;
+				let hash = make_hash(&code);
+				let mut substitute = WasmSubstitute {
+					code,
+					hash,
+					block_hash: substitute.block_hash.0,
+					version: Default::default(),
+				};
+				substitute.version = executor
+					.runtime_version(
+						&mut BasicExternalities::default(),
+						&substitute.runtime_code(None),
+					)
+					.map_err(|e| sp_blockchain::Error::VersionInvalid(format!(
+						"Invalid code substitute for block 0x{}: {:?}",
+						HexDisplay::from(&substitute.block_hash),
+						e,
+					)))?;
+				Ok(substitute)
+			})
+			.collect::<Result<_>>()?;
+		Ok(Self {
+			substitutes: Arc::new(substitutes),
+			decisions: Arc::new(Mutex::new(LruCache::new(DECISIONS_CACHE_SIZE))),
+		})
+	}
+
+	/// Returns true if there is no substitute.
+	pub fn is_empty(&self) -> bool {
+		self.substitutes.is_empty()
+	}
+
+	/// Gets the substitute used on top of the block with the given encoded hash, if any.
+	///
+	/// `spec_version` returns the spec version of the on-chain runtime of the block, and
+	/// `applies_from` whether the substitutes used from the block with the given encoded hash
+	/// apply to the block, i.e. whether it is this block or one of its descendants. If several
+	/// substitutes apply, the first one declared is used.
+	///
+	/// Both are costly, so the substitute used on top of a block is remembered.
+	pub fn get(
+		&self,
+		block_hash: &[u8],
+		spec_version: impl FnOnce() -> Result<u32>,
+		mut applies_from: impl FnMut(&[u8]) -> Result<bool>,
+	) -> Result<Option<&WasmSubstitute>> {
+		let block_hash = block_hash.to_vec();
+		let cached = self.decisions.lock().get(&block_hash).copied();
+		let index = match cached {
+			Some(index) => index,
+			None => {
+				let spec_version = spec_version()?;
+				let mut index = None;
+				for (i, substitute) in self.substitutes.iter().enumerate() {
+					if substitute.version.spec_version == spec_version
+						&& applies_from(&substitute.block_hash)?
+					{
+						index = Some(i);
+						break
+					}
+				}
+				self.decisions.lock().put(block_hash, index);
+				index
+			},
+		};
+		Ok(index.map(|index| &self.substitutes[index]))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_executor::{NativeExecutor, WasmExecutionMethod};
+	use substrate_test_runtime_client::LocalExecutor;
+
+	#[test]
+	fn substitutes_apply_to_their_spec_version_and_blocks() {
+		let executor =
+			NativeExecutor::<LocalExecutor>::new(WasmExecutionMethod::Interpreted, Some(128), 1);
+		let code = substrate_test_runtime::wasm_binary_unwrap().to_vec();
+		let substitutes = WasmSubstitutes::new(
+			vec![CodeSubstitute { block_hash: vec![1; 32].into(), code: code.clone().into() }],
+			&executor,
+		).unwrap();
+		let spec_version = substrate_test_runtime::VERSION.spec_version;
+		let fetch = |substitute: Option<&WasmSubstitute>| substitute
+			.and_then(|s| s.runtime_code(None).fetch_runtime_code().map(|c| c.into_owned()));
+
+		let applies: fn(&[u8]) -> Result<bool> = |hash| Ok(hash == &[1; 32][..]);
+		let doesnt_apply: fn(&[u8]) -> Result<bool> = |_| Ok(false);
+		let get = |block: u8, spec_version: u32, applies_from: fn(&[u8]) -> Result<bool>| fetch(
+			substitutes.get(&[block; 32], || Ok(spec_version), applies_from).unwrap()
+		);
+		assert_eq!(get(2, spec_version, applies), Some(code));
+		assert_eq!(get(3, spec_version, doesnt_apply), None);
+		assert_eq!(get(4, spec_version + 1, applies), None);
+	}
+
+	#[test]
+	fn substitute_used_on_top_of_a_block_is_remembered() {
+		let executor =
+			NativeExecutor::<LocalExecutor>::new(WasmExecutionMethod::Interpreted, Some(128), 1);
+		let code = substrate_test_runtime::wasm_binary_unwrap().to_vec();
+		let substitutes = WasmSubstitutes::new(
+			vec![CodeSubstitute { block_hash: vec![1; 32].into(), code: code.into() }],
+			&executor,
+		).unwrap();
+		let spec_version = substrate_test_runtime::VERSION.spec_version;
+
+		let mut checks = 0;
+		for _ in 0..3 {
+			let substitute = substitutes.get(
+				&[2; 32],
+				|| { checks += 1; Ok(spec_version) },
+				|hash| Ok(hash == &[1; 32][..]),
+			).unwrap();
+			assert!(substitute.is_some());
+		}
+		assert_eq!(checks, 1);
+	}
+
+	#[test]
+	fn invalid_substitutes_are_rejected() {
+		let executor =
+			NativeExecutor::<LocalExecutor>::new(WasmExecutionMethod::Interpreted, Some(128), 1);
+		let substitutes = vec![
+			CodeSubstitute { block_hash: vec![1; 32].into(), code: vec![1].into() },
+		];
+		assert!(WasmSubstitutes::new(substitutes, &executor).is_err());
+	}
+}
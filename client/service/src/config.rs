@@ -29,6 +29,13 @@ use sc_client_api::execution_extensions::ExecutionStrategies;
 
 use std::{io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
 pub use sc_transaction_pool::txpool::Options as TransactionPoolOptions;
+pub use sc_offchain::{
+	HttpClientConfig, ExecutionLimits as OffchainExecutionLimits, WhenBusy as OffchainWhenBusy,
+	StorageQuota as OffchainStorageQuota, QuotaScope as OffchainQuotaScope,
+	PruningPolicy as OffchainPruningPolicy, Trigger as OffchainTrigger,
+	RetryPolicy as OffchainRetryPolicy, EgressPolicy as OffchainEgressPolicy,
+	HostPattern as OffchainHostPattern, TlsConfig as OffchainTlsConfig,
+};
 use sc_chain_spec::ChainSpec;
 use sp_core::crypto::SecretString;
 pub use sc_telemetry::TelemetryEndpoints;
@@ -167,6 +174,18 @@ pub struct OffchainWorkerConfig {
 	pub enabled: bool,
 	/// allow writes from the runtime to the offchain worker database.
 	pub indexing_enabled: bool,
+	/// Configuration of the HTTP client used by the offchain workers.
+	pub http: HttpClientConfig,
+	/// Limits on the offchain worker executions.
+	pub limits: OffchainExecutionLimits,
+	/// Quota on the persistent storage written by the offchain workers, if any.
+	pub storage_quota: Option<OffchainStorageQuota>,
+	/// Which blocks the offchain workers run on.
+	pub trigger: OffchainTrigger,
+	/// Retries of the failed offchain worker executions.
+	pub retries: OffchainRetryPolicy,
+	/// Hosts the offchain workers may reach.
+	pub egress: OffchainEgressPolicy,
 }
 
 /// Configuration of the Prometheus endpoint.
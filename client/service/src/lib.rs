@@ -43,7 +43,6 @@ use std::task::Poll;
 use futures::{Future, FutureExt, Stream, StreamExt, stream, compat::*};
 use sc_network::{NetworkStatus, network_state::NetworkState, PeerId};
 use log::{warn, debug, error};
-use codec::{Encode, Decode};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use parity_util_mem::MallocSizeOf;
@@ -501,6 +500,8 @@ where
 	H: std::hash::Hash + Eq + sp_runtime::traits::Member + sp_runtime::traits::MaybeSerialize,
 	E: IntoPoolError + From<sp_transaction_pool::error::Error>,
 {
+	// Ready transactions are yielded by decreasing priority, while still coming after the
+	// transactions they depend on.
 	pool.ready()
 		.filter(|t| t.is_propagable())
 		.map(|t| {
@@ -528,27 +529,28 @@ where
 		self.pool.hash_of(transaction)
 	}
 
+	fn hash_of_encoded(&self, encoded: &[u8]) -> H {
+		self.pool.hash_of_encoded(encoded)
+	}
+
 	fn import(
 		&self,
 		transaction: B::Extrinsic,
+		encoded: &[u8],
 	) -> TransactionImportFuture {
 		if !self.imports_external_transactions {
 			debug!("Transaction rejected");
 			Box::pin(futures::future::ready(TransactionImport::None));
 		}
 
-		let encoded = transaction.encode();
-		let uxt = match Decode::decode(&mut &encoded[..]) {
-			Ok(uxt) => uxt,
-			Err(e) => {
-				debug!("Transaction invalid: {:?}", e);
-				return Box::pin(futures::future::ready(TransactionImport::Bad));
-			}
-		};
-
 		let best_block_id = BlockId::hash(self.client.info().best_hash);
 
-		let import_future = self.pool.submit_one(&best_block_id, sp_transaction_pool::TransactionSource::External, uxt);
+		let import_future = self.pool.submit_one_encoded(
+			&best_block_id,
+			sp_transaction_pool::TransactionSource::External,
+			transaction,
+			encoded,
+		);
 		Box::pin(async move {
 			match import_future.await {
 				Ok(_) => TransactionImport::NewGood,
@@ -39,7 +39,9 @@ use substrate_test_runtime_client::{
 };
 use sp_api::{InitializeBlock, StorageTransactionCache, ProofRecorder};
 use sp_consensus::BlockOrigin;
-use sc_executor::{NativeExecutor, WasmExecutionMethod, RuntimeVersion, NativeVersion};
+use sc_executor::{
+	NativeExecutor, WasmExecutionMethod, RuntimeVersion, NativeVersion, sp_wasm_interface::Function,
+};
 use sp_core::{H256, NativeOrEncoded, testing::TaskExecutor};
 use sc_client_api::{
 	blockchain::Info, backend::NewBlockState, Backend as ClientBackend, ProofProvider,
@@ -256,6 +258,10 @@ impl CallExecutor<Block> for DummyCallExecutor {
 	fn native_runtime_version(&self) -> Option<&NativeVersion> {
 		unreachable!()
 	}
+
+	fn host_functions(&self) -> &[&'static dyn Function] {
+		unreachable!()
+	}
 }
 
 fn local_executor() -> NativeExecutor<substrate_test_runtime_client::LocalExecutor> {
@@ -21,6 +21,7 @@
 //! For a more full-featured pool, have a look at the `pool` module.
 
 use std::{
+	cmp::Reverse,
 	collections::HashSet,
 	fmt,
 	hash,
@@ -28,16 +29,17 @@ use std::{
 };
 
 use log::{trace, debug, warn};
+use wasm_timer::Instant;
 use serde::Serialize;
 use sp_core::hexdisplay::HexDisplay;
-use sp_runtime::traits::Member;
+use sp_runtime::{Percent, traits::Member};
 use sp_runtime::transaction_validity::{
 	TransactionTag as Tag,
 	TransactionLongevity as Longevity,
 	TransactionPriority as Priority,
 	TransactionSource as Source,
 };
-use sp_transaction_pool::{error, PoolStatus, InPoolTransaction};
+use sp_transaction_pool::{error, PoolStatus, InPoolTransaction, TransactionQueue};
 
 use crate::future::{FutureTransactions, WaitingTransaction};
 use crate::ready::ReadyTransactions;
@@ -248,6 +250,12 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 	}
 
+	/// Sets the priority increase over the transactions it replaces that a transaction needs to
+	/// replace them.
+	pub fn set_replacement_bump(&mut self, replacement_bump: Percent) {
+		self.ready.set_replacement_bump(replacement_bump);
+	}
+
 	/// Temporary enables future transactions, runs closure and then restores
 	/// `reject_future_transactions` flag back to previous value.
 	///
@@ -383,6 +391,20 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.future.all()
 	}
 
+	/// Returns all transactions, ready ones first, with their queue and time of import.
+	///
+	/// The ready transactions are in the order they would be included in a block.
+	pub fn inspect(&self) -> Vec<(Arc<Transaction<Hash, Ex>>, TransactionQueue, Instant)> {
+		let mut all = self.ready.all()
+			.into_iter()
+			.map(|(tx, imported_at)| (tx, TransactionQueue::Ready, imported_at))
+			.collect::<Vec<_>>();
+		all.extend(self.future.all_with_import_time()
+			.map(|(tx, imported_at)| (tx.clone(), TransactionQueue::Future, imported_at))
+		);
+		all
+	}
+
 	/// Returns pool transactions given list of hashes.
 	///
 	/// Includes both ready and future pool. For every hash in the `hashes`
@@ -407,18 +429,23 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	///
 	/// Removes and returns worst transactions from the queues and all transactions that depend on them.
 	/// Technically the worst transaction should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the transaction that occupies the pool for the longest time.
+	/// We use a simplified approach to remove the transaction with the lowest priority, and the
+	/// newest one among those sharing that priority, so that an incoming transaction only pushes
+	/// out transactions with a strictly lower priority.
 	pub fn enforce_limits(&mut self, ready: &Limit, future: &Limit) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		let mut removed = vec![];
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
-			// find the worst transaction
+			// find the worst transaction: the one with the lowest priority, and the newest one
+			// among those sharing that priority
 			let minimal = self.ready
 				.fold(|minimal, current| {
 					let transaction = &current.transaction;
 					match minimal {
 						None => Some(transaction.clone()),
-						Some(ref tx) if tx.insertion_id > transaction.insertion_id => {
+						Some(ref tx) if (tx.transaction.priority, Reverse(tx.insertion_id)) >
+							(transaction.transaction.priority, Reverse(transaction.insertion_id)) =>
+						{
 							Some(transaction.clone())
 						},
 						other => other,
@@ -433,12 +460,15 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 
 		while future.is_exceeded(self.future.len(), self.future.bytes()) {
-			// find the worst transaction
+			// find the worst transaction: the one with the lowest priority, and the newest one
+			// among those sharing that priority
 			let minimal = self.future
 				.fold(|minimal, current| {
 					match minimal {
 						None => Some(current.clone()),
-						Some(ref tx) if tx.imported_at > current.imported_at => {
+						Some(ref tx) if (tx.transaction.priority, Reverse(tx.imported_at)) >
+							(current.transaction.priority, Reverse(current.imported_at)) =>
+						{
 							Some(current.clone())
 						},
 						other => other,
@@ -474,6 +504,14 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.future.clear()
 	}
 
+	/// Removes and returns the transactions waiting in the future queue since `time` or earlier.
+	pub fn remove_future_imported_before(
+		&mut self,
+		time: &Instant,
+	) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		self.future.remove_imported_before(time)
+	}
+
 	/// Prunes transactions that provide given list of tags.
 	///
 	/// This will cause all transactions that provide these tags to be removed from the pool,
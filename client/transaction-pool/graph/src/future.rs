@@ -219,6 +219,15 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		removed
 	}
 
+	/// Removes and returns the future transactions imported no later than `time`.
+	pub fn remove_imported_before(&mut self, time: &Instant) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let hashes = self.waiting.iter()
+			.filter(|(_, tx)| tx.imported_at <= *time)
+			.map(|(hash, _)| hash.clone())
+			.collect::<Vec<_>>();
+		self.remove(&hashes)
+	}
+
 	/// Fold a list of future transactions to compute a single value.
 	pub fn fold<R, F: FnMut(Option<R>, &WaitingTransaction<Hash, Ex>) -> Option<R>>(&mut self, f: F) -> Option<R> {
 		self.waiting
@@ -231,6 +240,13 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		self.waiting.values().map(|waiting| &*waiting.transaction)
 	}
 
+	/// Returns all future transactions with their time of import.
+	pub fn all_with_import_time(
+		&self,
+	) -> impl Iterator<Item=(&Arc<Transaction<Hash, Ex>>, Instant)> {
+		self.waiting.values().map(|waiting| (&waiting.transaction, waiting.imported_at))
+	}
+
 	/// Removes and returns all future transactions.
 	pub fn clear(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		self.wanted_tags.clear();
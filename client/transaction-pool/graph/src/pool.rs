@@ -19,12 +19,14 @@
 use std::{
 	collections::HashMap,
 	sync::Arc,
+	time::Duration,
 };
 
 use crate::{base_pool as base, watcher::Watcher};
 
 use futures::Future;
 use sp_runtime::{
+	Percent,
 	generic::BlockId,
 	traits::{self, SaturatedConversion, Block as BlockT},
 	transaction_validity::{
@@ -94,6 +96,13 @@ pub trait ChainApi: Send + Sync {
 	/// Returns hash and encoding length of the extrinsic.
 	fn hash_and_length(&self, uxt: &ExtrinsicFor<Self>) -> (ExtrinsicHash<Self>, usize);
 
+	/// Returns hash and length of an extrinsic given its SCALE encoding.
+	///
+	/// Must agree with `hash_and_length` for the decoded extrinsic.
+	fn hash_and_length_encoded(&self, encoded: &[u8]) -> (ExtrinsicHash<Self>, usize) {
+		(<traits::HashFor<Self::Block> as traits::Hash>::hash(encoded), encoded.len())
+	}
+
 	/// Returns a block body given the block id.
 	fn block_body(&self, at: &BlockId<Self::Block>) -> Self::BodyFuture;
 }
@@ -107,6 +116,16 @@ pub struct Options {
 	pub future: base::Limit,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// How long transactions are banned for, once rejected as invalid or stale.
+	pub ban_time: Duration,
+	/// Number of bans kept when the list of banned transactions overflows.
+	pub ban_capacity: usize,
+	/// Priority increase a transaction needs over the transactions providing the same tags to
+	/// replace them. With a zero bump, any strictly higher priority is enough.
+	pub replacement_bump: Percent,
+	/// How long transactions may wait in the future queue before being dropped.
+	/// With `None`, they are kept until promoted, stale or evicted by the limits.
+	pub max_future_duration: Option<Duration>,
 }
 
 impl Default for Options {
@@ -121,6 +140,10 @@ impl Default for Options {
 				total_bytes: 1 * 1024 * 1024,
 			},
 			reject_future_transactions: false,
+			ban_time: crate::rotator::BAN_TIME,
+			ban_capacity: crate::rotator::EXPECTED_SIZE,
+			replacement_bump: Percent::zero(),
+			max_future_duration: None,
 		}
 	}
 }
@@ -193,6 +216,29 @@ impl<B: ChainApi> Pool<B> {
 		res.expect("One extrinsic passed; one result returned; qed")
 	}
 
+	/// Imports one unverified extrinsic to the pool, with its hash and length already known.
+	///
+	/// Used for extrinsics received in encoded form, which then don't need to be encoded again.
+	pub async fn submit_one_hashed(
+		&self,
+		at: &BlockId<B::Block>,
+		source: TransactionSource,
+		xt: ExtrinsicFor<B>,
+		hash_and_length: (ExtrinsicHash<B>, usize),
+	) -> Result<ExtrinsicHash<B>, B::Error> {
+		let block_number = self.resolve_block_number(at)?;
+		let (_, tx) = self.verify_one(
+			at,
+			block_number,
+			source,
+			xt,
+			Some(hash_and_length),
+			CheckBannedBeforeVerify::Yes,
+		).await;
+		let res = self.validated_pool.submit(std::iter::once(tx)).pop();
+		res.expect("One extrinsic passed; one result returned; qed")
+	}
+
 	/// Import a single extrinsic and starts to watch their progress in the pool.
 	pub async fn submit_and_watch(
 		&self,
@@ -206,6 +252,7 @@ impl<B: ChainApi> Pool<B> {
 			block_number,
 			source,
 			xt,
+			None,
 			CheckBannedBeforeVerify::Yes,
 		).await;
 		self.validated_pool.submit_and_watch(tx)
@@ -364,6 +411,11 @@ impl<B: ChainApi> Pool<B> {
 		self.validated_pool.api().hash_and_length(xt).0
 	}
 
+	/// Returns transaction hash given its SCALE encoding.
+	pub fn hash_of_encoded(&self, encoded: &[u8]) -> ExtrinsicHash<B> {
+		self.validated_pool.api().hash_and_length_encoded(encoded).0
+	}
+
 	/// Resolves block number by id.
 	fn resolve_block_number(&self, at: &BlockId<B::Block>) -> Result<NumberFor<B>, B::Error> {
 		self.validated_pool.api().block_id_to_number(at)
@@ -383,22 +435,26 @@ impl<B: ChainApi> Pool<B> {
 
 		let res = futures::future::join_all(
 			xts.into_iter()
-				.map(|(source, xt)| self.verify_one(at, block_number, source, xt, check))
+				.map(|(source, xt)| self.verify_one(at, block_number, source, xt, None, check))
 		).await.into_iter().collect::<HashMap<_, _>>();
 
 		Ok(res)
 	}
 
 	/// Returns future that validates single transaction at given block.
+	///
+	/// Hash and length of the transaction are computed from `xt` unless given.
 	async fn verify_one(
 		&self,
 		block_id: &BlockId<B::Block>,
 		block_number: NumberFor<B>,
 		source: TransactionSource,
 		xt: ExtrinsicFor<B>,
+		hash_and_length: Option<(ExtrinsicHash<B>, usize)>,
 		check: CheckBannedBeforeVerify,
 	) -> (ExtrinsicHash<B>, ValidatedTransactionFor<B>) {
-		let (hash, bytes) = self.validated_pool.api().hash_and_length(&xt);
+		let (hash, bytes) = hash_and_length
+			.unwrap_or_else(|| self.validated_pool.api().hash_and_length(&xt));
 
 		let ignore_banned = matches!(check, CheckBannedBeforeVerify::No);
 		if let Err(err) = self.validated_pool.check_is_known(&hash, ignore_banned) {
@@ -614,6 +670,30 @@ mod tests {
 		assert_eq!(pool.validated_pool().ready().map(|v| v.hash).collect::<Vec<_>>(), vec![hash]);
 	}
 
+	#[test]
+	fn should_import_transaction_hashed_from_its_encoding() {
+		// given
+		let pool = pool();
+		let uxt = uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		});
+		let encoded = uxt.encode();
+
+		// when
+		let hash_and_length = pool.validated_pool().api().hash_and_length_encoded(&encoded);
+		let hash = block_on(
+			pool.submit_one_hashed(&BlockId::Number(0), SOURCE, uxt.clone(), hash_and_length),
+		).unwrap();
+
+		// then
+		assert_eq!(hash, pool.hash_of(&uxt));
+		assert_eq!(hash, pool.hash_of_encoded(&encoded));
+		assert_eq!(pool.validated_pool().ready().map(|v| v.hash).collect::<Vec<_>>(), vec![hash]);
+	}
+
 	#[test]
 	fn should_reject_if_temporarily_banned() {
 		// given
@@ -788,6 +868,79 @@ mod tests {
 		assert!(!pool.validated_pool.rotator().is_banned(&hash2));
 	}
 
+	#[test]
+	fn should_evict_lowest_priority_first() {
+		// given
+		let limit = Limit {
+			count: 1,
+			total_bytes: 1000,
+		};
+
+		let options = Options {
+			ready: limit.clone(),
+			future: limit.clone(),
+			..Default::default()
+		};
+
+		let pool = Pool::new(options, true.into(), TestApi::default().into());
+
+		let hash1 = block_on(
+			pool.submit_one(&BlockId::Number(0), SOURCE, Extrinsic::IncludeData(vec![1])),
+		).unwrap();
+		assert_eq!(pool.validated_pool().status().ready, 1);
+
+		// when
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		}))).unwrap_err();
+
+		// then
+		assert_matches!(err, error::Error::ImmediatelyDropped);
+		assert_eq!(pool.validated_pool().status().ready, 1);
+		assert!(pool.validated_pool().ready_by_hash(&hash1).is_some());
+	}
+
+	#[test]
+	fn should_not_evict_transactions_with_the_same_priority() {
+		// given
+		let limit = Limit {
+			count: 1,
+			total_bytes: 1000,
+		};
+
+		let options = Options {
+			ready: limit.clone(),
+			future: limit.clone(),
+			..Default::default()
+		};
+
+		let pool = Pool::new(options, true.into(), TestApi::default().into());
+
+		let hash1 = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		}))).unwrap();
+		assert_eq!(pool.validated_pool().status().ready, 1);
+
+		// when
+		let err = block_on(pool.submit_one(&BlockId::Number(1), SOURCE, uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(2)),
+			to: AccountId::from_h256(H256::from_low_u64_be(1)),
+			amount: 4,
+			nonce: 1,
+		}))).unwrap_err();
+
+		// then
+		assert_matches!(err, error::Error::ImmediatelyDropped);
+		assert_eq!(pool.validated_pool().status().ready, 1);
+		assert!(pool.validated_pool().ready_by_hash(&hash1).is_some());
+	}
+
 	#[test]
 	fn should_error_if_reject_immediately() {
 		// given
@@ -998,12 +1151,7 @@ mod tests {
 			assert_eq!(pool.validated_pool().status().ready, 1);
 
 			// when
-			let xt = uxt(Transfer {
-				from: AccountId::from_h256(H256::from_low_u64_be(2)),
-				to: AccountId::from_h256(H256::from_low_u64_be(1)),
-				amount: 4,
-				nonce: 1,
-			});
+			let xt = Extrinsic::IncludeData(vec![1]);
 			block_on(pool.submit_one(&BlockId::Number(1), SOURCE, xt)).unwrap();
 			assert_eq!(pool.validated_pool().status().ready, 1);
 
@@ -1013,6 +1161,71 @@ mod tests {
 			assert_eq!(stream.next(), Some(TransactionStatus::Dropped));
 		}
 
+		#[test]
+		fn should_trigger_dropped_for_old_future_transactions() {
+			// given
+			let options = Options {
+				max_future_duration: Some(Duration::from_secs(0)),
+				..Default::default()
+			};
+
+			let pool = Pool::new(options, true.into(), TestApi::default().into());
+
+			let xt = uxt(Transfer {
+				from: AccountId::from_h256(H256::from_low_u64_be(1)),
+				to: AccountId::from_h256(H256::from_low_u64_be(2)),
+				amount: 5,
+				nonce: 3,
+			});
+			let watcher = block_on(pool.submit_and_watch(&BlockId::Number(0), SOURCE, xt)).unwrap();
+			block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt(Transfer {
+				from: AccountId::from_h256(H256::from_low_u64_be(2)),
+				to: AccountId::from_h256(H256::from_low_u64_be(1)),
+				amount: 5,
+				nonce: 0,
+			}))).unwrap();
+			assert_eq!(pool.validated_pool().status().future, 1);
+
+			// when
+			pool.validated_pool().clear_stale(&BlockId::Number(0)).unwrap();
+
+			// then
+			assert_eq!(pool.validated_pool().status().ready, 1);
+			assert_eq!(pool.validated_pool().status().future, 0);
+			let mut stream = futures::executor::block_on_stream(watcher.into_stream());
+			assert_eq!(stream.next(), Some(TransactionStatus::Future));
+			assert_eq!(stream.next(), Some(TransactionStatus::Dropped));
+		}
+
+		#[test]
+		fn should_accept_old_future_transactions_again_after_dropping_them() {
+			// given
+			let options = Options {
+				max_future_duration: Some(Duration::from_secs(0)),
+				..Default::default()
+			};
+
+			let pool = Pool::new(options, true.into(), TestApi::default().into());
+
+			let xt = uxt(Transfer {
+				from: AccountId::from_h256(H256::from_low_u64_be(1)),
+				to: AccountId::from_h256(H256::from_low_u64_be(2)),
+				amount: 5,
+				nonce: 3,
+			});
+			let hash = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, xt.clone())).unwrap();
+			pool.validated_pool().clear_stale(&BlockId::Number(0)).unwrap();
+			assert_eq!(pool.validated_pool().status().future, 0);
+			assert!(!pool.validated_pool().is_banned(&hash));
+
+			// when
+			let resubmitted = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, xt)).unwrap();
+
+			// then
+			assert_eq!(resubmitted, hash);
+			assert_eq!(pool.validated_pool().status().future, 1);
+		}
+
 		#[test]
 		fn should_handle_pruning_in_the_middle_of_import() {
 			// given
@@ -25,11 +25,12 @@ use std::{
 
 use serde::Serialize;
 use log::trace;
-use sp_runtime::traits::Member;
+use sp_runtime::{Percent, traits::Member};
 use sp_runtime::transaction_validity::{
 	TransactionTag as Tag,
 };
 use sp_transaction_pool::error;
+use wasm_timer::Instant;
 
 use crate::{
 	base_pool::Transaction,
@@ -89,6 +90,8 @@ pub struct ReadyTx<Hash, Ex> {
 	/// Some transactions might be already pruned from the queue,
 	/// so when we compute ready set we may consider this transactions ready earlier.
 	pub requires_offset: usize,
+	/// Time of import to the pool, including the time spent in the future queue.
+	pub imported_at: Instant,
 }
 
 impl<Hash: Clone, Ex> Clone for ReadyTx<Hash, Ex> {
@@ -97,6 +100,7 @@ impl<Hash: Clone, Ex> Clone for ReadyTx<Hash, Ex> {
 			transaction: self.transaction.clone(),
 			unlocks: self.unlocks.clone(),
 			requires_offset: self.requires_offset,
+			imported_at: self.imported_at,
 		}
 	}
 }
@@ -118,6 +122,9 @@ pub struct ReadyTransactions<Hash: hash::Hash + Eq, Ex> {
 	ready: TrackedMap<Hash, ReadyTx<Hash, Ex>>,
 	/// Best transactions that are ready to be included to the block without any other previous transaction.
 	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	/// Priority increase a transaction needs over the transactions it replaces.
+	#[ignore_malloc_size_of = "no heap allocation"]
+	replacement_bump: Percent,
 }
 
 impl<Hash, Ex> tracked_map::Size for ReadyTx<Hash, Ex> {
@@ -133,11 +140,20 @@ impl<Hash: hash::Hash + Eq, Ex> Default for ReadyTransactions<Hash, Ex> {
 			provided_tags: Default::default(),
 			ready: Default::default(),
 			best: Default::default(),
+			replacement_bump: Percent::zero(),
 		}
 	}
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
+	/// Sets the priority increase over the transactions it replaces that a transaction needs to
+	/// replace them.
+	///
+	/// With a zero bump, any strictly higher priority is enough.
+	pub fn set_replacement_bump(&mut self, replacement_bump: Percent) {
+		self.replacement_bump = replacement_bump;
+	}
+
 	/// Borrows a map of tags that are provided by transactions in this queue.
 	pub fn provided_tags(&self) -> &HashMap<Tag, Hash> {
 		&self.provided_tags
@@ -180,6 +196,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 		self.insertion_id += 1;
 		let insertion_id = self.insertion_id;
 		let hash = tx.transaction.hash.clone();
+		let imported_at = tx.imported_at;
 		let transaction = tx.transaction;
 
 		let (replaced, unlocks) = self.replace_previous(&transaction)?;
@@ -222,6 +239,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			transaction,
 			unlocks,
 			requires_offset,
+			imported_at,
 		});
 
 		Ok(replaced)
@@ -235,6 +253,18 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			.fold(None, f)
 	}
 
+	/// Returns all ready transactions with their time of import, in the order of [`Self::get`].
+	pub fn all(&self) -> Vec<(Arc<Transaction<Hash, Ex>>, Instant)> {
+		let best = self.get().collect::<Vec<_>>();
+		let ready = self.ready.read();
+		best.into_iter()
+			.filter_map(|tx| {
+				let imported_at = ready.get(&tx.hash)?.imported_at;
+				Some((tx, imported_at))
+			})
+			.collect()
+	}
+
 	/// Returns true if given hash is part of the queue.
 	pub fn contains(&self, hash: &Hash) -> bool {
 		self.ready.read().contains_key(hash)
@@ -441,7 +471,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			};
 
 			// bail - the transaction has too low priority to replace the old ones
-			if old_priority >= tx.priority {
+			let bump = self.replacement_bump * old_priority;
+			let required_priority = old_priority.saturating_add(bump);
+			if required_priority >= tx.priority {
 				return Err(error::Error::TooLowPriority { old: old_priority, new: tx.priority })
 			}
 
@@ -605,6 +637,29 @@ mod tests {
 		assert_eq!(ready.get().count(), 1);
 	}
 
+	#[test]
+	fn should_require_priority_bump_to_replace() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		ready.set_replacement_bump(Percent::from_percent(50));
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.priority = 10;
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.priority = 15;
+		import(&mut ready, tx1).unwrap();
+
+		// when
+		let err = import(&mut ready, tx2.clone()).unwrap_err();
+		tx2.priority = 16;
+		import(&mut ready, tx2).unwrap();
+
+		// then
+		assert!(matches!(err, error::Error::TooLowPriority { old: 10, new: 15 }));
+		assert_eq!(ready.get().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+	}
+
 	#[test]
 	fn should_replace_multiple_transactions_correctly() {
 		// given
@@ -685,6 +740,9 @@ mod tests {
 		assert_eq!(it.next(), Some(4));
 		assert_eq!(it.next(), Some(5));
 		assert_eq!(it.next(), None);
+
+		let all = ready.all().into_iter().map(|(tx, _)| tx.data[0]).collect::<Vec<_>>();
+		assert_eq!(all, vec![1, 2, 3, 4, 5]);
 	}
 
 	#[test]
@@ -32,8 +32,11 @@ use wasm_timer::Instant;
 
 use crate::base_pool::Transaction;
 
-/// Expected size of the banned extrinsics cache.
-const EXPECTED_SIZE: usize = 2048;
+/// Default expected size of the banned extrinsics cache.
+pub const EXPECTED_SIZE: usize = 2048;
+
+/// Default duration of a ban.
+pub const BAN_TIME: Duration = Duration::from_secs(60 * 30);
 
 /// Pool rotator is responsible to only keep fresh extrinsics in the pool.
 ///
@@ -42,14 +45,27 @@ const EXPECTED_SIZE: usize = 2048;
 pub struct PoolRotator<Hash> {
 	/// How long the extrinsic is banned for.
 	ban_time: Duration,
+	/// Number of bans kept when the cache overflows.
+	expected_size: usize,
 	/// Currently banned extrinsics.
 	banned_until: RwLock<HashMap<Hash, Instant>>,
 }
 
 impl<Hash: hash::Hash + Eq> Default for PoolRotator<Hash> {
 	fn default() -> Self {
+		Self::new(BAN_TIME, EXPECTED_SIZE)
+	}
+}
+
+impl<Hash: hash::Hash + Eq> PoolRotator<Hash> {
+	/// Creates a rotator banning extrinsics for `ban_time`.
+	///
+	/// Once more than twice `expected_size` extrinsics are banned, bans are dropped until only
+	/// `expected_size` remain.
+	pub fn new(ban_time: Duration, expected_size: usize) -> Self {
 		PoolRotator {
-			ban_time: Duration::from_secs(60 * 30),
+			ban_time,
+			expected_size,
 			banned_until: Default::default(),
 		}
 	}
@@ -69,8 +85,8 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 			banned.insert(hash, *now + self.ban_time);
 		}
 
-		if banned.len() > 2 * EXPECTED_SIZE {
-			while banned.len() > EXPECTED_SIZE {
+		if banned.len() > 2 * self.expected_size {
+			while banned.len() > self.expected_size {
 				if let Some(key) = banned.keys().next().cloned() {
 					banned.remove(&key);
 				}
@@ -91,6 +107,15 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 		true
 	}
 
+	/// Lifts the ban of the given set of hashes.
+	pub fn unban(&self, hashes: impl IntoIterator<Item=Hash>) {
+		let mut banned = self.banned_until.write();
+
+		for hash in hashes {
+			banned.remove(&hash);
+		}
+	}
+
 	/// Removes timed bans.
 	pub fn clear_timeouts(&self, now: &Instant) {
 		let mut banned = self.banned_until.write();
@@ -178,6 +203,21 @@ mod tests {
 		assert!(!rotator.is_banned(&hash));
 	}
 
+	#[test]
+	fn should_unban() {
+		// given
+		let (hash, tx) = tx();
+		let rotator = rotator();
+		assert!(rotator.ban_if_stale(&Instant::now(), 1, &tx));
+		assert!(rotator.is_banned(&hash));
+
+		// when
+		rotator.unban(iter::once(hash));
+
+		// then
+		assert!(!rotator.is_banned(&hash));
+	}
+
 	#[test]
 	fn should_garbage_collect() {
 		// given
@@ -19,7 +19,7 @@
 use std::{
 	collections::{HashSet, HashMap},
 	hash,
-	sync::Arc,
+	sync::{Arc, atomic::{AtomicUsize, Ordering}},
 };
 
 use crate::base_pool as base;
@@ -34,10 +34,11 @@ use sp_runtime::{
 	traits::{self, SaturatedConversion},
 	transaction_validity::{TransactionTag as Tag, ValidTransaction, TransactionSource},
 };
-use sp_transaction_pool::{error, PoolStatus};
+use sp_transaction_pool::{error, PoolStatus, TransactionDiagnostics};
 use wasm_timer::Instant;
 use futures::channel::mpsc::{channel, Sender};
 use retain_mut::RetainMut;
+use sp_utils::memory_usage::MemoryUsage;
 
 use crate::base_pool::PruneStatus;
 use crate::pool::{
@@ -117,6 +118,13 @@ pub struct ValidatedPool<B: ChainApi> {
 	>>,
 	import_notification_sinks: Mutex<Vec<Sender<ExtrinsicHash<B>>>>,
 	rotator: PoolRotator<ExtrinsicHash<B>>,
+	/// Memory used by the pool, reported for metrics only.
+	///
+	/// The limit is the sum of the ready and future byte limits, which are
+	/// enforced by `enforce_limits`.
+	memory_usage: MemoryUsage,
+	/// Number of transactions dropped or found invalid since last taken.
+	churn: AtomicUsize,
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -133,7 +141,13 @@ where
 impl<B: ChainApi> ValidatedPool<B> {
 	/// Create a new transaction pool.
 	pub fn new(options: Options, is_validator: IsValidator, api: Arc<B>) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
+		let mut base_pool = base::BasePool::new(options.reject_future_transactions);
+		base_pool.set_replacement_bump(options.replacement_bump);
+		let memory_usage = MemoryUsage::new(
+			"transaction_pool",
+			options.ready.total_bytes.saturating_add(options.future.total_bytes),
+		);
+		let rotator = PoolRotator::new(options.ban_time, options.ban_capacity);
 		ValidatedPool {
 			is_validator,
 			options,
@@ -141,7 +155,9 @@ impl<B: ChainApi> ValidatedPool<B> {
 			api,
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
-			rotator: Default::default(),
+			rotator,
+			memory_usage,
+			churn: AtomicUsize::new(0),
 		}
 	}
 
@@ -155,6 +171,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.rotator.is_banned(hash)
 	}
 
+	/// Lifts the ban of the given set of hashes, allowing them to enter the pool again.
+	pub fn unban(&self, hashes: impl IntoIterator<Item=ExtrinsicHash<B>>) {
+		self.rotator.unban(hashes)
+	}
+
 	/// A fast check before doing any further processing of a transaction, like validation.
 	///
 	/// If `ingore_banned` is `true`, it will not check if the transaction is banned.
@@ -244,6 +265,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 		let future_limit = &self.options.future;
 
 		log::debug!(target: "txpool", "Pool Status: {:?}", status);
+		self.memory_usage.report_usage(status.ready_bytes + status.future_bytes);
 		if ready_limit.is_exceeded(status.ready, status.ready_bytes)
 			|| future_limit.is_exceeded(status.future, status.future_bytes)
 		{
@@ -257,8 +279,10 @@ impl<B: ChainApi> ValidatedPool<B> {
 			// clean up the pool
 			let removed = {
 				let mut pool = self.pool.write();
-				let removed = pool.enforce_limits(ready_limit, future_limit)
-					.into_iter().map(|x| x.hash.clone()).collect::<HashSet<_>>();
+				let removed = pool.enforce_limits(ready_limit, future_limit);
+				self.churn.fetch_add(removed.len(), Ordering::Relaxed);
+				self.memory_usage.report_eviction(removed.iter().map(|x| x.bytes).sum());
+				let removed = removed.into_iter().map(|x| x.hash.clone()).collect::<HashSet<_>>();
 				// ban all removed transactions
 				self.rotator.ban(&Instant::now(), removed.iter().map(|x| x.clone()));
 				removed
@@ -266,6 +290,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 			if !removed.is_empty() {
 				log::debug!(target: "txpool", "Enforcing limits: {} dropped", removed.len());
 			}
+			self.report_memory_usage();
 
 			// run notifications
 			let mut listener = self.listener.write();
@@ -541,12 +566,43 @@ impl<B: ChainApi> ValidatedPool<B> {
 		// removing old transactions
 		self.remove_invalid(&to_remove);
 		self.remove_invalid(&futures_to_remove);
+		// dropping transactions that waited too long to be promoted
+		if let Some(imported_before) = self.options.max_future_duration
+			.and_then(|max| now.checked_sub(max))
+		{
+			self.drop_future_imported_before(&imported_before);
+		}
 		// clear banned transactions timeouts
 		self.rotator.clear_timeouts(&now);
 
 		Ok(())
 	}
 
+	/// Drops the transactions waiting in the future queue since `imported_before` or earlier.
+	///
+	/// The transactions are not banned, they may still become valid and be resubmitted.
+	fn drop_future_imported_before(&self, imported_before: &Instant) {
+		let dropped = self.pool.write().remove_future_imported_before(imported_before);
+		if dropped.is_empty() {
+			return
+		}
+
+		log::debug!(target: "txpool", "Dropping {} old future transactions", dropped.len());
+		self.churn.fetch_add(dropped.len(), Ordering::Relaxed);
+		self.report_memory_usage();
+
+		let mut listener = self.listener.write();
+		for tx in &dropped {
+			listener.dropped(&tx.hash, None);
+		}
+	}
+
+	/// Report the current memory usage of the pool.
+	fn report_memory_usage(&self) {
+		let status = self.pool.read().status();
+		self.memory_usage.report_usage(status.ready_bytes + status.future_bytes);
+	}
+
 	/// Get rotator reference.
 	#[cfg(test)]
 	pub fn rotator(&self) -> &PoolRotator<ExtrinsicHash<B>> {
@@ -596,6 +652,7 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.rotator.ban(&Instant::now(), hashes.iter().cloned());
 
 		let invalid = self.pool.write().remove_subtree(hashes);
+		self.churn.fetch_add(invalid.len(), Ordering::Relaxed);
 
 		log::debug!(target: "txpool", "Removed invalid transactions: {:?}", invalid);
 
@@ -612,6 +669,29 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().ready()
 	}
 
+	/// Returns diagnostics about all transactions in the pool, ready ones first.
+	pub fn inspect(&self) -> Vec<TransactionDiagnostics<ExtrinsicHash<B>>> {
+		let now = Instant::now();
+		self.pool.read().inspect()
+			.into_iter()
+			.map(|(tx, queue, imported_at)| TransactionDiagnostics {
+				hash: tx.hash.clone(),
+				priority: tx.priority,
+				valid_till: tx.valid_till,
+				requires: tx.requires.clone(),
+				provides: tx.provides.clone(),
+				bytes: tx.bytes,
+				time_in_pool: now.duration_since(imported_at).as_millis() as u64,
+				queue,
+			})
+			.collect()
+	}
+
+	/// Returns the number of transactions dropped or found invalid since the last call.
+	pub fn take_churn(&self) -> usize {
+		self.churn.swap(0, Ordering::Relaxed)
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
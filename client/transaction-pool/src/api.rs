@@ -21,11 +21,14 @@
 use std::{marker::PhantomData, pin::Pin, sync::Arc};
 use codec::{Decode, Encode};
 use futures::{
-	channel::oneshot, executor::{ThreadPool, ThreadPoolBuilder}, future::{Future, FutureExt, ready, Ready},
+	channel::oneshot, future::{Future, FutureExt, ready, Ready},
 };
+use lru::LruCache;
+use parking_lot::Mutex;
 
 use sc_client_api::{
 	blockchain::HeaderBackend, light::{Fetcher, RemoteCallRequest, RemoteBodyRequest}, BlockBackend,
+	VerificationPool,
 };
 use sp_runtime::{
 	generic::BlockId, traits::{self, Block as BlockT, BlockIdTo, Header as HeaderT, Hash as HashT},
@@ -37,19 +40,36 @@ use prometheus_endpoint::Registry as PrometheusRegistry;
 
 use crate::{metrics::{ApiMetrics, ApiMetricsExt}, error::{self, Error}};
 
+/// The number of blocks to remember the maximal extrinsic length of.
+const MAX_EXTRINSIC_LENGTHS_CACHE_SIZE: usize = 64;
+
 /// The transaction pool logic for full client.
-pub struct FullChainApi<Client, Block> {
+pub struct FullChainApi<Client, Block: BlockT> {
 	client: Arc<Client>,
-	pool: ThreadPool,
+	pool: VerificationPool,
 	_marker: PhantomData<Block>,
 	metrics: Option<Arc<ApiMetrics>>,
+	/// The maximal extrinsic length allowed by the runtime at recent blocks.
+	max_extrinsic_lengths: Arc<Mutex<LruCache<Block::Hash, u32>>>,
 }
 
-impl<Client, Block> FullChainApi<Client, Block> {
+impl<Client, Block: BlockT> FullChainApi<Client, Block> {
 	/// Create new transaction pool logic.
 	pub fn new(
 		client: Arc<Client>,
 		prometheus: Option<&PrometheusRegistry>,
+	) -> Self {
+		let pool = VerificationPool::new(1, 2, "txpool-verifier")
+			.expect("Failed to spawn verifier threads, that are critical for node operation.");
+
+		Self::with_verification_pool(client, prometheus, pool)
+	}
+
+	/// Create new transaction pool logic validating transactions on the given pool.
+	pub fn with_verification_pool(
+		client: Arc<Client>,
+		prometheus: Option<&PrometheusRegistry>,
+		pool: VerificationPool,
 	) -> Self {
 		let metrics = prometheus.map(ApiMetrics::register).and_then(|r| {
 			match r {
@@ -67,13 +87,12 @@ impl<Client, Block> FullChainApi<Client, Block> {
 
 		FullChainApi {
 			client,
-			pool: ThreadPoolBuilder::new()
-				.pool_size(2)
-				.name_prefix("txpool-verifier")
-				.create()
-				.expect("Failed to spawn verifier threads, that are critical for node operation."),
+			pool,
 			_marker: Default::default(),
 			metrics,
+			max_extrinsic_lengths: Arc::new(Mutex::new(
+				LruCache::new(MAX_EXTRINSIC_LENGTHS_CACHE_SIZE),
+			)),
 		}
 	}
 }
@@ -104,15 +123,22 @@ where
 	) -> Self::ValidationFuture {
 		let (tx, rx) = oneshot::channel();
 		let client = self.client.clone();
+		let max_extrinsic_lengths = self.max_extrinsic_lengths.clone();
 		let at = at.clone();
 
 		let metrics = self.metrics.clone();
 		metrics.report(|m| m.validations_scheduled.inc());
 
-		self.pool.spawn_ok(futures_diagnose::diagnose(
+		self.pool.spawn_transaction_validation(futures_diagnose::diagnose(
 			"validate-transaction",
 			async move {
-				let res = validate_transaction_blocking(&*client, &at, source, uxt);
+				let res = validate_transaction_blocking(
+					&*client,
+					&max_extrinsic_lengths,
+					&at,
+					source,
+					uxt,
+				);
 				if let Err(e) = tx.send(res) {
 					log::warn!("Unable to send a validate transaction result: {:?}", e);
 				}
@@ -156,6 +182,7 @@ where
 /// This method will call into the runtime to perform the validation.
 fn validate_transaction_blocking<Client, Block>(
 	client: &Client,
+	max_extrinsic_lengths: &Mutex<LruCache<Block::Hash, u32>>,
 	at: &BlockId<Block>,
 	source: TransactionSource,
 	uxt: sc_transaction_graph::ExtrinsicFor<FullChainApi<Client, Block>>,
@@ -169,12 +196,36 @@ where
 	sp_tracing::within_span!(sp_tracing::Level::TRACE, "validate_transaction";
 	{
 		let runtime_api = client.runtime_api();
-		let has_v2 = sp_tracing::within_span! { sp_tracing::Level::TRACE, "check_version";
-			runtime_api
-				.has_api_with::<dyn TaggedTransactionQueue<Block>, _>(&at, |v| v >= 2)
-				.unwrap_or_default()
+		let (has_v2, has_v3) = sp_tracing::within_span! { sp_tracing::Level::TRACE, "check_version";
+			let has_version = |min| runtime_api
+				.has_api_with::<dyn TaggedTransactionQueue<Block>, _>(&at, |v| v >= min)
+				.unwrap_or_default();
+			(has_version(2), has_version(3))
 		};
 
+		// Reject the transactions that no block can include before running the validation.
+		if has_v3 {
+			let hash = client.to_hash(at)
+				.map_err(|e| Error::BlockIdConversion(format!("{:?}", e)))?;
+			let cached = hash.as_ref()
+				.and_then(|hash| max_extrinsic_lengths.lock().get(hash).cloned());
+			let max_length = match cached {
+				Some(max_length) => max_length,
+				None => {
+					let max_length = runtime_api.max_extrinsic_length(&at)
+						.map_err(|e| Error::RuntimeApi(e.to_string()))?;
+					if let Some(hash) = hash {
+						max_extrinsic_lengths.lock().put(hash, max_length);
+					}
+					max_length
+				},
+			};
+			let length = uxt.encoded_size();
+			if length > max_length as usize {
+				return Err(Error::ExtrinsicTooLarge { length, max_length })
+			}
+		}
+
 		let res = sp_tracing::within_span!(
 			sp_tracing::Level::TRACE, "runtime::validate_transaction";
 		{
@@ -207,7 +258,7 @@ where
 		source: TransactionSource,
 		uxt: sc_transaction_graph::ExtrinsicFor<Self>,
 	) -> error::Result<TransactionValidity> {
-		validate_transaction_blocking(&*self.client, at, source, uxt)
+		validate_transaction_blocking(&*self.client, &self.max_extrinsic_lengths, at, source, uxt)
 	}
 }
 
@@ -38,6 +38,9 @@ pub enum Error {
 
 	#[error("Runtime error: {0}")]
 	RuntimeApi(String),
+
+	#[error("Extrinsic of {length} bytes exceeds the maximum length of {max_length} bytes")]
+	ExtrinsicTooLarge { length: usize, max_length: u32 },
 }
 
 
@@ -34,7 +34,7 @@ pub mod testing;
 pub use sc_transaction_graph as txpool;
 pub use crate::api::{FullChainApi, LightChainApi};
 
-use std::{collections::{HashMap, HashSet}, sync::Arc, pin::Pin, convert::TryInto};
+use std::{collections::{HashMap, HashSet}, sync::Arc, pin::Pin, convert::TryInto, time::Duration};
 use futures::{prelude::*, future::{self, ready}, channel::oneshot};
 use parking_lot::Mutex;
 
@@ -46,7 +46,7 @@ use sp_core::traits::SpawnNamed;
 use sp_transaction_pool::{
 	TransactionPool, PoolStatus, ImportNotificationStream, TxHash, TransactionFor,
 	TransactionStatusStreamFor, MaintainedTransactionPool, PoolFuture, ChainEvent,
-	TransactionSource,
+	TransactionSource, TransactionPoolMaintainer, TransactionDiagnostics,
 };
 use sc_transaction_graph::{ChainApi, ExtrinsicHash};
 use wasm_timer::Instant;
@@ -191,10 +191,18 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 		spawner: impl SpawnNamed,
 	) -> Self {
 		let pool = Arc::new(sc_transaction_graph::Pool::new(options, is_validator, pool_api.clone()));
+		let metrics = PrometheusMetrics::new(prometheus);
 		let (revalidation_queue, background_task) = match revalidation_type {
-			RevalidationType::Light => (revalidation::RevalidationQueue::new(pool_api.clone(), pool.clone()), None),
+			RevalidationType::Light => (
+				revalidation::RevalidationQueue::new(pool_api.clone(), pool.clone(), metrics.clone()),
+				None,
+			),
 			RevalidationType::Full => {
-				let (queue, background) = revalidation::RevalidationQueue::new_background(pool_api.clone(), pool.clone());
+				let (queue, background) = revalidation::RevalidationQueue::new_background(
+					pool_api.clone(),
+					pool.clone(),
+					metrics.clone(),
+				);
 				(queue, Some(background))
 			},
 		};
@@ -209,12 +217,15 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 			revalidation_queue: Arc::new(revalidation_queue),
 			revalidation_strategy: Arc::new(Mutex::new(
 				match revalidation_type {
-					RevalidationType::Light => RevalidationStrategy::Light(RevalidationStatus::NotScheduled),
+					RevalidationType::Light => RevalidationStrategy::Light(
+						RevalidationStatus::NotScheduled,
+						AdaptivePeriods::default(),
+					),
 					RevalidationType::Full => RevalidationStrategy::Always,
 				}
 			)),
 			ready_poll: Default::default(),
-			metrics: PrometheusMetrics::new(prometheus),
+			metrics,
 		}
 	}
 
@@ -245,9 +256,15 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		let pool = self.pool.clone();
 		let at = *at;
 
-		self.metrics.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
+		let metrics = self.metrics.clone();
+
+		metrics.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
 
-		async move { pool.submit_at(&at, source, xts).await }.boxed()
+		async move {
+			let result = pool.submit_at(&at, source, xts).await;
+			report_pool_status(&metrics, &pool);
+			result
+		}.boxed()
 	}
 
 	fn submit_one(
@@ -259,9 +276,37 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		let pool = self.pool.clone();
 		let at = *at;
 
-		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
+		let metrics = self.metrics.clone();
+
+		metrics.report(|metrics| metrics.submitted_transactions.inc());
+
+		async move {
+			let result = pool.submit_one(&at, source, xt).await;
+			report_pool_status(&metrics, &pool);
+			result
+		}.boxed()
+	}
+
+	fn submit_one_encoded(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+		encoded: &[u8],
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		let pool = self.pool.clone();
+		let at = *at;
+		let hash_and_length = pool.validated_pool().api().hash_and_length_encoded(encoded);
+
+		let metrics = self.metrics.clone();
+
+		metrics.report(|metrics| metrics.submitted_transactions.inc());
 
-		async move { pool.submit_one(&at, source, xt).await }.boxed()
+		async move {
+			let result = pool.submit_one_hashed(&at, source, xt, hash_and_length).await;
+			report_pool_status(&metrics, &pool);
+			result
+		}.boxed()
 	}
 
 	fn submit_and_watch(
@@ -273,18 +318,23 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		let at = *at;
 		let pool = self.pool.clone();
 
-		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
+		let metrics = self.metrics.clone();
+
+		metrics.report(|metrics| metrics.submitted_transactions.inc());
 
 		async move {
-			pool.submit_and_watch(&at, source, xt)
+			let result = pool.submit_and_watch(&at, source, xt)
 				.map(|result| result.map(|watcher| Box::new(watcher.into_stream()) as _))
-				.await
+				.await;
+			report_pool_status(&metrics, &pool);
+			result
 		}.boxed()
 	}
 
 	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
 		let removed = self.pool.validated_pool().remove_invalid(hashes);
 		self.metrics.report(|metrics| metrics.validations_invalid.inc_by(removed.len() as u64));
+		report_pool_status(&self.metrics, &self.pool);
 		removed
 	}
 
@@ -300,6 +350,10 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 		self.pool.hash_of(xt)
 	}
 
+	fn hash_of_encoded(&self, encoded: &[u8]) -> TxHash<Self> {
+		self.pool.hash_of_encoded(encoded)
+	}
+
 	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
 		self.pool.validated_pool().on_broadcasted(propagations)
 	}
@@ -329,6 +383,10 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
 		Box::new(self.pool.validated_pool().ready())
 	}
+
+	fn inspect(&self) -> Vec<TransactionDiagnostics<TxHash<Self>>> {
+		self.pool.validated_pool().inspect()
+	}
 }
 
 impl<Block, Client, Fetcher> LightPool<Block, Client, Fetcher>
@@ -369,7 +427,11 @@ where
 		spawner: impl SpawnNamed,
 		client: Arc<Client>,
 	) -> Arc<Self> {
-		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus));
+		let pool_api = match client.execution_extensions().verification_pool() {
+			Some(pool) => FullChainApi::with_verification_pool(client.clone(), prometheus, pool.clone()),
+			None => FullChainApi::new(client.clone(), prometheus),
+		};
+		let pool_api = Arc::new(pool_api);
 		let pool = Arc::new(Self::with_revalidation_type(
 			options, is_validator, pool_api, prometheus, RevalidationType::Full, spawner
 		));
@@ -445,7 +507,54 @@ enum RevalidationStatus<N> {
 
 enum RevalidationStrategy<N> {
 	Always,
-	Light(RevalidationStatus<N>),
+	Light(RevalidationStatus<N>, AdaptivePeriods),
+}
+
+/// Largest number of retracted blocks whose transactions are resubmitted after a re-org.
+const MAX_RESUBMITTED_RETRACTED_BLOCKS: usize = 64;
+
+/// Shortest time between two revalidations of a light pool.
+const MIN_REVALIDATION_TIME: Duration = Duration::from_secs(10);
+/// Longest time between two revalidations of a light pool.
+const MAX_REVALIDATION_TIME: Duration = Duration::from_secs(120);
+/// Smallest number of blocks between two revalidations of a light pool.
+const MIN_REVALIDATION_BLOCKS: u32 = 5;
+/// Largest number of blocks between two revalidations of a light pool.
+const MAX_REVALIDATION_BLOCKS: u32 = 40;
+/// Share of the pool, in percent, which has to be dropped or invalidated during a block for the
+/// churn to be considered high.
+const HIGH_CHURN_PERCENT: usize = 10;
+
+/// Revalidation periods of a light pool, adapting to the pool churn.
+///
+/// When many transactions are dropped or found invalid, the pool is likely to hold more of them
+/// and is revalidated sooner. When none are, revalidation backs off.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+struct AdaptivePeriods {
+	time: Duration,
+	blocks: u32,
+}
+
+impl Default for AdaptivePeriods {
+	fn default() -> Self {
+		AdaptivePeriods {
+			time: Duration::from_secs(60),
+			blocks: 20,
+		}
+	}
+}
+
+impl AdaptivePeriods {
+	/// Adapt the periods to `churn` transactions leaving a pool of `pool_size` in the last block.
+	fn update(&mut self, churn: usize, pool_size: usize) {
+		if churn * 100 >= pool_size.max(1) * HIGH_CHURN_PERCENT {
+			self.time = (self.time / 2).max(MIN_REVALIDATION_TIME);
+			self.blocks = (self.blocks / 2).max(MIN_REVALIDATION_BLOCKS);
+		} else if churn == 0 {
+			self.time = (self.time * 3 / 2).min(MAX_REVALIDATION_TIME);
+			self.blocks = (self.blocks * 3 / 2).min(MAX_REVALIDATION_BLOCKS);
+		}
+	}
 }
 
 struct RevalidationAction {
@@ -455,24 +564,29 @@ struct RevalidationAction {
 
 impl<N: Clone + Copy + AtLeast32Bit> RevalidationStrategy<N> {
 	pub fn clear(&mut self) {
-		if let Self::Light(status) = self {
+		if let Self::Light(status, _) = self {
 			status.clear()
 		}
 	}
 
+	/// Decide what to do on a new block, given the number of transactions that were dropped
+	/// or found invalid since the previous one and the current pool size.
 	pub fn next(
 		&mut self,
 		block: N,
-		revalidate_time_period: Option<std::time::Duration>,
-		revalidate_block_period: Option<N>,
+		churn: usize,
+		pool_size: usize,
 	) -> RevalidationAction {
 		match self {
-			Self::Light(status) => RevalidationAction {
-				revalidate: status.next_required(
-					block,
-					revalidate_time_period,
-					revalidate_block_period,
-				),
+			Self::Light(status, periods) => RevalidationAction {
+				revalidate: {
+					periods.update(churn, pool_size);
+					status.next_required(
+						block,
+						Some(periods.time),
+						Some(periods.blocks.into()),
+					)
+				},
 				resubmit: false,
 			},
 			Self::Always => RevalidationAction {
@@ -490,10 +604,12 @@ impl<N: Clone + Copy + AtLeast32Bit> RevalidationStatus<N> {
 	}
 
 	/// Returns true if revalidation is required.
+	///
+	/// A scheduled revalidation is brought forward if the periods got shorter since.
 	pub fn next_required(
 		&mut self,
 		block: N,
-		revalidate_time_period: Option<std::time::Duration>,
+		revalidate_time_period: Option<Duration>,
 		revalidate_block_period: Option<N>,
 	) -> bool {
 		match *self {
@@ -505,6 +621,16 @@ impl<N: Clone + Copy + AtLeast32Bit> RevalidationStatus<N> {
 				false
 			}
 			Self::Scheduled(revalidate_at_time, revalidate_at_block) => {
+				let revalidate_at_time = match (revalidate_at_time, revalidate_time_period) {
+					(Some(at), Some(period)) => Some(at.min(Instant::now() + period)),
+					(at, _) => at,
+				};
+				let revalidate_at_block = match (revalidate_at_block, revalidate_block_period) {
+					(Some(at), Some(period)) => Some(at.min(block + period)),
+					(at, _) => at,
+				};
+				*self = Self::Scheduled(revalidate_at_time, revalidate_at_block);
+
 				let is_required = revalidate_at_time.map(|at| Instant::now() >= at).unwrap_or(false)
 					|| revalidate_at_block.map(|at| block >= at).unwrap_or(false);
 				if is_required {
@@ -517,6 +643,14 @@ impl<N: Clone + Copy + AtLeast32Bit> RevalidationStatus<N> {
 	}
 }
 
+/// Report the size of the pool to Prometheus.
+fn report_pool_status<Api: ChainApi>(
+	metrics: &PrometheusMetrics,
+	pool: &sc_transaction_graph::Pool<Api>,
+) {
+	metrics.report(|metrics| metrics.report_status(&pool.validated_pool().status()));
+}
+
 /// Prune the known txs for the given block.
 async fn prune_known_txs_for_block<Block: BlockT, Api: ChainApi<Block = Block>>(
 	block_id: BlockId<Block>,
@@ -566,10 +700,12 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 					}
 				};
 
+				let churn = pool.validated_pool().take_churn();
+				let pool_size = pool.validated_pool().status();
 				let next_action = self.revalidation_strategy.lock().next(
 					block_number,
-					Some(std::time::Duration::from_secs(60)),
-					Some(20u32.into()),
+					churn,
+					pool_size.ready + pool_size.future,
 				);
 				let revalidation_strategy = self.revalidation_strategy.clone();
 				let revalidation_queue = self.revalidation_queue.clone();
@@ -613,11 +749,26 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 					metrics.report(
 						|metrics| metrics.block_transactions_pruned.inc_by(pruned_log.len() as u64)
 					);
+					report_pool_status(&metrics, &pool);
 
 					if let (true, Some(tree_route)) = (next_action.resubmit, tree_route) {
 						let mut resubmit_transactions = Vec::new();
+						let mut resubmitted_hashes = HashSet::<ExtrinsicHash<PoolApi>>::new();
+
+						// Only resubmit the transactions of the retracted blocks closest to the
+						// common ancestor, so that a deep re-org doesn't flood the pool.
+						let retracted = tree_route.retracted();
+						let skipped = retracted.len()
+							.saturating_sub(MAX_RESUBMITTED_RETRACTED_BLOCKS);
+						if skipped > 0 {
+							log::debug!(
+								target: "txpool",
+								"Not resubmitting the transactions of {} retracted blocks",
+								skipped,
+							);
+						}
 
-						for retracted in tree_route.retracted() {
+						for retracted in &retracted[skipped..] {
 							let hash = retracted.hash.clone();
 
 							let block_transactions = api.block_body(&BlockId::hash(hash))
@@ -635,7 +786,10 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 							resubmit_transactions.extend(
 								block_transactions.into_iter().filter(|tx| {
 									let tx_hash = pool.hash_of(&tx);
-									let contains = pruned_log.contains(&tx_hash);
+									// Skip the transactions included in the new best chain, and
+									// the ones already found in another retracted block.
+									let contains = pruned_log.contains(&tx_hash) ||
+										!resubmitted_hashes.insert(tx_hash.clone());
 
 									// need to count all transactions, not just filtered, here
 									resubmitted_to_report += 1;
@@ -673,6 +827,8 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 						}
 					}
 
+					report_pool_status(&metrics, &pool);
+
 					let extra_pool = pool.clone();
 					// After #5200 lands, this arguably might be moved to the
 					// handler of "all blocks notification".
@@ -708,15 +864,17 @@ impl<PoolApi, Block> MaintainedTransactionPool for BasicPool<PoolApi, Block>
 	}
 }
 
-/// Inform the transaction pool about imported and finalized blocks.
-pub async fn notification_future<Client, Pool, Block>(
+/// Inform the transaction pool, or any other maintainer, about imported and finalized blocks.
+///
+/// A transaction pool is maintained by passing an `Arc` of it.
+pub async fn notification_future<Client, Maintainer, Block>(
 	client: Arc<Client>,
-	txpool: Arc<Pool>
+	maintainer: Maintainer,
 )
 	where
 		Block: BlockT,
 		Client: sc_client_api::BlockchainEvents<Block>,
-		Pool: MaintainedTransactionPool<Block=Block>,
+		Maintainer: TransactionPoolMaintainer<Block=Block>,
 {
 	let import_stream = client.import_notification_stream()
 		.filter_map(|n| ready(n.try_into().ok()))
@@ -726,6 +884,6 @@ pub async fn notification_future<Client, Pool, Block>(
 		.fuse();
 
 	futures::stream::select(import_stream, finality_stream)
-		.for_each(|evt| txpool.maintain(evt))
+		.for_each(|evt| maintainer.maintain(evt))
 		.await
 }
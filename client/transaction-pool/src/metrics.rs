@@ -20,7 +20,10 @@
 
 use std::sync::Arc;
 
-use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use prometheus_endpoint::{
+	register, Counter, Gauge, Histogram, HistogramOpts, PrometheusError, Registry, U64,
+};
+use sp_transaction_pool::PoolStatus;
 
 #[derive(Clone, Default)]
 pub struct MetricsLink(Arc<Option<Metrics>>);
@@ -49,6 +52,11 @@ pub struct Metrics {
 	pub validations_invalid: Counter<U64>,
 	pub block_transactions_pruned: Counter<U64>,
 	pub block_transactions_resubmitted: Counter<U64>,
+	pub ready_transactions: Gauge<U64>,
+	pub ready_bytes: Gauge<U64>,
+	pub future_transactions: Gauge<U64>,
+	pub future_bytes: Gauge<U64>,
+	pub revalidation_duration: Histogram,
 }
 
 impl Metrics {
@@ -82,8 +90,51 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			ready_transactions: register(
+				Gauge::new(
+					"sub_txpool_ready_transactions",
+					"Number of transactions in the ready queue",
+				)?,
+				registry,
+			)?,
+			ready_bytes: register(
+				Gauge::new(
+					"sub_txpool_ready_bytes",
+					"Total size in bytes of the transactions in the ready queue",
+				)?,
+				registry,
+			)?,
+			future_transactions: register(
+				Gauge::new(
+					"sub_txpool_future_transactions",
+					"Number of transactions in the future queue",
+				)?,
+				registry,
+			)?,
+			future_bytes: register(
+				Gauge::new(
+					"sub_txpool_future_bytes",
+					"Total size in bytes of the transactions in the future queue",
+				)?,
+				registry,
+			)?,
+			revalidation_duration: register(
+				Histogram::with_opts(HistogramOpts::new(
+					"sub_txpool_revalidation_duration_seconds",
+					"Histogram of time taken to revalidate a batch of transactions",
+				))?,
+				registry,
+			)?,
 		})
 	}
+
+	/// Report the size of the pool.
+	pub fn report_status(&self, status: &PoolStatus) {
+		self.ready_transactions.set(status.ready as u64);
+		self.ready_bytes.set(status.ready_bytes as u64);
+		self.future_transactions.set(status.future as u64);
+		self.future_bytes.set(status.future_bytes as u64);
+	}
 }
 
 /// Transaction pool api Prometheus metrics.
@@ -20,6 +20,7 @@
 
 use std::{sync::Arc, pin::Pin, collections::{HashMap, HashSet, BTreeMap}};
 
+use crate::metrics::MetricsLink;
 use sc_transaction_graph::{ChainApi, Pool, ExtrinsicHash, NumberFor, ValidatedTransaction};
 use sp_runtime::traits::{Zero, SaturatedConversion};
 use sp_runtime::generic::BlockId;
@@ -28,6 +29,7 @@ use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender, TracingUnbounded
 
 use futures::prelude::*;
 use std::time::Duration;
+use wasm_timer::Instant;
 
 #[cfg(not(test))]
 const BACKGROUND_REVALIDATION_INTERVAL: Duration = Duration::from_millis(200);
@@ -51,6 +53,7 @@ struct RevalidationWorker<Api: ChainApi> {
 	best_block: NumberFor<Api>,
 	block_ordered: BTreeMap<NumberFor<Api>, HashSet<ExtrinsicHash<Api>>>,
 	members: HashMap<ExtrinsicHash<Api>, NumberFor<Api>>,
+	metrics: MetricsLink,
 }
 
 impl<Api: ChainApi> Unpin for RevalidationWorker<Api> {}
@@ -64,7 +67,9 @@ async fn batch_revalidate<Api: ChainApi>(
 	api: Arc<Api>,
 	at: NumberFor<Api>,
 	batch: impl IntoIterator<Item=ExtrinsicHash<Api>>,
+	metrics: &MetricsLink,
 ) {
+	let start = Instant::now();
 	let mut invalid_hashes = Vec::new();
 	let mut revalidated = HashMap::new();
 
@@ -117,12 +122,17 @@ async fn batch_revalidate<Api: ChainApi>(
 	if revalidated.len() > 0 {
 		pool.resubmit(revalidated);
 	}
+
+	metrics.report(
+		|metrics| metrics.revalidation_duration.observe(start.elapsed().as_secs_f64())
+	);
 }
 
 impl<Api: ChainApi> RevalidationWorker<Api> {
 	fn new(
 		api: Arc<Api>,
 		pool: Arc<Pool<Api>>,
+		metrics: MetricsLink,
 	) -> Self {
 		Self {
 			api,
@@ -130,6 +140,7 @@ impl<Api: ChainApi> RevalidationWorker<Api> {
 			block_ordered: Default::default(),
 			members: Default::default(),
 			best_block: Zero::zero(),
+			metrics,
 		}
 	}
 
@@ -223,7 +234,13 @@ impl<Api: ChainApi> RevalidationWorker<Api> {
 					let next_batch = this.prepare_batch();
 					let batch_len = next_batch.len();
 
-					batch_revalidate(this.pool.clone(), this.api.clone(), this.best_block, next_batch).await;
+					batch_revalidate(
+						this.pool.clone(),
+						this.api.clone(),
+						this.best_block,
+						next_batch,
+						&this.metrics,
+					).await;
 
 					#[cfg(test)]
 					{
@@ -278,6 +295,7 @@ pub struct RevalidationQueue<Api: ChainApi> {
 	pool: Arc<Pool<Api>>,
 	api: Arc<Api>,
 	background: Option<TracingUnboundedSender<WorkerPayload<Api>>>,
+	metrics: MetricsLink,
 }
 
 impl<Api: ChainApi> RevalidationQueue<Api>
@@ -285,11 +303,12 @@ where
 	Api: 'static,
 {
 	/// New revalidation queue without background worker.
-	pub fn new(api: Arc<Api>, pool: Arc<Pool<Api>>) -> Self {
+	pub fn new(api: Arc<Api>, pool: Arc<Pool<Api>>, metrics: MetricsLink) -> Self {
 		Self {
 			api,
 			pool,
 			background: None,
+			metrics,
 		}
 	}
 
@@ -297,26 +316,33 @@ where
 		api: Arc<Api>,
 		pool: Arc<Pool<Api>>,
 		interval: R,
+		metrics: MetricsLink,
 	) -> (Self, Pin<Box<dyn Future<Output=()> + Send>>) where R: Send + 'static, R::Guard: Send {
 		let (to_worker, from_queue) = tracing_unbounded("mpsc_revalidation_queue");
 
-		let worker = RevalidationWorker::new(api.clone(), pool.clone());
+		let worker = RevalidationWorker::new(api.clone(), pool.clone(), metrics.clone());
 
 		let queue =
 			Self {
 				api,
 				pool,
 				background: Some(to_worker),
+				metrics,
 			};
 
 		(queue, worker.run(from_queue, interval).boxed())
 	}
 
 	/// New revalidation queue with background worker.
-	pub fn new_background(api: Arc<Api>, pool: Arc<Pool<Api>>) ->
+	pub fn new_background(api: Arc<Api>, pool: Arc<Pool<Api>>, metrics: MetricsLink) ->
 		(Self, Pin<Box<dyn Future<Output=()> + Send>>)
 	{
-		Self::new_with_interval(api, pool, intervalier::Interval::new(BACKGROUND_REVALIDATION_INTERVAL))
+		Self::new_with_interval(
+			api,
+			pool,
+			intervalier::Interval::new(BACKGROUND_REVALIDATION_INTERVAL),
+			metrics,
+		)
 	}
 
 	/// New revalidation queue with background worker and test signal.
@@ -325,7 +351,7 @@ where
 		(Self, Pin<Box<dyn Future<Output=()> + Send>>, intervalier::BackSignalControl)
 	{
 		let (interval, notifier) = intervalier::BackSignalInterval::new(BACKGROUND_REVALIDATION_INTERVAL);
-		let (queue, background) = Self::new_with_interval(api, pool, interval);
+		let (queue, background) = Self::new_with_interval(api, pool, interval, Default::default());
 
 		(queue, background, notifier)
 	}
@@ -354,7 +380,7 @@ where
 		} else {
 			let pool = self.pool.clone();
 			let api = self.api.clone();
-			batch_revalidate(pool, api, at, transactions).await
+			batch_revalidate(pool, api, at, transactions, &self.metrics).await
 		}
 	}
 }
@@ -378,7 +404,7 @@ mod tests {
 	fn smoky() {
 		let (api, pool) = setup();
 		let pool = Arc::new(pool);
-		let queue = Arc::new(RevalidationQueue::new(api.clone(), pool.clone()));
+		let queue = Arc::new(RevalidationQueue::new(api.clone(), pool.clone(), Default::default()));
 
 		let uxt = uxt(Alice, 0);
 		let uxt_hash = block_on(
@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::*;
-use sp_transaction_pool::TransactionStatus;
+use sp_transaction_pool::{TransactionStatus, ChainedMaintainer};
 use futures::executor::{block_on, block_on_stream};
 use txpool::{self, Pool};
 use sp_runtime::{
@@ -306,6 +306,29 @@ fn should_not_retain_invalid_hashes_from_retracted() {
 	assert_eq!(pool.status().ready, 0);
 }
 
+#[test]
+fn should_only_resubmit_from_retracted_blocks_closest_to_common_ancestor() {
+	let (pool, _guard, _notifier) = maintained_pool();
+
+	let header = pool.api.push_block(1, vec![], true);
+	let mut fork_header = pool.api.push_block(1, vec![uxt(Alice, 209)], false);
+	for _ in 1..MAX_RESUBMITTED_RETRACTED_BLOCKS {
+		fork_header = pool.api.push_block_with_parent(fork_header.hash(), vec![], false);
+	}
+	// One block too many, its transaction isn't resubmitted.
+	let fork_header = pool.api.push_block_with_parent(
+		fork_header.hash(),
+		vec![uxt(Alice, 210)],
+		false,
+	);
+
+	let event = block_event_with_retracted(header, fork_header.hash(), &*pool.api);
+
+	block_on(pool.maintain(event));
+	assert_eq!(pool.status().ready, 1);
+	assert_eq!(pool.status().future, 0);
+}
+
 #[test]
 fn should_revalidate_transaction_multiple_times() {
 	let xt = uxt(Alice, 209);
@@ -966,6 +989,23 @@ fn should_not_accept_old_signatures() {
 	);
 }
 
+#[test]
+fn should_reject_extrinsics_longer_than_blocks() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+
+	let pool = Arc::new(
+		BasicPool::new_test(Arc::new(FullChainApi::new(client, None))).0
+	);
+
+	let xt = Extrinsic::IncludeData(vec![0; 4 * 1024 * 1024 + 1]);
+
+	assert_matches::assert_matches!(
+		block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt)),
+		Err(error::Error::ExtrinsicTooLarge { max_length: 4194304, .. })
+	);
+	assert_eq!(pool.status().ready, 0);
+}
+
 #[test]
 fn import_notification_to_pool_maintain_works() {
 	let mut client = Arc::new(substrate_test_runtime_client::new());
@@ -1038,3 +1078,77 @@ fn only_revalidate_on_best_block() {
 
 	assert_eq!(pool.status().ready, 1);
 }
+
+#[test]
+fn chained_maintainer_should_maintain_the_pool_first() {
+	struct CountReady {
+		pool: Arc<BasicPool<TestApi, Block>>,
+		ready: Arc<Mutex<Vec<usize>>>,
+	}
+
+	impl TransactionPoolMaintainer for CountReady {
+		type Block = Block;
+
+		fn maintain(&self, _: ChainEvent<Block>) -> Pin<Box<dyn Future<Output=()> + Send>> {
+			self.ready.lock().push(self.pool.status().ready);
+			futures::future::ready(()).boxed()
+		}
+	}
+
+	let xt = uxt(Alice, 209);
+	let (pool, _guard, _notifier) = maintained_pool();
+	let pool = Arc::new(pool);
+	let ready = Arc::new(Mutex::new(Vec::new()));
+	let maintainer = ChainedMaintainer::new(
+		pool.clone(),
+		CountReady { pool: pool.clone(), ready: ready.clone() },
+	);
+
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, xt.clone())).expect("1. Imported");
+	let header = pool.api.push_block(1, vec![xt], true);
+	block_on(maintainer.maintain(block_event(header)));
+
+	assert_eq!(pool.status().ready, 0);
+	assert_eq!(*ready.lock(), vec![0]);
+}
+
+#[test]
+fn revalidation_periods_adapt_to_churn() {
+	let mut periods = AdaptivePeriods::default();
+
+	// a stable pool backs off up to the upper bounds
+	for _ in 0..10 {
+		periods.update(0, 100);
+	}
+	assert_eq!(
+		periods,
+		AdaptivePeriods { time: MAX_REVALIDATION_TIME, blocks: MAX_REVALIDATION_BLOCKS },
+	);
+
+	// moderate churn keeps the periods as they are
+	periods.update(5, 100);
+	assert_eq!(
+		periods,
+		AdaptivePeriods { time: MAX_REVALIDATION_TIME, blocks: MAX_REVALIDATION_BLOCKS },
+	);
+
+	// high churn revalidates sooner, down to the lower bounds
+	periods.update(10, 100);
+	assert_eq!(periods, AdaptivePeriods { time: Duration::from_secs(60), blocks: 20 });
+	for _ in 0..10 {
+		periods.update(50, 100);
+	}
+	assert_eq!(
+		periods,
+		AdaptivePeriods { time: MIN_REVALIDATION_TIME, blocks: MIN_REVALIDATION_BLOCKS },
+	);
+}
+
+#[test]
+fn shorter_period_brings_scheduled_revalidation_forward() {
+	let mut status = RevalidationStatus::<u64>::NotScheduled;
+	assert!(!status.next_required(1, None, Some(40)));
+	assert!(!status.next_required(2, None, Some(40)));
+	assert!(!status.next_required(10, None, Some(5)));
+	assert!(status.next_required(15, None, Some(5)));
+}
@@ -370,6 +370,7 @@ mod tests {
 			repeat_reads: 0,
 			writes,
 			repeat_writes: 0,
+			host_calls: vec![],
 		}
 	}
 
@@ -766,7 +766,12 @@ macro_rules! impl_benchmark {
 
 							let start_extrinsic = $crate::benchmarking::current_time();
 
-							closure_to_benchmark()?;
+							// Count the host calls inside the timing, so the calls getting the
+							// time are not counted.
+							$crate::benchmarking::start_host_call_counting();
+							let result = closure_to_benchmark();
+							let host_calls = $crate::benchmarking::stop_host_call_counting();
+							result?;
 
 							let finish_extrinsic = $crate::benchmarking::current_time();
 							let elapsed_extrinsic = finish_extrinsic - start_extrinsic;
@@ -796,6 +801,7 @@ macro_rules! impl_benchmark {
 								repeat_reads: read_write_count.1,
 								writes: read_write_count.2,
 								repeat_writes: read_write_count.3,
+								host_calls,
 							});
 						}
 
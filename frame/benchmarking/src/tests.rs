@@ -129,9 +129,10 @@ fn new_test_ext() -> sp_io::TestExternalities {
 mod benchmarks {
 	use sp_std::prelude::*;
 	use frame_system::RawOrigin;
-	use super::{Test, pallet_test::{self, Value}, new_test_ext};
+	use super::{Block, GenesisConfig, Test, pallet_test::{self, Value}, new_test_ext};
 	use frame_support::{assert_ok, assert_err, ensure, traits::Get, StorageValue};
-	use crate::{BenchmarkingSetup, BenchmarkParameter, account};
+	use sp_runtime::BuildStorage;
+	use crate::{Benchmarking, BenchmarkingSetup, BenchmarkParameter, account};
 
 	// Additional used internally by the benchmark macro.
 	use super::pallet_test::{Call, Config, Pallet};
@@ -274,6 +275,34 @@ mod benchmarks {
 		});
 	}
 
+	#[test]
+	fn benchmark_results_contain_the_host_calls() {
+		let genesis = GenesisConfig::default().build_storage().unwrap();
+		let state = sc_client_db::BenchmarkingState::<Block>::new(genesis, None).unwrap();
+		let mut overlay = Default::default();
+		let mut cache = Default::default();
+		let mut ext = sp_state_machine::Ext::<_, u64, _>::new(
+			&mut overlay,
+			&mut cache,
+			&state,
+			None,
+			None,
+		);
+
+		let results = sp_externalities::set_and_run_with_externalities(&mut ext, || {
+			Pallet::<Test>::run_benchmark(b"set_value", &[], &[], &[1], 1, &[], false)
+		}).unwrap();
+
+		assert_eq!(results.len(), 2);
+		for result in results {
+			// `Value::put` is the only storage write of the benchmarked call.
+			assert!(result.host_calls.contains(&(b"sp_io::storage::set_version_1".to_vec(), 1)));
+			// The calls getting the time of the benchmark are not counted.
+			let benchmarking = b"frame_benchmarking";
+			assert!(result.host_calls.iter().all(|(path, _)| !path.starts_with(benchmarking)));
+		}
+	}
+
 	#[test]
 	fn benchmarks_generate_unit_tests() {
 		new_test_ext().execute_with(|| {
@@ -62,6 +62,9 @@ pub struct BenchmarkResults {
 	pub repeat_reads: u32,
 	pub writes: u32,
 	pub repeat_writes: u32,
+	/// The number of calls of each host function made by the benchmarked call, keyed by the
+	/// path of the function.
+	pub host_calls: Vec<(Vec<u8>, u32)>,
 }
 
 /// Configuration used to setup and run runtime benchmarks.
@@ -126,6 +129,23 @@ pub trait Benchmarking {
 		self.reset_read_write_count()
 	}
 
+	/// Start counting the host function calls, resetting the counts.
+	fn start_host_call_counting() {
+		sp_runtime_interface::host_calls::start_counting()
+	}
+
+	/// Stop counting the host function calls.
+	///
+	/// Returns the number of calls of each host function since counting was started, keyed by
+	/// the path of the function, e.g. `sp_io::storage::get_version_1`. Host calls made by other
+	/// host functions and the calls starting and stopping the counting are not counted.
+	fn stop_host_call_counting() -> Vec<(Vec<u8>, u32)> {
+		sp_runtime_interface::host_calls::stop_counting()
+			.into_iter()
+			.map(|(path, count)| (path.as_bytes().to_vec(), count))
+			.collect()
+	}
+
 	/// Get the DB whitelist.
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		self.get_whitelist()
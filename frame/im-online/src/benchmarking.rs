@@ -43,9 +43,13 @@ pub fn create_heartbeat<T: Config>(k: u32, e: u32) ->
 	}
 	Keys::<T>::put(keys.clone());
 
+	let mut peer_id = vec![0x00, 0x24, 0x08, 0x01, 0x12, 0x20];
+	peer_id.extend_from_slice(&[0; 32]);
+	// 32 bytes long addresses, which are validated as well.
+	let external_address = b"/dns4/substrate.dev/tcp/30333/ws".to_vec();
 	let network_state = OpaqueNetworkState {
-		peer_id: OpaquePeerId::default(),
-		external_addresses: vec![OpaqueMultiaddr::new(vec![0; 32]); e as usize],
+		peer_id: OpaquePeerId::new(peer_id.encode()),
+		external_addresses: vec![OpaqueMultiaddr::new(external_address.encode()); e as usize],
 	};
 	let input_heartbeat = Heartbeat {
 		block_number: T::BlockNumber::zero(),
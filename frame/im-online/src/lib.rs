@@ -76,9 +76,9 @@ pub mod weights;
 
 use sp_application_crypto::RuntimeAppPublic;
 use codec::{Encode, Decode};
-use sp_core::offchain::OpaqueNetworkState;
+use sp_core::offchain::{OpaqueNetworkState, network::{Multiaddr, PeerId}};
 use sp_std::prelude::*;
-use sp_std::convert::TryInto;
+use sp_std::convert::{TryFrom, TryInto};
 use sp_runtime::{
 	offchain::storage::StorageValueRef,
 	traits::{AtLeast32BitUnsigned, Convert, Member, Saturating},
@@ -374,7 +374,12 @@ decl_module! {
 			if let (false, Some(public)) = (exists, public) {
 				Self::deposit_event(Event::<T>::HeartbeatReceived(public.clone()));
 
-				let network_state = heartbeat.network_state.encode();
+				// the addresses are not checked when validating the heartbeat, drop the
+				// malformed ones
+				let mut network_state = heartbeat.network_state;
+				network_state.external_addresses
+					.retain(|address| Multiaddr::try_from(address).is_ok());
+				let network_state = network_state.encode();
 				<ReceivedHeartbeats>::insert(
 					&current_session,
 					&heartbeat.authority_index,
@@ -717,6 +722,9 @@ impl<T: Config> OneSessionHandler<T::AccountId> for Module<T> {
 /// Invalid transaction custom error. Returned when validators_len field in heartbeat is incorrect.
 const INVALID_VALIDATORS_LEN: u8 = 10;
 
+/// Invalid transaction custom error. Returned when the peer id in heartbeat is malformed.
+const INVALID_NETWORK_STATE: u8 = 11;
+
 impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
 	type Call = Call<T>;
 
@@ -743,6 +751,13 @@ impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
 				None => return InvalidTransaction::BadProof.into(),
 			};
 
+			// the network state is only stored, make sure it is the one of an actual node. The
+			// malformed addresses are dropped when storing it, as a node may not be able to tell
+			// which of its addresses are valid.
+			if PeerId::try_from(&heartbeat.network_state.peer_id).is_err() {
+				return InvalidTransaction::Custom(INVALID_NETWORK_STATE).into();
+			}
+
 			// check signature (this is expensive so we do it last).
 			let signature_valid = heartbeat.using_encoded(|encoded_heartbeat| {
 				authority_id.verify(&encoded_heartbeat, &signature)
@@ -24,11 +24,12 @@ use crate::mock::*;
 use sp_core::OpaquePeerId;
 use sp_core::offchain::{
 	OffchainDbExt,
+	OpaqueMultiaddr,
 	OffchainWorkerExt,
 	TransactionPoolExt,
 	testing::{TestOffchainExt, TestTransactionPoolExt},
 };
-use frame_support::{dispatch, assert_noop};
+use frame_support::{dispatch, assert_noop, assert_ok};
 use sp_runtime::{testing::UintAuthorityId, transaction_validity::TransactionValidityError};
 
 #[test]
@@ -108,6 +109,15 @@ fn should_report_offline_validators() {
 	});
 }
 
+fn network_state() -> OpaqueNetworkState {
+	let mut peer_id = vec![0x00, 0x24, 0x08, 0x01, 0x12, 0x20];
+	peer_id.extend_from_slice(&[1; 32]);
+	OpaqueNetworkState {
+		peer_id: OpaquePeerId::new(peer_id.encode()),
+		external_addresses: vec![OpaqueMultiaddr::new("/ip4/127.0.0.1/tcp/30333".encode())],
+	}
+}
+
 fn heartbeat(
 	block_number: u64,
 	session_index: u32,
@@ -119,10 +129,7 @@ fn heartbeat(
 
 	let heartbeat = Heartbeat {
 		block_number,
-		network_state: OpaqueNetworkState {
-			peer_id: OpaquePeerId(vec![1]),
-			external_addresses: vec![],
-		},
+		network_state: network_state(),
 		session_index,
 		authority_index,
 		validators_len: validators.len() as u32,
@@ -133,6 +140,8 @@ fn heartbeat(
 		.map_err(|e| match e {
 			TransactionValidityError::Invalid(InvalidTransaction::Custom(INVALID_VALIDATORS_LEN)) =>
 				"invalid validators len",
+			TransactionValidityError::Invalid(InvalidTransaction::Custom(INVALID_NETWORK_STATE)) =>
+				"invalid network state",
 			e @ _ => <&'static str>::from(e),
 		})?;
 	ImOnline::heartbeat(
@@ -199,6 +208,66 @@ fn late_heartbeat_and_invalid_keys_len_should_fail() {
 	});
 }
 
+#[test]
+fn heartbeat_with_malformed_peer_id_should_fail() {
+	use frame_support::unsigned::ValidateUnsigned;
+
+	new_test_ext().execute_with(|| {
+		advance_session();
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2, 3]));
+		advance_session();
+
+		let heartbeat = Heartbeat {
+			block_number: 1,
+			network_state: OpaqueNetworkState {
+				peer_id: OpaquePeerId(vec![1]),
+				external_addresses: vec![],
+			},
+			session_index: 2,
+			authority_index: 0,
+			validators_len: 3,
+		};
+		let signature = UintAuthorityId(1).sign(&heartbeat.encode()).unwrap();
+
+		assert_eq!(
+			ImOnline::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::heartbeat(heartbeat, signature),
+			),
+			Err(TransactionValidityError::Invalid(
+				InvalidTransaction::Custom(INVALID_NETWORK_STATE),
+			)),
+		);
+	});
+}
+
+#[test]
+fn malformed_addresses_of_heartbeat_are_dropped() {
+	use frame_support::unsigned::ValidateUnsigned;
+
+	new_test_ext().execute_with(|| {
+		advance_session();
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2, 3]));
+		advance_session();
+
+		let mut network_state = network_state();
+		network_state.external_addresses.push(OpaqueMultiaddr::new(vec![0; 32]));
+		let heartbeat = Heartbeat {
+			block_number: 1,
+			network_state,
+			session_index: 2,
+			authority_index: 0,
+			validators_len: 3,
+		};
+		let signature = UintAuthorityId(1).sign(&heartbeat.encode()).unwrap();
+		let call = crate::Call::heartbeat(heartbeat.clone(), signature.clone());
+
+		assert!(ImOnline::validate_unsigned(TransactionSource::External, &call).is_ok());
+		assert_ok!(ImOnline::heartbeat(Origin::none(), heartbeat, signature));
+		assert_eq!(ImOnline::received_heartbeats(&2, &0), Some(network_state().encode()));
+	});
+}
+
 #[test]
 fn should_generate_heartbeats() {
 	use frame_support::traits::OffchainWorker;
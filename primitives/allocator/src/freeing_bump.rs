@@ -47,7 +47,7 @@
 
 use crate::Error;
 use sp_std::{mem, convert::{TryFrom, TryInto}, ops::{Range, Index, IndexMut}};
-use sp_wasm_interface::{Pointer, WordSize};
+use sp_wasm_interface::{AllocationStats, Pointer, WordSize};
 
 /// The minimal alignment guaranteed by this allocator.
 ///
@@ -308,6 +308,9 @@ pub struct FreeingBumpHeapAllocator {
 	bumper: u32,
 	free_lists: FreeLists,
 	total_size: u32,
+	peak_size: u32,
+	live_allocations: u32,
+	total_allocations: u32,
 	poisoned: bool,
 }
 
@@ -324,6 +327,9 @@ impl FreeingBumpHeapAllocator {
 			bumper: aligned_heap_base,
 			free_lists: FreeLists::new(),
 			total_size: 0,
+			peak_size: 0,
+			live_allocations: 0,
+			total_allocations: 0,
 			poisoned: false,
 		}
 	}
@@ -382,6 +388,9 @@ impl FreeingBumpHeapAllocator {
 		Header::Occupied(order).write_into(mem, header_ptr)?;
 
 		self.total_size += order.size() + HEADER_SIZE;
+		self.peak_size = self.peak_size.max(self.total_size);
+		self.live_allocations += 1;
+		self.total_allocations = self.total_allocations.saturating_add(1);
 		trace!("Heap size is {} bytes after allocation", self.total_size);
 
 		bomb.disarm();
@@ -420,12 +429,23 @@ impl FreeingBumpHeapAllocator {
 			.total_size
 			.checked_sub(order.size() + HEADER_SIZE)
 			.ok_or_else(|| error("Unable to subtract from total heap size without overflow"))?;
+		self.live_allocations -= 1;
 		trace!("Heap size is {} bytes after deallocation", self.total_size);
 
 		bomb.disarm();
 		Ok(())
 	}
 
+	/// Returns the statistics of the allocations made so far.
+	pub fn stats(&self) -> AllocationStats {
+		AllocationStats {
+			bytes_allocated: self.total_size,
+			bytes_allocated_peak: self.peak_size,
+			live_allocations: self.live_allocations,
+			total_allocations: self.total_allocations,
+		}
+	}
+
 	/// Increases the `bumper` by `size`.
 	///
 	/// Returns the `bumper` from before the increase.
@@ -785,6 +805,30 @@ mod tests {
 		assert_eq!(heap.total_size, 0);
 	}
 
+	#[test]
+	fn should_track_allocation_stats() {
+		// given
+		let mut mem = [0u8; PAGE_SIZE as usize];
+		let mut heap = FreeingBumpHeapAllocator::new(0);
+
+		// when
+		let ptr1 = heap.allocate(&mut mem[..], 16).unwrap();
+		let ptr2 = heap.allocate(&mut mem[..], 32).unwrap();
+		heap.deallocate(&mut mem[..], ptr1).unwrap();
+
+		// then
+		assert_eq!(heap.stats(), AllocationStats {
+			bytes_allocated: HEADER_SIZE + 32,
+			bytes_allocated_peak: 2 * HEADER_SIZE + 48,
+			live_allocations: 1,
+			total_allocations: 2,
+		});
+
+		heap.deallocate(&mut mem[..], ptr2).unwrap();
+		assert_eq!(heap.stats().bytes_allocated, 0);
+		assert_eq!(heap.stats().live_allocations, 0);
+	}
+
 	#[test]
 	fn should_read_and_write_u64_correctly() {
 		// given
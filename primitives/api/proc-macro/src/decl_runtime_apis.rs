@@ -427,6 +427,8 @@ fn generate_call_api_at_calls(decl: &ItemTrait) -> Result<TokenStream> {
 				native_call: Option<NC>,
 				context: #crate_::ExecutionContext,
 				recorder: &Option<#crate_::ProofRecorder<Block>>,
+				execution_strategy: Option<#crate_::ExecutionStrategy>,
+				extensions: &#crate_::CallExtensions,
 			) -> std::result::Result<#crate_::NativeOrEncoded<R>, #crate_::ApiError> {
 				let version = call_runtime_at.runtime_version_at(at)?;
 				use #crate_::InitializeBlock;
@@ -453,6 +455,8 @@ fn generate_call_api_at_calls(decl: &ItemTrait) -> Result<TokenStream> {
 							initialize_block,
 							context,
 							recorder,
+							execution_strategy,
+							extensions,
 						};
 
 						let ret = call_runtime_at.call_api_at(params)?;
@@ -473,6 +477,8 @@ fn generate_call_api_at_calls(decl: &ItemTrait) -> Result<TokenStream> {
 					initialize_block,
 					context,
 					recorder,
+					execution_strategy,
+					extensions,
 				};
 
 				let ret = call_runtime_at.call_api_at(params)?;
@@ -215,6 +215,8 @@ fn generate_runtime_api_base_structures() -> Result<TokenStream> {
 				#crate_::StorageTransactionCache<Block, C::StateBackend>
 			>,
 			recorder: Option<#crate_::ProofRecorder<Block>>,
+			execution_strategy: Option<#crate_::ExecutionStrategy>,
+			extensions: #crate_::CallExtensions,
 		}
 
 		// `RuntimeApi` itself is not threadsafe. However, an instance is only available in a
@@ -294,6 +296,18 @@ fn generate_runtime_api_base_structures() -> Result<TokenStream> {
 					})
 			}
 
+			fn set_execution_strategy(&mut self, strategy: Option<#crate_::ExecutionStrategy>) {
+				self.execution_strategy = strategy;
+			}
+
+			fn register_extension<E: #crate_::Extension + Clone + Sync>(&mut self, extension: E) {
+				self.extensions.register(extension);
+			}
+
+			fn deregister_extension<E: #crate_::Extension>(&mut self) -> bool {
+				self.extensions.deregister::<E>()
+			}
+
 			fn into_storage_changes(
 				&self,
 				backend: &Self::StateBackend,
@@ -336,6 +350,8 @@ fn generate_runtime_api_base_structures() -> Result<TokenStream> {
 					changes: Default::default(),
 					recorder: Default::default(),
 					storage_transaction_cache: Default::default(),
+					execution_strategy: None,
+					extensions: Default::default(),
 				}.into()
 			}
 		}
@@ -355,6 +371,8 @@ fn generate_runtime_api_base_structures() -> Result<TokenStream> {
 					&std::cell::RefCell<#crate_::StorageTransactionCache<Block, C::StateBackend>>,
 					&std::cell::RefCell<Option<#crate_::BlockId<Block>>>,
 					&Option<#crate_::ProofRecorder<Block>>,
+					Option<#crate_::ExecutionStrategy>,
+					&#crate_::CallExtensions,
 				) -> std::result::Result<#crate_::NativeOrEncoded<R>, E>,
 				E,
 			>(
@@ -371,6 +389,8 @@ fn generate_runtime_api_base_structures() -> Result<TokenStream> {
 					&self.storage_transaction_cache,
 					&self.initialized_block,
 					&self.recorder,
+					self.execution_strategy,
+					&self.extensions,
 				);
 
 				self.commit_or_rollback(res.is_ok());
@@ -526,7 +546,9 @@ impl<'a> Fold for ApiRuntimeImplToApiRuntimeApiImpl<'a> {
 							changes,
 							storage_transaction_cache,
 							initialized_block,
-							recorder
+							recorder,
+							execution_strategy,
+							extensions
 						| {
 							#runtime_mod_path #call_api_at_call(
 								call_runtime_at,
@@ -544,6 +566,8 @@ impl<'a> Fold for ApiRuntimeImplToApiRuntimeApiImpl<'a> {
 								}),
 								context,
 								recorder,
+								execution_strategy,
+								extensions,
 							)
 						}
 					)
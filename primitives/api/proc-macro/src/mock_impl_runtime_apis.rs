@@ -102,6 +102,18 @@ fn implement_common_api_traits(
 				unimplemented!("`extract_proof` not implemented for runtime api mocks")
 			}
 
+			fn set_execution_strategy(&mut self, _: Option<#crate_::ExecutionStrategy>) {
+				// Mocks are not executed, so there is no strategy to override.
+			}
+
+			fn register_extension<E: #crate_::Extension + Clone + Sync>(&mut self, _: E) {
+				// Mocks are not executed, so there is no call to provide the extension to.
+			}
+
+			fn deregister_extension<E: #crate_::Extension>(&mut self) -> bool {
+				false
+			}
+
 			fn into_storage_changes(
 				&self,
 				_: &Self::StateBackend,
@@ -54,6 +54,10 @@ extern crate self as sp_api;
 pub use sp_state_machine::{
 	OverlayedChanges, StorageProof, Backend as StateBackend, ChangesTrieState, InMemoryBackend,
 };
+#[cfg(feature = "std")]
+pub use sp_state_machine::ExecutionStrategy;
+#[cfg(feature = "std")]
+pub use sp_externalities::{Extension, Extensions};
 #[doc(hidden)]
 #[cfg(feature = "std")]
 pub use sp_core::NativeOrEncoded;
@@ -471,6 +475,23 @@ pub trait ApiExt<Block: BlockT> {
 	/// If `record_proof` was not called before, this will return `None`.
 	fn extract_proof(&mut self) -> Option<StorageProof>;
 
+	/// Override the execution strategy of all the following calls made through this instance.
+	///
+	/// By default, and when `None` is given, the strategy is selected by the client based on the
+	/// execution context of each call.
+	fn set_execution_strategy(&mut self, strategy: Option<ExecutionStrategy>);
+
+	/// Register an extension provided to all the following calls made through this instance, on
+	/// top of the extensions of their execution context.
+	///
+	/// Each call is given its own copy of the extension.
+	fn register_extension<E: Extension + Clone + Sync>(&mut self, extension: E) where Self: Sized;
+
+	/// Stop providing the extension of type `E` registered with `register_extension`.
+	///
+	/// Returns `true` if such an extension was registered.
+	fn deregister_extension<E: Extension>(&mut self) -> bool where Self: Sized;
+
 	/// Convert the api object into the storage changes that were done while executing runtime
 	/// api functions.
 	///
@@ -533,6 +554,46 @@ pub struct CallApiAtParams<'a, Block: BlockT, C, NC, Backend: StateBackend<HashF
 	pub context: ExecutionContext,
 	/// The optional proof recorder for recording storage accesses.
 	pub recorder: &'a Option<ProofRecorder<Block>>,
+	/// The execution strategy to use instead of the one selected for the `context`, if any.
+	pub execution_strategy: Option<ExecutionStrategy>,
+	/// The extensions registered on the api instance, on top of those of the `context`.
+	pub extensions: &'a CallExtensions,
+}
+
+/// Extensions registered on a runtime api instance, see [`ApiExt::register_extension`].
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct CallExtensions(
+	std::collections::BTreeMap<
+		std::any::TypeId,
+		Box<dyn Fn() -> Box<dyn Extension> + Send + Sync>,
+	>,
+);
+
+#[cfg(feature = "std")]
+impl CallExtensions {
+	/// Register `extension`, replacing any extension of the same type.
+	pub fn register<E: Extension + Clone + Sync>(&mut self, extension: E) {
+		self.0.insert(
+			std::any::TypeId::of::<E>(),
+			Box::new(move || Box::new(extension.clone())),
+		);
+	}
+
+	/// Deregister the extension of type `E`.
+	///
+	/// Returns `true` if it was registered.
+	pub fn deregister<E: Extension>(&mut self) -> bool {
+		self.0.remove(&std::any::TypeId::of::<E>()).is_some()
+	}
+
+	/// Add a copy of the registered extensions to `extensions`, replacing those of the same type.
+	pub fn copy_into(&self, extensions: &mut Extensions) {
+		for (type_id, extension) in &self.0 {
+			extensions.deregister(*type_id);
+			let _ = extensions.register_with_type_id(*type_id, extension());
+		}
+	}
 }
 
 /// Something that can call into the an api at a given block.
@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ProvideRuntimeApi, ApiExt};
 use substrate_test_runtime_client::{
 	prelude::*,
 	DefaultTestClientBuilderExt, TestClientBuilder,
@@ -120,6 +120,22 @@ fn calling_with_native_else_wasm_and_fail_on_native_should_work() {
 	assert_eq!(runtime_api.fail_on_native(&block_id).unwrap(), 1);
 }
 
+#[test]
+fn execution_strategy_can_be_overridden_per_api_instance() {
+	let client = TestClientBuilder::new().set_execution_strategy(ExecutionStrategy::NativeElseWasm).build();
+	let mut runtime_api = client.runtime_api();
+	let block_id = BlockId::Number(client.chain_info().best_number);
+
+	runtime_api.set_execution_strategy(Some(ExecutionStrategy::AlwaysWasm));
+	assert!(runtime_api.fail_on_wasm(&block_id).is_err());
+
+	runtime_api.set_execution_strategy(Some(ExecutionStrategy::Both));
+	assert!(runtime_api.fail_on_wasm(&block_id).is_err());
+
+	runtime_api.set_execution_strategy(None);
+	assert_eq!(runtime_api.fail_on_wasm(&block_id).unwrap(), 1);
+}
+
 #[test]
 fn use_trie_function() {
 	let client = TestClientBuilder::new().set_execution_strategy(ExecutionStrategy::AlwaysWasm).build();
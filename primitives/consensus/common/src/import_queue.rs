@@ -26,7 +26,7 @@
 //! instantiated. The `BasicQueue` and `BasicVerifier` traits allow serial
 //! queues to be instantiated simply.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use sp_runtime::{Justifications, traits::{Block as BlockT, Header as _, NumberFor}};
 
@@ -181,33 +181,82 @@ pub(crate) async fn import_single_block_metered<B: BlockT, V: Verifier<B>, Trans
 	verifier: &mut V,
 	metrics: Option<Metrics>,
 ) -> Result<BlockImportResult<NumberFor<B>>, BlockImportError> {
-	let peer = block.origin;
-
-	let (header, justifications) = match (block.header, block.justifications) {
-		(Some(header), justifications) => (header, justifications),
-		(None, _) => {
-			if let Some(ref peer) = peer {
-				debug!(target: "sync", "Header {} was not provided by {} ", block.hash, peer);
-			} else {
-				debug!(target: "sync", "Header {} was not provided ", block.hash);
-			}
-			return Err(BlockImportError::IncompleteHeader(peer))
-		},
-	};
+	let meta = BlockMeta::new(&block)?;
+
+	if let Some(r) = check_single_block(import_handle, &meta).await? {
+		return Ok(r)
+	}
+
+	let header = block.header.expect("`BlockMeta::new` checks the header is present; qed");
+	let (verified, verification_time) = verify_single_block(
+		verifier,
+		block_origin,
+		&meta,
+		header,
+		block.justifications,
+		block.body,
+	).await;
 
-	trace!(target: "sync", "Header {} has {:?} logs", block.hash, header.digest().logs().len());
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_verification(verified.is_ok(), verification_time);
+	}
 
-	let number = header.number().clone();
-	let hash = header.hash();
-	let parent_hash = header.parent_hash().clone();
+	import_verified_block(import_handle, &meta, verified?, verification_time, metrics).await
+}
+
+/// What the queue needs to know about a block once its header and body were handed to the
+/// verifier.
+#[derive(Debug, Clone)]
+pub(crate) struct BlockMeta<B: BlockT> {
+	pub(crate) hash: B::Hash,
+	pub(crate) number: NumberFor<B>,
+	pub(crate) parent_hash: B::Hash,
+	pub(crate) peer: Option<Origin>,
+	pub(crate) allow_missing_state: bool,
+	pub(crate) import_existing: bool,
+}
+
+impl<B: BlockT> BlockMeta<B> {
+	/// Extract the metadata of the given block, failing if its header is missing.
+	pub(crate) fn new(block: &IncomingBlock<B>) -> Result<Self, BlockImportError> {
+		let peer = block.origin.clone();
+
+		let header = match block.header.as_ref() {
+			Some(header) => header,
+			None => {
+				if let Some(ref peer) = peer {
+					debug!(target: "sync", "Header {} was not provided by {} ", block.hash, peer);
+				} else {
+					debug!(target: "sync", "Header {} was not provided ", block.hash);
+				}
+				return Err(BlockImportError::IncompleteHeader(peer))
+			},
+		};
+
+		trace!(target: "sync", "Header {} has {:?} logs", block.hash, header.digest().logs().len());
+
+		Ok(BlockMeta {
+			hash: header.hash(),
+			number: header.number().clone(),
+			parent_hash: header.parent_hash().clone(),
+			peer,
+			allow_missing_state: block.allow_missing_state,
+			import_existing: block.import_existing,
+		})
+	}
+
+	fn import_result(
+		&self,
+		import: Result<ImportResult, ConsensusError>,
+	) -> Result<BlockImportResult<NumberFor<B>>, BlockImportError> {
+		let BlockMeta { number, hash, parent_hash, peer, .. } = self;
 
-	let import_handler = |import| {
 		match import {
 			Ok(ImportResult::AlreadyInChain) => {
 				trace!(target: "sync", "Block already in chain {}: {:?}", number, hash);
-				Ok(BlockImportResult::ImportedKnown(number, peer.clone()))
+				Ok(BlockImportResult::ImportedKnown(*number, peer.clone()))
 			},
-			Ok(ImportResult::Imported(aux)) => Ok(BlockImportResult::ImportedUnknown(number, aux, peer.clone())),
+			Ok(ImportResult::Imported(aux)) => Ok(BlockImportResult::ImportedUnknown(*number, aux, peer.clone())),
 			Ok(ImportResult::MissingState) => {
 				debug!(target: "sync", "Parent state is missing for {}: {:?}, parent: {:?}", number, hash, parent_hash);
 				Err(BlockImportError::MissingState)
@@ -225,50 +274,81 @@ pub(crate) async fn import_single_block_metered<B: BlockT, V: Verifier<B>, Trans
 				Err(BlockImportError::Other(e))
 			}
 		}
-	};
-
-	match import_handler(import_handle.check_block(BlockCheckParams {
-		hash,
-		number,
-		parent_hash,
-		allow_missing_state: block.allow_missing_state,
-		import_existing: block.import_existing,
+	}
+}
+
+/// Check whether the block still has to be verified and imported.
+///
+/// Returns `Ok(None)` if it does, or the result to report for it otherwise.
+pub(crate) async fn check_single_block<B: BlockT, Transaction: Send + 'static>(
+	import_handle: &mut impl BlockImport<B, Transaction = Transaction, Error = ConsensusError>,
+	meta: &BlockMeta<B>,
+) -> Result<Option<BlockImportResult<NumberFor<B>>>, BlockImportError> {
+	match meta.import_result(import_handle.check_block(BlockCheckParams {
+		hash: meta.hash,
+		number: meta.number,
+		parent_hash: meta.parent_hash,
+		allow_missing_state: meta.allow_missing_state,
+		import_existing: meta.import_existing,
 	}).await)? {
-		BlockImportResult::ImportedUnknown { .. } => (),
-		r => return Ok(r), // Any other successful result means that the block is already imported.
+		BlockImportResult::ImportedUnknown { .. } => Ok(None),
+		r => Ok(Some(r)), // Any other successful result means that the block is already imported.
 	}
+}
 
+/// Result of the verification of a single block.
+pub(crate) type VerificationResult<B> = Result<
+	(BlockImportParams<B, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>),
+	BlockImportError,
+>;
+
+/// Verify a single block, returning the verification result and the time it took.
+pub(crate) async fn verify_single_block<B: BlockT, V: Verifier<B>>(
+	verifier: &mut V,
+	block_origin: BlockOrigin,
+	meta: &BlockMeta<B>,
+	header: B::Header,
+	justifications: Option<Justifications>,
+	body: Option<Vec<B::Extrinsic>>,
+) -> (VerificationResult<B>, Duration) {
 	let started = wasm_timer::Instant::now();
-	let (mut import_block, maybe_keys) = verifier.verify(
+	let verified = verifier.verify(
 		block_origin,
 		header,
 		justifications,
-		block.body
+		body,
 	).await.map_err(|msg| {
+		let BlockMeta { number, hash, peer, .. } = meta;
 		if let Some(ref peer) = peer {
 			trace!(target: "sync", "Verifying {}({}) from {} failed: {}", number, hash, peer, msg);
 		} else {
 			trace!(target: "sync", "Verifying {}({}) failed: {}", number, hash, msg);
 		}
-		if let Some(metrics) = metrics.as_ref() {
-			metrics.report_verification(false, started.elapsed());
-		}
 		BlockImportError::VerificationFailed(peer.clone(), msg)
-	})?;
+	});
 
-	if let Some(metrics) = metrics.as_ref() {
-		metrics.report_verification(true, started.elapsed());
-	}
+	(verified, started.elapsed())
+}
+
+/// Import a block that was already verified.
+pub(crate) async fn import_verified_block<B: BlockT, Transaction: Send + 'static>(
+	import_handle: &mut impl BlockImport<B, Transaction = Transaction, Error = ConsensusError>,
+	meta: &BlockMeta<B>,
+	(mut import_block, maybe_keys): (BlockImportParams<B, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>),
+	verification_time: Duration,
+	metrics: Option<Metrics>,
+) -> Result<BlockImportResult<NumberFor<B>>, BlockImportError> {
+	let started = wasm_timer::Instant::now();
 
 	let mut cache = HashMap::new();
 	if let Some(keys) = maybe_keys {
 		cache.extend(keys.into_iter());
 	}
-	import_block.allow_missing_state = block.allow_missing_state;
+	import_block.allow_missing_state = meta.allow_missing_state;
 
 	let imported = import_handle.import_block(import_block.convert_transaction(), cache).await;
 	if let Some(metrics) = metrics.as_ref() {
-		metrics.report_verification_and_import(started.elapsed());
+		metrics.report_verification_and_import(verification_time + started.elapsed());
 	}
-	import_handler(imported)
+	meta.import_result(imported)
 }
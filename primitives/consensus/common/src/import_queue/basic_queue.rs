@@ -16,7 +16,7 @@
 // limitations under the License.
 
 use std::{pin::Pin, time::Duration, marker::PhantomData};
-use futures::{prelude::*, task::Context, task::Poll};
+use futures::{prelude::*, channel::oneshot, task::Context, task::Poll};
 use futures_timer::Delay;
 use sp_runtime::{Justification, Justifications, traits::{Block as BlockT, Header as HeaderT, NumberFor}};
 use sp_utils::mpsc::{TracingUnboundedSender, tracing_unbounded, TracingUnboundedReceiver};
@@ -26,8 +26,8 @@ use crate::{
 	block_import::BlockOrigin,
 	import_queue::{
 		BlockImportResult, BlockImportError, Verifier, BoxBlockImport,
-		BoxJustificationImport, ImportQueue, Link, Origin,
-		IncomingBlock, import_single_block_metered,
+		BoxJustificationImport, ImportQueue, Link, Origin, IncomingBlock,
+		BlockMeta, VerificationResult, check_single_block, verify_single_block, import_verified_block,
 		buffered_link::{self, BufferedLinkSender, BufferedLinkReceiver},
 	},
 	metrics::Metrics,
@@ -35,6 +35,9 @@ use crate::{
 
 /// Interface to a basic block import queue that is importing blocks sequentially in a separate
 /// task, with plugable verification.
+///
+/// Verification runs in a task of its own, so that the next block of a batch is verified while
+/// the current one is being imported.
 pub struct BasicQueue<B: BlockT, Transaction> {
 	/// Channel to send justifcation import messages to the background task.
 	justification_sender: TracingUnboundedSender<worker_messages::ImportJustification<B>>,
@@ -75,7 +78,7 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 				.ok()
 		});
 
-		let (future, justification_sender, block_import_sender) = BlockImportWorker::new(
+		let (future, verification, justification_sender, block_import_sender) = BlockImportWorker::new(
 			result_sender,
 			verifier,
 			block_import,
@@ -84,6 +87,7 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 		);
 
 		spawner.spawn_essential_blocking("basic-block-import-worker", future.boxed());
+		spawner.spawn_essential_blocking("basic-block-verifier", verification.boxed());
 
 		Self {
 			justification_sender,
@@ -146,6 +150,65 @@ mod worker_messages {
 
 	pub struct ImportBlocks<B: BlockT>(pub BlockOrigin, pub Vec<IncomingBlock<B>>);
 	pub struct ImportJustification<B: BlockT>(pub Origin, pub B::Hash, pub NumberFor<B>, pub Justification);
+
+	pub struct VerifyBlock<B: BlockT> {
+		pub origin: BlockOrigin,
+		pub meta: BlockMeta<B>,
+		pub header: B::Header,
+		pub justifications: Option<Justifications>,
+		pub body: Option<Vec<B::Extrinsic>>,
+		pub result: oneshot::Sender<(VerificationResult<B>, Duration)>,
+	}
+}
+
+/// Handle to the block verification task.
+struct VerifierHandle<B: BlockT> {
+	sender: TracingUnboundedSender<worker_messages::VerifyBlock<B>>,
+}
+
+impl<B: BlockT> VerifierHandle<B> {
+	/// Send the given block for verification, returning the receiving end of the result.
+	fn verify(
+		&self,
+		origin: BlockOrigin,
+		meta: &BlockMeta<B>,
+		block: IncomingBlock<B>,
+	) -> oneshot::Receiver<(VerificationResult<B>, Duration)> {
+		let (result, receiver) = oneshot::channel();
+		let header = block.header.expect("`BlockMeta::new` checks the header is present; qed");
+		let _ = self.sender.unbounded_send(worker_messages::VerifyBlock {
+			origin,
+			meta: meta.clone(),
+			header,
+			justifications: block.justifications,
+			body: block.body,
+			result,
+		});
+		receiver
+	}
+}
+
+/// The process of verifying blocks.
+///
+/// Returns when the verification channel is closed.
+async fn block_verification_process<B: BlockT>(
+	mut verifier: impl Verifier<B>,
+	mut verification_receiver: TracingUnboundedReceiver<worker_messages::VerifyBlock<B>>,
+) {
+	while let Some(request) = verification_receiver.next().await {
+		let worker_messages::VerifyBlock { origin, meta, header, justifications, body, result } = request;
+		let verified = verify_single_block(
+			&mut verifier,
+			origin,
+			&meta,
+			header,
+			justifications,
+			body,
+		).await;
+
+		// The import process may have given up on the block in the meantime.
+		let _ = result.send(verified);
+	}
 }
 
 /// The process of importing blocks.
@@ -157,7 +220,7 @@ mod worker_messages {
 /// Returns when `block_import` ended.
 async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 	mut block_import: BoxBlockImport<B, Transaction>,
-	mut verifier: impl Verifier<B>,
+	verifier: VerifierHandle<B>,
 	mut result_sender: BufferedLinkSender<B>,
 	mut block_import_receiver: TracingUnboundedReceiver<worker_messages::ImportBlocks<B>>,
 	metrics: Option<Metrics>,
@@ -179,7 +242,7 @@ async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 			&mut block_import,
 			origin,
 			blocks,
-			&mut verifier,
+			&verifier,
 			delay_between_blocks,
 			metrics.clone(),
 		).await;
@@ -202,6 +265,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 		justification_import: Option<BoxJustificationImport<B>>,
 		metrics: Option<Metrics>,
 	) -> (
+		impl Future<Output = ()> + Send,
 		impl Future<Output = ()> + Send,
 		TracingUnboundedSender<worker_messages::ImportJustification<B>>,
 		TracingUnboundedSender<worker_messages::ImportBlocks<B>>,
@@ -214,6 +278,9 @@ impl<B: BlockT> BlockImportWorker<B> {
 		let (block_import_sender, block_import_port) =
 			tracing_unbounded("mpsc_import_queue_worker_blocks");
 
+		let (verification_sender, verification_port) =
+			tracing_unbounded("mpsc_import_queue_worker_verification");
+
 		let mut worker = BlockImportWorker {
 			result_sender,
 			justification_import,
@@ -232,7 +299,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 		let future = async move {
 			let block_import_process = block_import_process(
 				block_import,
-				verifier,
+				VerifierHandle { sender: verification_sender },
 				worker.result_sender.clone(),
 				block_import_port,
 				worker.metrics.clone(),
@@ -275,7 +342,9 @@ impl<B: BlockT> BlockImportWorker<B> {
 			}
 		};
 
-		(future, justification_sender, block_import_sender)
+		let verification = block_verification_process(verifier, verification_port);
+
+		(future, verification, justification_sender, block_import_sender)
 	}
 
 	fn import_justification(
@@ -321,12 +390,16 @@ struct ImportManyBlocksResult<B: BlockT> {
 
 /// Import several blocks at once, returning import result for each block.
 ///
+/// Once a block is verified, the verification of the next one is started before the block is
+/// imported, so that both run concurrently. That verification is speculative: it happens while
+/// the parent is not yet in the chain, so if it fails it is retried after the parent is imported.
+///
 /// This will yield after each imported block once, to ensure that other futures can be called as well.
-async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'static>(
+async fn import_many_blocks<B: BlockT, Transaction: Send + 'static>(
 	import_handle: &mut BoxBlockImport<B, Transaction>,
 	blocks_origin: BlockOrigin,
 	blocks: Vec<IncomingBlock<B>>,
-	verifier: &mut V,
+	verifier: &VerifierHandle<B>,
 	delay_between_blocks: Duration,
 	metrics: Option<Metrics>,
 ) -> ImportManyBlocksResult<B> {
@@ -346,7 +419,8 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'stat
 	let mut imported = 0;
 	let mut results = vec![];
 	let mut has_error = false;
-	let mut blocks = blocks.into_iter();
+	let mut blocks = blocks.into_iter().peekable();
+	let mut speculative = None;
 
 	// Blocks in the response/drain should be in ascending order.
 	loop {
@@ -365,13 +439,18 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'stat
 			Err(BlockImportError::Cancelled)
 		} else {
 			// The actual import.
-			import_single_block_metered(
+			import_pipelined_block(
 				import_handle,
 				blocks_origin.clone(),
 				block,
+				speculative.take(),
+				blocks.peek(),
 				verifier,
 				metrics.clone(),
-			).await
+			).await.map(|(result, next)| {
+				speculative = next;
+				result
+			})
 		};
 
 		if let Some(metrics) = metrics.as_ref() {
@@ -400,6 +479,58 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'stat
 	}
 }
 
+/// Speculative verification of the next block of a batch, started before its parent is imported.
+type SpeculativeVerification<B> = oneshot::Receiver<(VerificationResult<B>, Duration)>;
+
+/// Import a single block of a batch, starting the verification of `next` before importing it.
+///
+/// Returns the import result along with the speculative verification of `next`, if any.
+async fn import_pipelined_block<B: BlockT, Transaction: Send + 'static>(
+	import_handle: &mut BoxBlockImport<B, Transaction>,
+	block_origin: BlockOrigin,
+	block: IncomingBlock<B>,
+	speculative: Option<SpeculativeVerification<B>>,
+	next: Option<&IncomingBlock<B>>,
+	verifier: &VerifierHandle<B>,
+	metrics: Option<Metrics>,
+) -> Result<
+	(BlockImportResult<NumberFor<B>>, Option<SpeculativeVerification<B>>),
+	BlockImportError,
+> {
+	let meta = BlockMeta::new(&block)?;
+
+	if let Some(r) = check_single_block(import_handle, &meta).await? {
+		return Ok((r, None))
+	}
+
+	let speculative = match speculative {
+		Some(speculative) => speculative.await.ok().filter(|(verified, _)| verified.is_ok()),
+		None => None,
+	};
+
+	let (verified, verification_time) = match speculative {
+		Some(verified) => verified,
+		None => verifier.verify(block_origin.clone(), &meta, block).await.map_err(|_| {
+			BlockImportError::VerificationFailed(meta.peer.clone(), "Verifier is no longer alive".into())
+		})?,
+	};
+
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_verification(verified.is_ok(), verification_time);
+	}
+
+	let verified = verified?;
+
+	// Verify the next block while this one is imported.
+	let next = next
+		.and_then(|next| BlockMeta::new(next).ok().map(|meta| (meta, next.clone())))
+		.map(|(meta, next)| verifier.verify(block_origin, &meta, next));
+
+	let imported = import_verified_block(import_handle, &meta, verified, verification_time, metrics).await?;
+
+	Ok((imported, next))
+}
+
 /// A future that will always `yield` on the first call of `poll` but schedules the current task for
 /// re-execution.
 
@@ -524,9 +655,10 @@ mod tests {
 	fn prioritizes_finality_work_over_block_import() {
 		let (result_sender, mut result_port) = buffered_link::buffered_link();
 
-		let (worker, mut finality_sender, mut block_import_sender) =
+		let (worker, verification, mut finality_sender, mut block_import_sender) =
 			BlockImportWorker::new(result_sender, (), Box::new(()), Some(Box::new(())), None);
 		futures::pin_mut!(worker);
+		futures::pin_mut!(verification);
 
 		let mut import_block = |n| {
 			let header = Header {
@@ -590,6 +722,11 @@ mod tests {
 					Poll::Ready(()) => panic!("import queue worker should not conclude."),
 				}
 
+				match Future::poll(Pin::new(&mut verification), cx) {
+					Poll::Pending => {}
+					Poll::Ready(()) => panic!("block verification should not conclude."),
+				}
+
 				result_port.poll_actions(cx, &mut link).unwrap();
 			}
 
@@ -612,4 +749,102 @@ mod tests {
 			]
 		);
 	}
+
+	/// Blocks imported by `ChainImport`, shared with `ChainVerifier`.
+	type Imported = std::sync::Arc<std::sync::Mutex<std::collections::HashSet<Hash>>>;
+
+	/// Only accepts blocks whose parent was already imported, other than the first one.
+	struct ChainVerifier(Imported);
+
+	#[async_trait::async_trait]
+	impl Verifier<Block> for ChainVerifier {
+		async fn verify(
+			&mut self,
+			origin: BlockOrigin,
+			header: Header,
+			_justifications: Option<Justifications>,
+			_body: Option<Vec<Extrinsic>>,
+		) -> Result<(BlockImportParams<Block, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+			if header.number > 1 && !self.0.lock().unwrap().contains(&header.parent_hash) {
+				return Err("Parent is not imported".into())
+			}
+			Ok((BlockImportParams::new(origin, header), None))
+		}
+	}
+
+	struct ChainImport(Imported);
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for ChainImport {
+		type Error = crate::Error;
+		type Transaction = Extrinsic;
+
+		async fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		async fn import_block(
+			&mut self,
+			block: BlockImportParams<Block, Self::Transaction>,
+			_cache: HashMap<CacheKeyId, Vec<u8>>,
+		) -> Result<ImportResult, Self::Error> {
+			self.0.lock().unwrap().insert(block.header.hash());
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	#[test]
+	fn retries_speculative_verification_after_parent_import() {
+		let imported = Imported::default();
+		let mut block_import: BoxBlockImport<Block, Extrinsic> = Box::new(ChainImport(imported.clone()));
+		let (sender, receiver) = tracing_unbounded("mpsc_test_verification");
+		let verifier = VerifierHandle { sender };
+
+		let mut parent_hash = Hash::random();
+		let blocks = (1..=5).map(|number| {
+			let header = Header {
+				parent_hash,
+				number,
+				extrinsics_root: Hash::random(),
+				state_root: Default::default(),
+				digest: Default::default(),
+			};
+			parent_hash = header.hash();
+
+			IncomingBlock {
+				hash: parent_hash,
+				header: Some(header),
+				body: None,
+				justifications: None,
+				origin: None,
+				allow_missing_state: false,
+				import_existing: false,
+			}
+		}).collect();
+
+		let import = async move {
+			let res = import_many_blocks(
+				&mut block_import,
+				BlockOrigin::NetworkInitialSync,
+				blocks,
+				&verifier,
+				Duration::default(),
+				None,
+			).await;
+			drop(verifier);
+			res
+		};
+
+		let (res, ()) = block_on(future::join(
+			import,
+			block_verification_process(ChainVerifier(imported.clone()), receiver),
+		));
+
+		assert_eq!(res.imported, 5);
+		assert!(res.results.iter().all(|(r, _)| r.is_ok()));
+		assert_eq!(imported.lock().unwrap().len(), 5);
+	}
 }
@@ -178,6 +178,15 @@ impl From<Pair> for Public {
 	}
 }
 
+#[cfg(feature = "std")]
+impl std::str::FromStr for Public {
+	type Err = crate::crypto::PublicError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::from_ss58check(s)
+	}
+}
+
 impl UncheckedFrom<[u8; 33]> for Public {
 	fn unchecked_from(x: [u8; 33]) -> Self {
 		Public(x)
@@ -675,6 +684,7 @@ mod test {
 		println!("Correct: {}", s);
 		let cmp = Public::from_ss58check(&s).unwrap();
 		assert_eq!(cmp, public);
+		assert_eq!(s.parse::<Public>(), Ok(public));
 	}
 
 	#[test]
@@ -554,6 +554,31 @@ impl Pair {
 	}
 }
 
+/// Verify a batch of signatures at once, `signatures[i]` being the signature of `messages[i]`
+/// by `public_keys[i]`.
+///
+/// Returns `true` only if all the signatures are valid. Faster than verifying the signatures one
+/// by one, but the batch equation is cofactored: it may accept signatures with a small order
+/// component that [`Pair::verify`] rejects.
+#[cfg(feature = "std")]
+pub fn verify_batch(messages: &[&[u8]], signatures: &[Signature], public_keys: &[Public]) -> bool {
+	if messages.len() != signatures.len() || messages.len() != public_keys.len() {
+		return false
+	}
+
+	let signatures = signatures.iter()
+		.map(|signature| ed25519_dalek::Signature::try_from(&signature.0[..]))
+		.collect::<Result<Vec<_>, _>>();
+	let public_keys = public_keys.iter()
+		.map(|public| ed25519_dalek::PublicKey::from_bytes(&public.0))
+		.collect::<Result<Vec<_>, _>>();
+	match (signatures, public_keys) {
+		(Ok(signatures), Ok(public_keys)) =>
+			ed25519_dalek::verify_batch(messages, &signatures, &public_keys).is_ok(),
+		_ => false,
+	}
+}
+
 impl CryptoType for Public {
 	#[cfg(feature = "full_crypto")]
 	type Pair = Pair;
@@ -710,4 +735,22 @@ mod test {
 		// Poorly-sized
 		assert!(deserialize_signature("\"abc123\"").is_err());
 	}
+
+	#[test]
+	fn verify_batch_works() {
+		let pairs = (0u8..3).map(|i| Pair::from_seed(&[i; 32])).collect::<Vec<_>>();
+		let messages: Vec<&[u8]> = vec![b"first", b"second", b"third"];
+		let mut signatures = pairs.iter()
+			.zip(&messages)
+			.map(|(pair, message)| pair.sign(message))
+			.collect::<Vec<_>>();
+		let public_keys = pairs.iter().map(|pair| pair.public()).collect::<Vec<_>>();
+
+		assert!(verify_batch(&messages, &signatures, &public_keys));
+		assert!(verify_batch(&[], &[], &[]));
+		assert!(!verify_batch(&messages[..2], &signatures, &public_keys));
+
+		signatures.swap(0, 1);
+		assert!(!verify_batch(&messages, &signatures, &public_keys));
+	}
 }
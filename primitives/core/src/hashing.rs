@@ -23,7 +23,23 @@
 //! computing the hash in WASM it delegates that computation to the host client.
 
 use sha2::{Digest, Sha256};
+use ripemd160::Ripemd160;
 use tiny_keccak::{Hasher, Keccak};
+use sp_std::vec::Vec;
+
+/// Maximum output and key length of a Blake2b hash, in bytes.
+pub const BLAKE2B_MAX_LEN: usize = 64;
+
+/// Do a keyed Blake2 hash with an output of `out_len` bytes and return result.
+///
+/// Returns `None` if `out_len` is zero or if `out_len` or the length of `key` are above
+/// [`BLAKE2B_MAX_LEN`].
+pub fn blake2b(data: &[u8], key: &[u8], out_len: usize) -> Option<Vec<u8>> {
+	if out_len == 0 || out_len > BLAKE2B_MAX_LEN || key.len() > BLAKE2B_MAX_LEN {
+		return None
+	}
+	Some(blake2_rfc::blake2b::blake2b(out_len, key, data).as_bytes().to_vec())
+}
 
 /// Do a Blake2 512-bit hash and place result in `dest`.
 pub fn blake2_512_into(data: &[u8], dest: &mut [u8; 64]) {
@@ -166,3 +182,12 @@ pub fn sha2_256(data: &[u8]) -> [u8; 32] {
 	output.copy_from_slice(&hasher.finalize());
 	output
 }
+
+/// Do a ripemd 160-bit hash and return result.
+pub fn ripemd_160(data: &[u8]) -> [u8; 20] {
+	let mut hasher = Ripemd160::new();
+	hasher.update(data);
+	let mut output = [0u8; 20];
+	output.copy_from_slice(&hasher.finalize());
+	output
+}
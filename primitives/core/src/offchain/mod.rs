@@ -24,6 +24,7 @@ use sp_runtime_interface::pass_by::{PassByCodec, PassByInner, PassByEnum};
 
 pub use crate::crypto::KeyTypeId;
 
+pub mod network;
 #[cfg(feature = "std")]
 pub mod storage;
 #[cfg(feature = "std")]
@@ -32,6 +33,27 @@ pub mod testing;
 /// Persistent storage prefix used by the Offchain Worker API when creating a DB key.
 pub const STORAGE_PREFIX : &[u8] = b"storage";
 
+/// Persistent storage prefix reserved for the node itself.
+///
+/// The runtime only reaches keys under [`STORAGE_PREFIX`], and the storage quotas don't account
+/// for the keys under this prefix.
+pub const NODE_STORAGE_PREFIX: &[u8] = b"node";
+
+/// Key under [`NODE_STORAGE_PREFIX`] of the persistent storage, under which the node records the
+/// offchain worker executions which kept failing, as a SCALE-encoded `Vec<WorkerFailure>`.
+pub const WORKER_FAILURES_KEY: &[u8] = b":offchain_worker_failures:";
+
+/// An offchain worker execution which kept failing after all its retries.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub struct WorkerFailure {
+	/// Encoded hash of the block the offchain workers ran on.
+	pub block_hash: Vec<u8>,
+	/// Number of executions attempted.
+	pub attempts: u32,
+	/// UTF-8 description of the last error.
+	pub error: Vec<u8>,
+}
+
 /// Offchain DB persistent (non-fork-aware) storage.
 pub trait OffchainStorage: Clone + Send + Sync {
 	/// Persist a value in storage under given key and prefix.
@@ -53,6 +75,23 @@ pub trait OffchainStorage: Clone + Send + Sync {
 		old_value: Option<&[u8]>,
 		new_value: &[u8],
 	) -> bool;
+
+	/// Apply the given changes at once.
+	///
+	/// Each change is a prefix, a key and the new value, `None` clearing the entry. Storages
+	/// backed by a database write them in a single transaction. By default the changes are
+	/// applied one by one.
+	fn apply_batch<'a>(
+		&mut self,
+		changes: impl IntoIterator<Item = (&'a [u8], &'a [u8], Option<&'a [u8]>)>,
+	) {
+		for (prefix, key, value) in changes {
+			match value {
+				Some(value) => self.set(prefix, key, value),
+				None => self.remove(prefix, key),
+			}
+		}
+	}
 }
 
 /// A type of supported crypto.
@@ -134,6 +173,50 @@ impl From<HttpError> for u32 {
 	}
 }
 
+/// Opaque type for offchain TCP sockets.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug, Encode, Decode, PassByInner)]
+#[cfg_attr(feature = "std", derive(Hash))]
+pub struct TcpSocketId(pub u16);
+
+impl From<TcpSocketId> for u32 {
+	fn from(c: TcpSocketId) -> Self {
+		c.0 as u32
+	}
+}
+
+/// An error enum returned by the TCP socket methods.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, PassByEnum)]
+#[repr(C)]
+pub enum TcpError {
+	/// The requested action couldn't been completed within a deadline.
+	DeadlineReached = 1,
+	/// There was an IO Error while connecting or using the socket.
+	IoError = 2,
+	/// The address or the ID of the socket is invalid in this context.
+	///
+	/// This includes the addresses the node does not allow connecting to.
+	Invalid = 3,
+}
+
+impl TryFrom<u32> for TcpError {
+	type Error = ();
+
+	fn try_from(error: u32) -> Result<Self, Self::Error> {
+		match error {
+			e if e == TcpError::DeadlineReached as u8 as u32 => Ok(TcpError::DeadlineReached),
+			e if e == TcpError::IoError as u8 as u32 => Ok(TcpError::IoError),
+			e if e == TcpError::Invalid as u8 as u32 => Ok(TcpError::Invalid),
+			_ => Err(())
+		}
+	}
+}
+
+impl From<TcpError> for u32 {
+	fn from(c: TcpError) -> Self {
+		c as u8 as u32
+	}
+}
+
 /// Status of the HTTP request
 #[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, PassByCodec)]
 pub enum HttpRequestStatus {
@@ -254,7 +337,7 @@ impl Timestamp {
 pub enum Capability {
 	/// Access to transaction pool.
 	TransactionPool = 1,
-	/// External http calls.
+	/// External http calls and raw TCP sockets.
 	Http = 2,
 	/// Keystore access.
 	Keystore = 4,
@@ -451,6 +534,49 @@ pub trait Externalities: Send {
 		deadline: Option<Timestamp>
 	) -> Result<usize, HttpError>;
 
+	/// Opens a TCP connection to `address`, given as `host:port`.
+	///
+	/// Returns an error if:
+	/// - The address is malformed or not allowed by the node. Returns `TcpError::Invalid`.
+	/// - The deadline is reached before the connection is established.
+	/// - An I/O error has happened, for example the remote has refused the connection.
+	///
+	fn tcp_connect(
+		&mut self,
+		address: &str,
+		deadline: Option<Timestamp>
+	) -> Result<TcpSocketId, TcpError>;
+
+	/// Writes all of `data` to the given socket.
+	///
+	/// Returns an error if the socket identifier is invalid, if the deadline is reached or
+	/// if an I/O error has happened. After an I/O error the socket is closed and its
+	/// identifier becomes invalid.
+	fn tcp_write(
+		&mut self,
+		socket_id: TcpSocketId,
+		data: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), TcpError>;
+
+	/// Reads from the given socket into `buffer`, returning the number of bytes read.
+	///
+	/// Blocks until at least one byte is available. `0` is returned when the remote has
+	/// closed its side of the connection; the socket is then closed and its identifier
+	/// becomes invalid.
+	///
+	/// Returns an error if the socket identifier is invalid, if the deadline is reached or
+	/// if an I/O error has happened.
+	fn tcp_read(
+		&mut self,
+		socket_id: TcpSocketId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, TcpError>;
+
+	/// Closes the given socket. Does nothing if the identifier is invalid.
+	fn tcp_close(&mut self, socket_id: TcpSocketId);
+
 	/// Set the authorized nodes from runtime.
 	///
 	/// In a permissioned network, the connections between nodes need to reach a
@@ -519,6 +645,36 @@ impl<T: Externalities + ?Sized> Externalities for Box<T> {
 		(&mut **self).http_response_read_body(request_id, buffer, deadline)
 	}
 
+	fn tcp_connect(
+		&mut self,
+		address: &str,
+		deadline: Option<Timestamp>
+	) -> Result<TcpSocketId, TcpError> {
+		(&mut **self).tcp_connect(address, deadline)
+	}
+
+	fn tcp_write(
+		&mut self,
+		socket_id: TcpSocketId,
+		data: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), TcpError> {
+		(&mut **self).tcp_write(socket_id, data, deadline)
+	}
+
+	fn tcp_read(
+		&mut self,
+		socket_id: TcpSocketId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, TcpError> {
+		(&mut **self).tcp_read(socket_id, buffer, deadline)
+	}
+
+	fn tcp_close(&mut self, socket_id: TcpSocketId) {
+		(&mut **self).tcp_close(socket_id)
+	}
+
 	fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
 		(&mut **self).set_authorized_nodes(nodes, authorized_only)
 	}
@@ -615,6 +771,40 @@ impl<T: Externalities> Externalities for LimitedExternalities<T> {
 		self.externalities.http_response_read_body(request_id, buffer, deadline)
 	}
 
+	fn tcp_connect(
+		&mut self,
+		address: &str,
+		deadline: Option<Timestamp>
+	) -> Result<TcpSocketId, TcpError> {
+		self.check(Capability::Http, "tcp_connect");
+		self.externalities.tcp_connect(address, deadline)
+	}
+
+	fn tcp_write(
+		&mut self,
+		socket_id: TcpSocketId,
+		data: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), TcpError> {
+		self.check(Capability::Http, "tcp_write");
+		self.externalities.tcp_write(socket_id, data, deadline)
+	}
+
+	fn tcp_read(
+		&mut self,
+		socket_id: TcpSocketId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, TcpError> {
+		self.check(Capability::Http, "tcp_read");
+		self.externalities.tcp_read(socket_id, buffer, deadline)
+	}
+
+	fn tcp_close(&mut self, socket_id: TcpSocketId) {
+		self.check(Capability::Http, "tcp_close");
+		self.externalities.tcp_close(socket_id)
+	}
+
 	fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
 		self.check(Capability::NodeAuthorization, "set_authorized_nodes");
 		self.externalities.set_authorized_nodes(nodes, authorized_only)
@@ -672,6 +862,18 @@ pub trait DbExternalities: Send {
 	/// Note this storage is not part of the consensus, it's only accessible by
 	/// offchain worker tasks running on the same machine. It _is_ persisted between runs.
 	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Sets a value in the persistent storage, under [`NODE_STORAGE_PREFIX`].
+	///
+	/// This is for the node itself and not exposed to the runtime. Does nothing by default.
+	fn node_storage_set(&mut self, _key: &[u8], _value: &[u8]) {}
+
+	/// Gets a value from the persistent storage, under [`NODE_STORAGE_PREFIX`].
+	///
+	/// This is for the node itself and not exposed to the runtime. Returns `None` by default.
+	fn node_storage_get(&mut self, _key: &[u8]) -> Option<Vec<u8>> {
+		None
+	}
 }
 
 impl<T: DbExternalities + ?Sized> DbExternalities for Box<T> {
@@ -696,6 +898,14 @@ impl<T: DbExternalities + ?Sized> DbExternalities for Box<T> {
 	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>> {
 		(&mut **self).local_storage_get(kind, key)
 	}
+
+	fn node_storage_set(&mut self, key: &[u8], value: &[u8]) {
+		(&mut **self).node_storage_set(key, value)
+	}
+
+	fn node_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		(&mut **self).node_storage_get(key)
+	}
 }
 
 impl<T: DbExternalities> DbExternalities for LimitedExternalities<T> {
@@ -767,6 +977,32 @@ impl TransactionPoolExt {
 	}
 }
 
+/// Read-only abstraction over transaction pool.
+///
+/// Like [`TransactionPool`], it is used within the `ExternalitiesExtension` to let offchain
+/// calls see the transactions pending in the pool.
+#[cfg(feature = "std")]
+pub trait TransactionPoolReader {
+	/// Returns the hash and the encoding of the transactions ready to be included in a block.
+	///
+	/// Returns at most `max_count` transactions, whose encodings add up to at most `max_bytes`.
+	fn ready_transactions(&self, max_count: u32, max_bytes: u32) -> Vec<(Vec<u8>, Vec<u8>)>;
+}
+
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+	/// An externalities extension to read the transactions pending in the pool.
+	pub struct TransactionPoolReaderExt(Box<dyn TransactionPoolReader + Send>);
+}
+
+#[cfg(feature = "std")]
+impl TransactionPoolReaderExt {
+	/// Create a new instance of `TransactionPoolReaderExt`.
+	pub fn new<O: TransactionPoolReader + Send + 'static>(pool: O) -> Self {
+		Self(Box::new(pool))
+	}
+}
+
 /// Change to be applied to the offchain worker db in regards to a key.
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum OffchainOverlayedChange {
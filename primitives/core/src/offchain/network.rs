@@ -0,0 +1,361 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed network state of a node.
+//!
+//! The network state is handed to the runtime as an [`OpaqueNetworkState`], which holds the
+//! SCALE-encoded bytes of the peer id and the SCALE-encoded textual form of the addresses of the
+//! node. The types of this module are obtained by validating these blobs, so that the runtime can
+//! reject network states that were not produced by a node.
+
+use codec::{Encode, Decode};
+use sp_std::{prelude::Vec, convert::TryFrom, str::{self, FromStr}};
+use crate::{OpaquePeerId, RuntimeDebug};
+use super::{OpaqueNetworkState, OpaqueMultiaddr};
+
+/// Multihash code of the identity hash, used for the peer ids of small public keys.
+const IDENTITY_CODE: u64 = 0x00;
+
+/// Maximum length of an identity peer id digest.
+const MAX_IDENTITY_DIGEST_LEN: u64 = 42;
+
+/// Multihash code of SHA2-256.
+const SHA2_256_CODE: u64 = 0x12;
+
+/// Length of a SHA2-256 digest.
+const SHA2_256_DIGEST_LEN: u64 = 32;
+
+/// Error validating a network state.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum InvalidNetworkState {
+	/// The peer id is not a valid multihash.
+	PeerId,
+	/// One of the addresses is not a valid multiaddress.
+	Multiaddr,
+}
+
+/// A peer id, as the bytes of a multihash.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PeerId(Vec<u8>);
+
+impl PeerId {
+	/// Validate the bytes of a peer id.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, InvalidNetworkState> {
+		let mut input = bytes;
+		let code = read_varint(&mut input).ok_or(InvalidNetworkState::PeerId)?;
+		let len = read_varint(&mut input).ok_or(InvalidNetworkState::PeerId)?;
+
+		let valid = len == input.len() as u64 && match code {
+			IDENTITY_CODE => len <= MAX_IDENTITY_DIGEST_LEN,
+			SHA2_256_CODE => len == SHA2_256_DIGEST_LEN,
+			_ => false,
+		};
+
+		if valid {
+			Ok(PeerId(bytes.to_vec()))
+		} else {
+			Err(InvalidNetworkState::PeerId)
+		}
+	}
+
+	/// Validate a peer id given in base58, as found in multiaddresses.
+	pub fn from_base58(s: &str) -> Result<Self, InvalidNetworkState> {
+		let bytes = decode_base58(s).ok_or(InvalidNetworkState::PeerId)?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// The bytes of the peer id.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl TryFrom<&OpaquePeerId> for PeerId {
+	type Error = InvalidNetworkState;
+
+	fn try_from(peer_id: &OpaquePeerId) -> Result<Self, Self::Error> {
+		let bytes = Vec::<u8>::decode(&mut &peer_id.0[..])
+			.map_err(|_| InvalidNetworkState::PeerId)?;
+		Self::from_bytes(&bytes)
+	}
+}
+
+impl From<PeerId> for OpaquePeerId {
+	fn from(peer_id: PeerId) -> Self {
+		OpaquePeerId::new(peer_id.0.encode())
+	}
+}
+
+/// A multiaddress, in its textual form, e.g. `/ip4/127.0.0.1/tcp/30333`.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Multiaddr(Vec<u8>);
+
+/// Argument expected after the name of a protocol in a multiaddress.
+enum Argument {
+	None,
+	Ip4,
+	Ip6,
+	Port,
+	Name,
+	Number,
+	PeerId,
+}
+
+fn protocol_argument(name: &str) -> Option<Argument> {
+	Some(match name {
+		"ip4" => Argument::Ip4,
+		"ip6" => Argument::Ip6,
+		"tcp" | "udp" | "dccp" | "sctp" => Argument::Port,
+		"dns" | "dns4" | "dns6" | "dnsaddr" | "ip6zone" | "onion" | "onion3" => Argument::Name,
+		"memory" => Argument::Number,
+		"p2p" | "ipfs" => Argument::PeerId,
+		"ws" | "wss" | "http" | "https" | "quic" | "utp" | "udt" | "p2p-circuit"
+			| "p2p-webrtc-star" | "p2p-webrtc-direct" | "p2p-websocket-star" => Argument::None,
+		_ => return None,
+	})
+}
+
+fn is_number(s: &str) -> bool {
+	!s.is_empty() && s.bytes().all(|c| c.is_ascii_digit())
+}
+
+fn is_valid_argument(argument: &Argument, value: &str) -> bool {
+	match argument {
+		Argument::None => true,
+		Argument::Ip4 => value.split('.').count() == 4
+			&& value.split('.').all(|octet| is_number(octet) && octet.parse::<u8>().is_ok()),
+		Argument::Ip6 => value.contains(':')
+			&& value.bytes().all(|c| c.is_ascii_hexdigit() || c == b':' || c == b'.'),
+		Argument::Port => is_number(value) && value.parse::<u16>().is_ok(),
+		Argument::Name => !value.is_empty()
+			&& value.bytes().all(|c| c.is_ascii_graphic()),
+		Argument::Number => is_number(value) && value.parse::<u64>().is_ok(),
+		Argument::PeerId => PeerId::from_base58(value).is_ok(),
+	}
+}
+
+impl FromStr for Multiaddr {
+	type Err = InvalidNetworkState;
+
+	/// Validate the textual form of a multiaddress.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = match s.strip_prefix('/') {
+			Some(rest) if !rest.is_empty() => rest.split('/'),
+			_ => return Err(InvalidNetworkState::Multiaddr),
+		};
+
+		while let Some(name) = parts.next() {
+			let argument = protocol_argument(name).ok_or(InvalidNetworkState::Multiaddr)?;
+			let valid = match argument {
+				Argument::None => true,
+				argument => parts.next().map_or(false, |value| is_valid_argument(&argument, value)),
+			};
+			if !valid {
+				return Err(InvalidNetworkState::Multiaddr)
+			}
+		}
+
+		Ok(Multiaddr(s.as_bytes().to_vec()))
+	}
+}
+
+impl Multiaddr {
+	/// The textual form of the multiaddress.
+	pub fn as_str(&self) -> &str {
+		str::from_utf8(&self.0).expect("Multiaddresses are only created from strings; qed")
+	}
+}
+
+impl TryFrom<&OpaqueMultiaddr> for Multiaddr {
+	type Error = InvalidNetworkState;
+
+	fn try_from(multiaddr: &OpaqueMultiaddr) -> Result<Self, Self::Error> {
+		let bytes = Vec::<u8>::decode(&mut &multiaddr.0[..])
+			.map_err(|_| InvalidNetworkState::Multiaddr)?;
+		let s = str::from_utf8(&bytes).map_err(|_| InvalidNetworkState::Multiaddr)?;
+		Self::from_str(s)
+	}
+}
+
+impl From<Multiaddr> for OpaqueMultiaddr {
+	fn from(multiaddr: Multiaddr) -> Self {
+		OpaqueMultiaddr::new(multiaddr.0.encode())
+	}
+}
+
+/// The validated network state of a node.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct NetworkState {
+	/// The peer id of the node.
+	pub peer_id: PeerId,
+	/// The addresses the node knows it can be reached at.
+	pub external_addresses: Vec<Multiaddr>,
+}
+
+impl TryFrom<&OpaqueNetworkState> for NetworkState {
+	type Error = InvalidNetworkState;
+
+	fn try_from(state: &OpaqueNetworkState) -> Result<Self, Self::Error> {
+		Ok(NetworkState {
+			peer_id: PeerId::try_from(&state.peer_id)?,
+			external_addresses: state.external_addresses
+				.iter()
+				.map(Multiaddr::try_from)
+				.collect::<Result<_, _>>()?,
+		})
+	}
+}
+
+impl From<NetworkState> for OpaqueNetworkState {
+	fn from(state: NetworkState) -> Self {
+		OpaqueNetworkState {
+			peer_id: state.peer_id.into(),
+			external_addresses: state.external_addresses.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Read an unsigned varint from the start of `input`, advancing it.
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+	let bytes: &[u8] = *input;
+	let mut value = 0u64;
+	// A `u64` takes at most 10 groups of 7 bits, but multihash varints are limited to 9 bytes.
+	for (i, byte) in bytes.iter().take(9).enumerate() {
+		value |= u64::from(byte & 0x7f) << (7 * i);
+		if byte & 0x80 == 0 {
+			*input = &bytes[i + 1..];
+			return Some(value)
+		}
+	}
+	None
+}
+
+/// Decode a string in the bitcoin base58 alphabet.
+fn decode_base58(s: &str) -> Option<Vec<u8>> {
+	const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+	// Little-endian digits of the decoded number.
+	let mut bytes = Vec::new();
+	for c in s.bytes() {
+		let mut carry = ALPHABET.iter().position(|&a| a == c)? as u32;
+		for byte in bytes.iter_mut() {
+			carry += u32::from(*byte) * 58;
+			*byte = carry as u8;
+			carry >>= 8;
+		}
+		while carry > 0 {
+			bytes.push(carry as u8);
+			carry >>= 8;
+		}
+	}
+
+	let leading_zeros = s.bytes().take_while(|&c| c == b'1').count();
+	let mut decoded = sp_std::vec![0; leading_zeros];
+	decoded.extend(bytes.iter().rev());
+	Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ed25519_peer_id() -> Vec<u8> {
+		let mut peer_id = vec![0x00, 0x24, 0x08, 0x01, 0x12, 0x20];
+		peer_id.extend_from_slice(&[7; 32]);
+		peer_id
+	}
+
+	fn encode_base58(bytes: &[u8]) -> String {
+		const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+		let mut digits: Vec<u8> = Vec::new();
+		for byte in bytes {
+			let mut carry = u32::from(*byte);
+			for digit in digits.iter_mut() {
+				carry += u32::from(*digit) << 8;
+				*digit = (carry % 58) as u8;
+				carry /= 58;
+			}
+			while carry > 0 {
+				digits.push((carry % 58) as u8);
+				carry /= 58;
+			}
+		}
+		let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+		std::iter::repeat('1').take(zeros)
+			.chain(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char))
+			.collect()
+	}
+
+	#[test]
+	fn validates_peer_ids() {
+		assert!(PeerId::from_bytes(&ed25519_peer_id()).is_ok());
+
+		let mut sha256 = vec![0x12, 0x20];
+		sha256.extend_from_slice(&[1; 32]);
+		assert!(PeerId::from_bytes(&sha256).is_ok());
+
+		assert_eq!(PeerId::from_bytes(&[]), Err(InvalidNetworkState::PeerId));
+		assert_eq!(PeerId::from_bytes(&[1]), Err(InvalidNetworkState::PeerId));
+		assert_eq!(PeerId::from_bytes(&sha256[..20]), Err(InvalidNetworkState::PeerId));
+		assert_eq!(PeerId::from_bytes(&[0x13, 0x01, 0x00]), Err(InvalidNetworkState::PeerId));
+
+		let base58 = encode_base58(&ed25519_peer_id());
+		assert_eq!(PeerId::from_base58(&base58).unwrap().as_bytes(), &ed25519_peer_id()[..]);
+		assert!(PeerId::from_base58("0OIl").is_err());
+	}
+
+	#[test]
+	fn validates_multiaddrs() {
+		let peer_id = encode_base58(&ed25519_peer_id());
+		let valid = [
+			"/ip4/127.0.0.1/tcp/30333".to_owned(),
+			"/ip6/::1/tcp/30333/ws".to_owned(),
+			"/dns4/example.com/tcp/443/wss".to_owned(),
+			format!("/ip4/10.0.0.1/tcp/30333/p2p/{}", peer_id),
+		];
+		for addr in &valid {
+			assert_eq!(Multiaddr::from_str(addr).unwrap().as_str(), addr);
+		}
+
+		let invalid = [
+			"", "/", "ip4/127.0.0.1", "/ip4/256.0.0.1", "/ip4/127.0.0.1/tcp", "/ip4/1.2.3.4/tcp/+1",
+			"/tcp/65536", "/foo/bar", "/ip4/127.0.0.1/", "/p2p/QmInvalid",
+		];
+		for addr in &invalid {
+			assert_eq!(Multiaddr::from_str(addr), Err(InvalidNetworkState::Multiaddr), "{}", addr);
+		}
+	}
+
+	#[test]
+	fn network_state_round_trips_through_opaque_state() {
+		let state = NetworkState {
+			peer_id: PeerId::from_bytes(&ed25519_peer_id()).unwrap(),
+			external_addresses: vec![Multiaddr::from_str("/ip4/127.0.0.1/tcp/30333").unwrap()],
+		};
+		let opaque = OpaqueNetworkState::from(state.clone());
+
+		// The node encodes the addresses as strings.
+		assert_eq!(opaque.external_addresses[0].0, "/ip4/127.0.0.1/tcp/30333".encode());
+		assert_eq!(NetworkState::try_from(&opaque), Ok(state));
+
+		let invalid = OpaqueNetworkState {
+			peer_id: OpaquePeerId(vec![1]),
+			external_addresses: Vec::new(),
+		};
+		assert_eq!(NetworkState::try_from(&invalid), Err(InvalidNetworkState::PeerId));
+	}
+}
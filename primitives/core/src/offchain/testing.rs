@@ -36,7 +36,10 @@ use crate::offchain::{
 	StorageKind,
 	OpaqueNetworkState,
 	TransactionPool,
+	TransactionPoolReader,
 	OffchainStorage,
+	TcpError,
+	TcpSocketId,
 };
 
 use parking_lot::RwLock;
@@ -189,6 +192,13 @@ impl OffchainState {
 		}
 		self.expected_requests.push_front(expected);
 	}
+
+	/// Remove the expected HTTP requests which haven't been performed yet, in insertion order.
+	///
+	/// Dropping the state once they are taken doesn't panic.
+	pub fn take_unfulfilled_requests(&mut self) -> Vec<PendingRequest> {
+		std::mem::take(&mut self.expected_requests).into_iter().rev().collect()
+	}
 }
 
 impl Drop for OffchainState {
@@ -350,6 +360,35 @@ impl offchain::Externalities for TestOffchainExt {
 		}
 	}
 
+	fn tcp_connect(
+		&mut self,
+		_address: &str,
+		_deadline: Option<Timestamp>
+	) -> Result<TcpSocketId, TcpError> {
+		// No sockets are available in tests; every address is refused.
+		Err(TcpError::Invalid)
+	}
+
+	fn tcp_write(
+		&mut self,
+		_socket_id: TcpSocketId,
+		_data: &[u8],
+		_deadline: Option<Timestamp>
+	) -> Result<(), TcpError> {
+		Err(TcpError::Invalid)
+	}
+
+	fn tcp_read(
+		&mut self,
+		_socket_id: TcpSocketId,
+		_buffer: &mut [u8],
+		_deadline: Option<Timestamp>
+	) -> Result<usize, TcpError> {
+		Err(TcpError::Invalid)
+	}
+
+	fn tcp_close(&mut self, _socket_id: TcpSocketId) {}
+
 	fn set_authorized_nodes(&mut self, _nodes: Vec<OpaquePeerId>, _authorized_only: bool) {
 		unimplemented!()
 	}
@@ -431,3 +470,17 @@ impl TransactionPool for TestTransactionPoolExt {
 		Ok(())
 	}
 }
+
+impl TransactionPoolReader for TestTransactionPoolExt {
+	fn ready_transactions(&self, max_count: u32, max_bytes: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let mut total_bytes = 0usize;
+		self.0.read().transactions.iter()
+			.take(max_count as usize)
+			.take_while(|xt| {
+				total_bytes = total_bytes.saturating_add(xt.len());
+				total_bytes <= max_bytes as usize
+			})
+			.map(|xt| (crate::hashing::blake2_256(xt).to_vec(), xt.clone()))
+			.collect()
+	}
+}
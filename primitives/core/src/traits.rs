@@ -186,6 +186,29 @@ impl TaskExecutorExt {
 	}
 }
 
+sp_externalities::decl_extension! {
+	/// Task executor used for batch signature verification.
+	///
+	/// When registered, it takes precedence over [`TaskExecutorExt`] for the
+	/// verification tasks only.
+	pub struct VerificationExecutorExt(Box<dyn SpawnNamed>);
+}
+
+impl VerificationExecutorExt {
+	/// New instance of verification executor extension.
+	pub fn new(spawn_handle: impl SpawnNamed + Send + 'static) -> Self {
+		Self(Box::new(spawn_handle))
+	}
+}
+
+sp_externalities::decl_extension! {
+	/// The properties of the chain, as found in its chain specification.
+	///
+	/// Holds a SCALE-encoded `Vec<(String, String)>` mapping the name of each property to its
+	/// value serialized as JSON.
+	pub struct ChainPropertiesExt(Vec<u8>);
+}
+
 /// Runtime spawn extension.
 pub trait RuntimeSpawn: Send {
 	/// Create new runtime instance and use dynamic dispatch to invoke with specified payload.
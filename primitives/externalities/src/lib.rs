@@ -238,6 +238,13 @@ pub trait Externalities: ExtensionStore {
 		unimplemented!("storage_renew_transaction_index");
 	}
 
+	/// Returns the encoded size of the storage proof recorded so far.
+	///
+	/// `None` if no proof is being recorded.
+	fn proof_size(&self) -> Option<u32> {
+		None
+	}
+
 	/// !!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
 	/// Benchmarking related functionality and shouldn't be used anywhere else!
 	/// !!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
@@ -150,12 +150,25 @@ impl InherentData {
 		}
 	}
 
+	/// Returns the encoded data for the requested inherent, if any.
+	pub fn get_data_encoded(&self, identifier: &InherentIdentifier) -> Option<&[u8]> {
+		self.data.get(identifier).map(|inherent| &inherent[..])
+	}
+
 	/// Get the number of inherents in this instance
 	pub fn len(&self) -> usize {
 		self.data.len()
 	}
 }
 
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+	/// The inherent data of the block being built, exposed to the runtime through the
+	/// `inherent_data` host functions.
+	#[derive(Clone)]
+	pub struct InherentDataExt(InherentData);
+}
+
 /// The result of checking inherents.
 ///
 /// It either returns okay for all checks, stores all occurred errors or just one fatal error.
@@ -0,0 +1,144 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! BLS12-381 signature verification.
+//!
+//! Signatures follow the `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` ciphersuite used by Eth2:
+//! public keys are compressed G1 points and signatures are compressed G2 points.
+
+use bls12_381::{
+	G1Affine, G1Projective, G2Affine, G2Projective, Gt, pairing,
+	hash_to_curve::{ExpandMsgXmd, HashToCurve},
+};
+
+/// Domain separation tag of the signatures.
+pub const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Hash a message to a point of G2.
+pub fn hash_to_g2(message: &[u8], dst: &[u8]) -> G2Affine {
+	<G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(message, dst).into()
+}
+
+fn public_key(bytes: &[u8; 48]) -> Option<G1Affine> {
+	Option::<G1Affine>::from(G1Affine::from_compressed(bytes))
+		.filter(|key| !bool::from(key.is_identity()))
+}
+
+fn signature(bytes: &[u8; 96]) -> Option<G2Affine> {
+	Option::<G2Affine>::from(G2Affine::from_compressed(bytes))
+		.filter(|signature| !bool::from(signature.is_identity()))
+}
+
+/// Verify a signature of `message` by `public`.
+pub fn verify(sig: &[u8; 96], message: &[u8], public: &[u8; 48]) -> bool {
+	aggregate_verify(sig, &[message], &[*public])
+}
+
+/// Verify an aggregated signature of each of the `messages` by the key at the same index in
+/// `publics`.
+///
+/// Returns `false` if there is no message, or if there are not as many messages as keys.
+pub fn aggregate_verify(sig: &[u8; 96], messages: &[&[u8]], publics: &[[u8; 48]]) -> bool {
+	if messages.is_empty() || messages.len() != publics.len() {
+		return false
+	}
+
+	let sig = match signature(sig) {
+		Some(sig) => sig,
+		None => return false,
+	};
+
+	let mut signed = Gt::identity();
+	for (message, public) in messages.iter().zip(publics) {
+		let public = match public_key(public) {
+			Some(public) => public,
+			None => return false,
+		};
+		signed += pairing(&public, &hash_to_g2(message, SIGNATURE_DST));
+	}
+
+	signed == pairing(&G1Affine::generator(), &sig)
+}
+
+/// Verify an aggregated signature of the same `message` by all the `publics`.
+///
+/// Returns `false` if there is no key.
+pub fn fast_aggregate_verify(sig: &[u8; 96], message: &[u8], publics: &[[u8; 48]]) -> bool {
+	if publics.is_empty() {
+		return false
+	}
+
+	let mut aggregated = G1Projective::identity();
+	for public in publics {
+		match public_key(public) {
+			Some(public) => aggregated += public,
+			None => return false,
+		}
+	}
+
+	aggregate_verify(sig, &[message], &[G1Affine::from(aggregated).to_compressed()])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bls12_381::Scalar;
+
+	fn key(secret: u64) -> (Scalar, [u8; 48]) {
+		let secret = Scalar::from(secret);
+		(secret, G1Affine::from(G1Affine::generator() * secret).to_compressed())
+	}
+
+	fn sign(secret: &Scalar, message: &[u8]) -> G2Projective {
+		hash_to_g2(message, SIGNATURE_DST) * secret
+	}
+
+	fn compress(signature: G2Projective) -> [u8; 96] {
+		G2Affine::from(signature).to_compressed()
+	}
+
+	#[test]
+	fn verifies_signatures() {
+		let (secret, public) = key(42);
+		let (_, other) = key(43);
+		let sig = compress(sign(&secret, b"message"));
+
+		assert!(verify(&sig, b"message", &public));
+		assert!(!verify(&sig, b"other message", &public));
+		assert!(!verify(&sig, b"message", &other));
+		assert!(!verify(&[0; 96], b"message", &public));
+	}
+
+	#[test]
+	fn verifies_aggregated_signatures() {
+		let (alice, alice_public) = key(1);
+		let (bob, bob_public) = key(2);
+
+		let sig = compress(sign(&alice, b"alice") + sign(&bob, b"bob"));
+		let (alice_message, bob_message): (&[u8], &[u8]) = (b"alice", b"bob");
+		let publics = [alice_public, bob_public];
+		assert!(aggregate_verify(&sig, &[alice_message, bob_message], &publics));
+		assert!(!aggregate_verify(&sig, &[bob_message, alice_message], &publics));
+		assert!(!aggregate_verify(&sig, &[alice_message], &publics));
+		assert!(!aggregate_verify(&sig, &[], &[]));
+
+		let sig = compress(sign(&alice, b"block") + sign(&bob, b"block"));
+		assert!(fast_aggregate_verify(&sig, b"block", &[alice_public, bob_public]));
+		assert!(!fast_aggregate_verify(&sig, b"block", &[alice_public]));
+		assert!(!fast_aggregate_verify(&sig, b"block", &[]));
+	}
+}
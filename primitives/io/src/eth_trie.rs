@@ -0,0 +1,189 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Roots of Ethereum Merkle Patricia tries.
+//!
+//! Unlike the tries of Substrate, nodes are RLP encoded, paths use the hex-prefix encoding and
+//! nodes shorter than 32 bytes are inlined in their parent.
+
+use sp_core::{H256, hashing::keccak_256};
+use std::collections::BTreeMap;
+
+/// RLP encoding of the empty string, which stands for an empty node.
+const EMPTY_NODE: u8 = 0x80;
+
+/// Returns the root of the trie mapping the RLP encoded index of each value to the value, as
+/// used for the transactions and receipts of Ethereum blocks.
+pub fn ordered_root(input: Vec<Vec<u8>>) -> H256 {
+	root(input.into_iter().enumerate().map(|(index, value)| {
+		(rlp_bytes(&trimmed_be_bytes(index as u64)), value)
+	}))
+}
+
+/// Returns the root of the trie holding the given key-value pairs.
+///
+/// When a key appears more than once, the last value wins.
+pub fn root(input: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> H256 {
+	let input = input.into_iter()
+		.map(|(key, value)| (key.iter().flat_map(|b| vec![b >> 4, b & 0x0f]).collect(), value))
+		.collect::<BTreeMap<Vec<u8>, Vec<u8>>>()
+		.into_iter()
+		.collect::<Vec<_>>();
+	keccak_256(&encode_node(&input, 0)).into()
+}
+
+/// RLP encodes the node holding `input`, whose keys are nibbles sharing their first
+/// `prefix_len` nibbles.
+fn encode_node(input: &[(Vec<u8>, Vec<u8>)], prefix_len: usize) -> Vec<u8> {
+	let (first, last) = match (input.first(), input.last()) {
+		(Some(first), Some(last)) => (&first.0, &last.0),
+		_ => return vec![EMPTY_NODE],
+	};
+
+	if input.len() == 1 {
+		return rlp_list(&[
+			rlp_bytes(&hex_prefix(&first[prefix_len..], true)),
+			rlp_bytes(&input[0].1),
+		])
+	}
+
+	// The keys are sorted, so the prefix shared by the first and last ones is shared by all.
+	let shared = first[prefix_len..].iter()
+		.zip(&last[prefix_len..])
+		.take_while(|(a, b)| a == b)
+		.count();
+	if shared > 0 {
+		return rlp_list(&[
+			rlp_bytes(&hex_prefix(&first[prefix_len..prefix_len + shared], false)),
+			encode_child(input, prefix_len + shared),
+		])
+	}
+
+	let (value, mut rest) = if first.len() == prefix_len {
+		(&input[0].1[..], &input[1..])
+	} else {
+		(&[][..], input)
+	};
+	let mut items = Vec::with_capacity(17);
+	for nibble in 0..16 {
+		let len = rest.iter().take_while(|(key, _)| key[prefix_len] == nibble).count();
+		let (children, tail) = rest.split_at(len);
+		items.push(if children.is_empty() {
+			vec![EMPTY_NODE]
+		} else {
+			encode_child(children, prefix_len + 1)
+		});
+		rest = tail;
+	}
+	items.push(rlp_bytes(value));
+	rlp_list(&items)
+}
+
+/// Encodes a node as referenced by its parent: inline if short enough, by hash otherwise.
+fn encode_child(input: &[(Vec<u8>, Vec<u8>)], prefix_len: usize) -> Vec<u8> {
+	let node = encode_node(input, prefix_len);
+	if node.len() < 32 {
+		node
+	} else {
+		rlp_bytes(&keccak_256(&node))
+	}
+}
+
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+	let flag = if is_leaf { 2 } else { 0 };
+	let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+	let rest = if nibbles.len() % 2 == 1 {
+		out.push(((flag + 1) << 4) | nibbles[0]);
+		&nibbles[1..]
+	} else {
+		out.push(flag << 4);
+		nibbles
+	};
+	out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+	out
+}
+
+fn rlp_bytes(data: &[u8]) -> Vec<u8> {
+	if data.len() == 1 && data[0] < 0x80 {
+		return data.to_vec()
+	}
+	let mut out = rlp_length_prefix(data.len(), 0x80);
+	out.extend_from_slice(data);
+	out
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+	let payload = items.concat();
+	let mut out = rlp_length_prefix(payload.len(), 0xc0);
+	out.extend(payload);
+	out
+}
+
+fn rlp_length_prefix(len: usize, offset: u8) -> Vec<u8> {
+	if len <= 55 {
+		return vec![offset + len as u8]
+	}
+	let len = trimmed_be_bytes(len as u64);
+	let mut out = vec![offset + 55 + len.len() as u8];
+	out.extend(len);
+	out
+}
+
+fn trimmed_be_bytes(value: u64) -> Vec<u8> {
+	let bytes = value.to_be_bytes();
+	let zeros = bytes.iter().take_while(|b| **b == 0).count();
+	bytes[zeros..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	#[test]
+	fn empty_root() {
+		assert_eq!(
+			ordered_root(Vec::new()),
+			H256::from(hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")),
+		);
+	}
+
+	#[test]
+	fn root_matches_ethereum() {
+		let input = vec![
+			(b"doe".to_vec(), b"reindeer".to_vec()),
+			(b"dog".to_vec(), b"puppy".to_vec()),
+			(b"dogglesworth".to_vec(), b"cat".to_vec()),
+		];
+		assert_eq!(
+			root(input),
+			H256::from(hex!("8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")),
+		);
+	}
+
+	#[test]
+	fn ordered_root_works() {
+		assert_eq!(
+			ordered_root(vec![b"cat".to_vec(), b"dog".to_vec(), b"horse".to_vec()]),
+			H256::from(hex!("ad9a2b4e8ba9f5b8ae2759839fcbb030dd2b821726037d1c106ec679d04346f4")),
+		);
+		assert_eq!(
+			ordered_root((0..200).map(|i| vec![i as u8; 40]).collect()),
+			H256::from(hex!("8807025e51e6711456504fe17c63b47ccd2ae57da3b0cc45aaaef1bf63b703ad")),
+		);
+	}
+}
@@ -38,18 +38,24 @@ use tracing;
 #[cfg(feature = "std")]
 use sp_core::{
 	crypto::Pair,
-	traits::{CallInWasmExt, TaskExecutorExt, RuntimeSpawnExt},
-	offchain::{OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+	traits::{
+		CallInWasmExt, TaskExecutorExt, VerificationExecutorExt, RuntimeSpawnExt,
+		ChainPropertiesExt,
+	},
+	offchain::{OffchainDbExt, OffchainWorkerExt, TransactionPoolExt, TransactionPoolReaderExt},
 	hexdisplay::HexDisplay,
 	storage::ChildInfo,
 };
 #[cfg(feature = "std")]
 use sp_keystore::{KeystoreExt, SyncCryptoStore};
+#[cfg(feature = "std")]
+use sp_inherents::InherentDataExt;
 
 use sp_core::{
-	OpaquePeerId, crypto::KeyTypeId, ed25519, sr25519, ecdsa, H256, LogLevel,
+	OpaquePeerId, crypto::KeyTypeId, ed25519, sr25519, ecdsa, H256, LogLevel, LogLevelFilter,
 	offchain::{
 		Timestamp, HttpRequestId, HttpRequestStatus, HttpError, StorageKind, OpaqueNetworkState,
+		TcpSocketId, TcpError,
 	},
 };
 
@@ -57,6 +63,7 @@ use sp_core::{
 use sp_trie::{TrieConfiguration, trie_types::Layout};
 
 use sp_runtime_interface::{runtime_interface, Pointer};
+use sp_wasm_interface::AllocationStats;
 use sp_runtime_interface::pass_by::{PassBy, PassByCodec};
 
 use codec::{Encode, Decode};
@@ -70,6 +77,12 @@ mod batch_verifier;
 #[cfg(feature = "std")]
 use batch_verifier::BatchVerifier;
 
+#[cfg(feature = "std")]
+mod bls_verifier;
+
+#[cfg(feature = "std")]
+mod eth_trie;
+
 /// Error verifying ECDSA signature
 #[derive(Encode, Decode)]
 pub enum EcdsaVerifyError {
@@ -99,6 +112,14 @@ pub trait Storage {
 		self.storage(key).map(|s| s.to_vec())
 	}
 
+	/// Returns the data for each of the given `keys`, in the same order.
+	///
+	/// Equivalent to calling `get` for each key, but with a single call into the
+	/// host.
+	fn get_many(&self, keys: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>> {
+		keys.iter().map(|key| self.storage(key)).collect()
+	}
+
 	/// Get `key` from storage, placing the value into `value_out` and return the number of
 	/// bytes that the entry in storage has beyond the offset or `None` if the storage entry
 	/// doesn't exist at all.
@@ -211,6 +232,13 @@ pub trait Storage {
 		self.storage_commit_transaction()
 			.expect("No open transaction that can be committed.");
 	}
+
+	/// Returns the encoded size of the storage proof recorded so far.
+	///
+	/// Returns `None` when no proof is being recorded, for example during block import.
+	fn proof_size(&self) -> Option<u32> {
+		Externalities::proof_size(&**self)
+	}
 }
 
 /// Interface for accessing the child storage for default child trie,
@@ -427,6 +455,15 @@ pub trait Trie {
 	fn keccak_256_ordered_root(input: Vec<Vec<u8>>) -> H256 {
 		Layout::<sp_core::KeccakHasher>::ordered_trie_root(input)
 	}
+
+	/// An Ethereum trie root formed from the enumerated items.
+	///
+	/// Unlike `keccak_256_ordered_root`, this uses the layout of Ethereum (RLP encoded nodes,
+	/// keys being the RLP encoded indices), producing the transactions and receipts roots of
+	/// Ethereum blocks.
+	fn keccak_256_ordered_eth_root(input: Vec<Vec<u8>>) -> H256 {
+		eth_trie::ordered_root(input)
+	}
 }
 
 /// Interface that provides miscellaneous functions for communicating between the runtime and the node.
@@ -479,6 +516,21 @@ pub trait Misc {
 			)
 			.ok()
 	}
+
+	/// Returns the properties of the chain, such as its token symbol and decimals.
+	///
+	/// The returned `Vec<u8>` holds a SCALE-encoded `Vec<(String, String)>` mapping the name of
+	/// each property to its value serialized as JSON. The list is empty if the node provides
+	/// no properties.
+	///
+	/// The properties are node configuration rather than chain state, so nodes only provide
+	/// them to offchain workers, RPC and other calls made outside of block import and
+	/// construction. Consensus code always gets an empty list.
+	fn chain_properties(&mut self) -> Vec<u8> {
+		self.extension::<ChainPropertiesExt>()
+			.map(|ext| ext.0.clone())
+			.unwrap_or_else(|| Vec::<(String, String)>::new().encode())
+	}
 }
 
 /// Interfaces for working with crypto related types from within the runtime.
@@ -553,6 +605,22 @@ pub trait Crypto {
 		).unwrap_or_else(|| ed25519_verify(sig, msg, pub_key))
 	}
 
+	/// Verify a batch of `ed25519` signatures in one call, `sigs[i]` being the signature of
+	/// `msgs[i]` by `pub_keys[i]`.
+	///
+	/// Returns `true` only if all the signatures are valid, and `false` if the lengths differ.
+	/// Unlike [`ed25519_batch_verify`], this doesn't depend on [`start_batch_verify`]: the
+	/// signatures are checked at once with a batch equation. This equation is cofactored, so it
+	/// may accept signatures with a small order component that [`ed25519_verify`] rejects.
+	fn ed25519_verify_batch(
+		sigs: Vec<ed25519::Signature>,
+		msgs: Vec<Vec<u8>>,
+		pub_keys: Vec<ed25519::Public>,
+	) -> bool {
+		let msgs = msgs.iter().map(|msg| &msg[..]).collect::<Vec<_>>();
+		ed25519::verify_batch(&msgs, &sigs, &pub_keys)
+	}
+
 	/// Verify `sr25519` signature.
 	///
 	/// Returns `true` when the verification was successful.
@@ -586,9 +654,14 @@ pub trait Crypto {
 
 	/// Start verification extension.
 	fn start_batch_verify(&mut self) {
-		let scheduler = self.extension::<TaskExecutorExt>()
-			.expect("No task executor associated with the current context!")
-			.clone();
+		let verification_scheduler = self.extension::<VerificationExecutorExt>()
+			.map(|scheduler| scheduler.clone());
+		let scheduler = match verification_scheduler {
+			Some(scheduler) => scheduler,
+			None => self.extension::<TaskExecutorExt>()
+				.expect("No task executor associated with the current context!")
+				.clone(),
+		};
 
 		self.register_extension(VerificationExt(BatchVerifier::new(scheduler)))
 			.expect("Failed to register required extension: `VerificationExt`");
@@ -767,6 +840,14 @@ pub trait Crypto {
 			.map_err(|_| EcdsaVerifyError::BadSignature)?;
 		Ok(pubkey.serialize_compressed())
 	}
+
+	/// Returns `true` if the keystore holds the private key of `public_key` for the given key
+	/// type, whatever its crypto.
+	fn has_key(&mut self, public_key: &[u8], id: KeyTypeId) -> bool {
+		let keystore = &***self.extension::<KeystoreExt>()
+			.expect("No `keystore` associated for the current context!");
+		SyncCryptoStore::has_keys(keystore, &[(public_key.to_vec(), id)])
+	}
 }
 
 /// Interface that provides functions for hashing with different algorithms.
@@ -787,6 +868,23 @@ pub trait Hashing {
 		sp_core::hashing::sha2_256(data)
 	}
 
+	/// Conduct a 160-bit Ripemd hash.
+	fn ripemd_160(data: &[u8]) -> [u8; 20] {
+		sp_core::hashing::ripemd_160(data)
+	}
+
+	/// Conduct a 512-bit Blake2 hash.
+	fn blake2_512(data: &[u8]) -> [u8; 64] {
+		sp_core::hashing::blake2_512(data)
+	}
+
+	/// Conduct a keyed Blake2 hash with an output of `out_len` bytes.
+	///
+	/// Returns `None` if `out_len` is zero, or if `out_len` or the length of `key` are above 64.
+	fn blake2b(data: &[u8], key: &[u8], out_len: u32) -> Option<Vec<u8>> {
+		sp_core::hashing::blake2b(data, key, out_len as usize)
+	}
+
 	/// Conduct a 128-bit Blake2 hash.
 	fn blake2_128(data: &[u8]) -> [u8; 16] {
 		sp_core::hashing::blake2_128(data)
@@ -813,6 +911,80 @@ pub trait Hashing {
 	}
 }
 
+/// Interface that provides functions for BLS12-381 signatures.
+///
+/// Signatures follow the `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` ciphersuite used by Eth2:
+/// public keys are compressed G1 points and signatures are compressed G2 points. Rogue key
+/// attacks on aggregated signatures are expected to be prevented by proofs of possession of the
+/// keys.
+#[runtime_interface]
+pub trait Bls {
+	/// Verify a BLS12-381 signature of `msg` by `public`.
+	fn bls12_381_verify(sig: &[u8; 96], msg: &[u8], public: &[u8; 48]) -> bool {
+		bls_verifier::verify(sig, msg, public)
+	}
+
+	/// Verify an aggregated BLS12-381 signature of each of the `msgs` by the key at the same
+	/// index in `publics`.
+	///
+	/// Returns `false` if there is no message, or if there are not as many messages as keys.
+	fn bls12_381_aggregate_verify(
+		sig: &[u8; 96],
+		msgs: Vec<Vec<u8>>,
+		publics: Vec<[u8; 48]>,
+	) -> bool {
+		let msgs = msgs.iter().map(|msg| &msg[..]).collect::<Vec<_>>();
+		bls_verifier::aggregate_verify(sig, &msgs, &publics)
+	}
+
+	/// Verify an aggregated BLS12-381 signature of the same `msg` by all the `publics`.
+	///
+	/// Returns `false` if there is no key.
+	fn bls12_381_fast_aggregate_verify(
+		sig: &[u8; 96],
+		msg: &[u8],
+		publics: Vec<[u8; 48]>,
+	) -> bool {
+		bls_verifier::fast_aggregate_verify(sig, msg, &publics)
+	}
+
+	/// Hash `msg` to a point of G2 with the domain separation tag `dst`, returning the point
+	/// compressed.
+	fn bls12_381_hash_to_g2(msg: &[u8], dst: &[u8]) -> [u8; 96] {
+		bls_verifier::hash_to_g2(msg, dst).to_compressed()
+	}
+}
+
+/// Interface that provides functions for encoding and decoding SS58 addresses.
+#[runtime_interface]
+pub trait Address {
+	/// Encode the 32 byte `public` key as an SS58 address with the given `prefix`.
+	///
+	/// Returns `None` if the prefix is reserved or above `16_383`, the largest one SS58 can
+	/// encode.
+	fn ss58_encode(public: [u8; 32], prefix: u16) -> Option<Vec<u8>> {
+		use sp_core::crypto::{AccountId32, Ss58AddressFormat, Ss58Codec};
+		use sp_std::convert::TryFrom;
+
+		let format = Ss58AddressFormat::try_from(prefix).ok()?;
+		if prefix > 16_383 || !AccountId32::format_is_allowed(format) {
+			return None
+		}
+		Some(AccountId32::from(public).to_ss58check_with_version(format).into_bytes())
+	}
+
+	/// Decode an SS58 address of a 32 byte public key, returning the key and the prefix.
+	///
+	/// Returns `None` if the address is invalid, including if it has a reserved prefix.
+	fn ss58_decode(address: &str) -> Option<([u8; 32], u16)> {
+		use sp_core::crypto::{AccountId32, Ss58Codec};
+
+		AccountId32::from_ss58check_with_version(address)
+			.ok()
+			.map(|(public, format)| (public.into(), format.into()))
+	}
+}
+
 /// Interface that provides functions to access the Offchain DB.
 #[runtime_interface]
 pub trait OffchainIndex {
@@ -827,6 +999,23 @@ pub trait OffchainIndex {
 	}
 }
 
+/// Interface that provides access to the inherent data of the block being built.
+///
+/// The inherent data is only provided to the runtime while the node builds a block, from the
+/// computation of its inherent extrinsics to the finalization of the block. The functions return
+/// `None` in any other call, which includes all the calls made during block import, so the data
+/// must not change the outcome of the calls which are replayed on import.
+#[runtime_interface]
+pub trait InherentData {
+	/// Returns the SCALE-encoded data of the inherent with the given 8-byte `identifier`.
+	fn get(&mut self, identifier: &[u8]) -> Option<Vec<u8>> {
+		let identifier = std::convert::TryFrom::try_from(identifier).ok()?;
+		self.extension::<InherentDataExt>()
+			.and_then(|ext| ext.get_data_encoded(&identifier))
+			.map(|data| data.to_vec())
+	}
+}
+
 #[cfg(feature = "std")]
 sp_externalities::decl_extension! {
 	/// Batch verification extension to register/retrieve from the externalities.
@@ -858,6 +1047,18 @@ pub trait Offchain {
 			.submit_transaction(data)
 	}
 
+	/// Returns the hash and the encoding of the transactions ready to be included in a block.
+	///
+	/// The transactions are ordered as they would be included in the next block. At most
+	/// `max_count` transactions are returned, and only as many as fit in `max_bytes` bytes of
+	/// encoded transactions, so that a large pool isn't copied into the runtime.
+	fn ready_transactions(&mut self, max_count: u32, max_bytes: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.extension::<TransactionPoolReaderExt>()
+			.expect("ready_transactions can be called only in the offchain call context with
+				TransactionPool capabilities enabled")
+			.ready_transactions(max_count, max_bytes)
+	}
+
 	/// Returns information about the local node's network state.
 	fn network_state(&mut self) -> Result<OpaqueNetworkState, ()> {
 		self.extension::<OffchainWorkerExt>()
@@ -1041,6 +1242,57 @@ pub trait Offchain {
 			.map(|r| r as u32)
 	}
 
+	/// Open a TCP connection to `address`, given as `host:port`.
+	///
+	/// Passing `None` as a deadline blocks forever.
+	fn tcp_connect(
+		&mut self,
+		address: &str,
+		deadline: Option<Timestamp>,
+	) -> Result<TcpSocketId, TcpError> {
+		self.extension::<OffchainWorkerExt>()
+			.expect("tcp_connect can be called only in the offchain worker context")
+			.tcp_connect(address, deadline)
+	}
+
+	/// Write all of `data` to an open TCP socket.
+	///
+	/// Passing `None` as a deadline blocks forever.
+	fn tcp_write(
+		&mut self,
+		socket_id: TcpSocketId,
+		data: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), TcpError> {
+		self.extension::<OffchainWorkerExt>()
+			.expect("tcp_write can be called only in the offchain worker context")
+			.tcp_write(socket_id, data, deadline)
+	}
+
+	/// Read a chunk of data from an open TCP socket into the given buffer.
+	///
+	/// Returns the number of bytes written to the buffer. If `0` is returned the remote
+	/// has closed the connection and the `socket_id` is now invalid.
+	/// Passing `None` as a deadline blocks forever.
+	fn tcp_read(
+		&mut self,
+		socket_id: TcpSocketId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<u32, TcpError> {
+		self.extension::<OffchainWorkerExt>()
+			.expect("tcp_read can be called only in the offchain worker context")
+			.tcp_read(socket_id, buffer, deadline)
+			.map(|r| r as u32)
+	}
+
+	/// Close a TCP socket.
+	fn tcp_close(&mut self, socket_id: TcpSocketId) {
+		self.extension::<OffchainWorkerExt>()
+			.expect("tcp_close can be called only in the offchain worker context")
+			.tcp_close(socket_id)
+	}
+
 	/// Set the authorized nodes and authorized_only flag.
 	fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
 		self.extension::<OffchainWorkerExt>()
@@ -1063,6 +1315,17 @@ trait Allocator {
 	}
 }
 
+/// Wasm-only interface that provides statistics about the heap of the runtime.
+///
+/// Only available to runtimes executed as wasm, as native runtimes use the allocator of the node.
+#[runtime_interface(wasm_only)]
+pub trait Heap {
+	/// Returns the statistics of the allocator of the wasm instance.
+	fn stats(&mut self) -> AllocationStats {
+		self.allocation_stats()
+	}
+}
+
 /// Interface that provides functions for logging from within the runtime.
 #[runtime_interface]
 pub trait Logging {
@@ -1082,6 +1345,14 @@ pub trait Logging {
 			)
 		}
 	}
+
+	/// Returns the max log level used by the host.
+	///
+	/// Messages above this level are discarded by the host, so the runtime can skip formatting
+	/// them. The level follows the log filters of the node, e.g. its `--log` option.
+	fn max_level() -> LogLevelFilter {
+		log::max_level().into()
+	}
 }
 
 #[derive(Encode, Decode)]
@@ -1412,12 +1683,16 @@ pub type SubstrateHostFunctions = (
 	offchain::HostFunctions,
 	crypto::HostFunctions,
 	hashing::HostFunctions,
+	bls::HostFunctions,
 	allocator::HostFunctions,
+	heap::HostFunctions,
+	address::HostFunctions,
 	logging::HostFunctions,
 	sandbox::HostFunctions,
 	crate::trie::HostFunctions,
 	offchain_index::HostFunctions,
 	runtime_tasks::HostFunctions,
+	inherent_data::HostFunctions,
 );
 
 #[cfg(test)]
@@ -1425,10 +1700,31 @@ mod tests {
 	use super::*;
 	use sp_state_machine::BasicExternalities;
 	use sp_core::{
-		storage::Storage, map, traits::TaskExecutorExt, testing::TaskExecutor,
+		storage::Storage, map, traits::{TaskExecutorExt, VerificationExecutorExt},
+		testing::TaskExecutor,
 	};
 	use std::any::TypeId;
 
+	#[test]
+	fn blake2b_supports_keys_and_output_lengths() {
+		BasicExternalities::default().execute_with(|| {
+			assert_eq!(
+				hashing::blake2b(b"data", &[], 32),
+				Some(hashing::blake2_256(b"data").to_vec()),
+			);
+			assert_eq!(
+				hashing::blake2b(b"data", &[], 64),
+				Some(hashing::blake2_512(b"data").to_vec()),
+			);
+			assert_eq!(hashing::blake2b(b"data", b"key", 20).map(|h| h.len()), Some(20));
+			assert_ne!(hashing::blake2b(b"data", b"key", 32), hashing::blake2b(b"data", &[], 32));
+
+			assert_eq!(hashing::blake2b(b"data", &[], 0), None);
+			assert_eq!(hashing::blake2b(b"data", &[], 65), None);
+			assert_eq!(hashing::blake2b(b"data", &[0; 65], 32), None);
+		});
+	}
+
 	#[test]
 	fn storage_works() {
 		let mut t = BasicExternalities::default();
@@ -1451,6 +1747,25 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn get_many_works() {
+		let mut t = BasicExternalities::new(Storage {
+			top: map![
+				b"foo".to_vec() => b"bar".to_vec(),
+				b"hello".to_vec() => b"world".to_vec()
+			],
+			children_default: map![],
+		});
+
+		t.execute_with(|| {
+			assert_eq!(
+				storage::get_many(vec![b"hello".to_vec(), b"baz".to_vec(), b"foo".to_vec()]),
+				vec![Some(b"world".to_vec()), None, Some(b"bar".to_vec())],
+			);
+			assert!(storage::get_many(Vec::new()).is_empty());
+		});
+	}
+
 	#[test]
 	fn read_storage_works() {
 		let value = b"\x0b\0\0\0Hello world".to_vec();
@@ -1509,6 +1824,20 @@ mod tests {
 		assert!(ext.extensions().get_mut(TypeId::of::<VerificationExt>()).is_none());
 	}
 
+	#[test]
+	fn batch_verify_works_with_verification_executor() {
+		let mut ext = BasicExternalities::default();
+		ext.register_extension(VerificationExecutorExt::new(TaskExecutor::new()));
+
+		ext.execute_with(|| {
+			let pair = sr25519::Pair::generate_with_phrase(None).0;
+			crypto::start_batch_verify();
+			let signature = pair.sign(b"Schnorrkel!");
+			crypto::sr25519_batch_verify(&signature, b"Schnorrkel!", &pair.public());
+			assert!(crypto::finish_batch_verify());
+		});
+	}
+
 	#[test]
 	fn long_sr25519_batching() {
 		let mut ext = BasicExternalities::default();
@@ -1622,4 +1951,83 @@ mod tests {
 			assert!(!crypto::finish_batch_verify());
 		});
 	}
+
+	#[test]
+	fn inherent_data_is_only_available_when_provided() {
+		let mut ext = BasicExternalities::default();
+		ext.execute_with(|| assert_eq!(inherent_data::get(b"timstap0"), None));
+
+		let mut data = sp_inherents::InherentData::new();
+		data.put_data(*b"timstap0", &42u64).unwrap();
+		ext.register_extension(InherentDataExt(data));
+		ext.execute_with(|| {
+			assert_eq!(inherent_data::get(b"timstap0"), Some(42u64.encode()));
+			assert_eq!(inherent_data::get(b"babeslot"), None);
+			assert_eq!(inherent_data::get(b"short"), None);
+		});
+	}
+
+	#[test]
+	fn chain_properties_works() {
+		let mut ext = BasicExternalities::default();
+		ext.execute_with(|| {
+			assert_eq!(misc::chain_properties(), Vec::<(String, String)>::new().encode());
+		});
+
+		let properties = vec![("tokenDecimals".to_string(), "12".to_string())].encode();
+		ext.register_extension(ChainPropertiesExt(properties.clone()));
+		ext.execute_with(|| assert_eq!(misc::chain_properties(), properties));
+	}
+
+	#[test]
+	fn ready_transactions_works() {
+		let (pool, state) = sp_core::offchain::testing::TestTransactionPoolExt::new();
+		state.write().transactions.push(vec![1, 2, 3]);
+		state.write().transactions.push(vec![4, 5, 6]);
+
+		let mut ext = BasicExternalities::default();
+		ext.register_extension(TransactionPoolReaderExt::new(pool));
+		ext.execute_with(|| {
+			let first = (hashing::blake2_256(&[1, 2, 3]).to_vec(), vec![1, 2, 3]);
+			let second = (hashing::blake2_256(&[4, 5, 6]).to_vec(), vec![4, 5, 6]);
+
+			assert_eq!(offchain::ready_transactions(10, 1024), vec![first.clone(), second]);
+			assert_eq!(offchain::ready_transactions(1, 1024), vec![first.clone()]);
+			assert_eq!(offchain::ready_transactions(10, 5), vec![first]);
+		});
+	}
+
+	#[test]
+	fn has_key_works() {
+		let key_type = KeyTypeId(*b"test");
+		let keystore = sp_keystore::testing::KeyStore::new();
+		let public = SyncCryptoStore::sr25519_generate_new(&keystore, key_type, None).unwrap();
+
+		let mut ext = BasicExternalities::default();
+		ext.register_extension(KeystoreExt(std::sync::Arc::new(keystore)));
+		ext.execute_with(|| {
+			assert!(crypto::has_key(public.as_ref(), key_type));
+			assert!(!crypto::has_key(public.as_ref(), KeyTypeId(*b"othr")));
+			assert!(!crypto::has_key(&[0; 32], key_type));
+		});
+	}
+
+	#[test]
+	fn ss58_encode_and_decode_work() {
+		use hex_literal::hex;
+
+		let alice = hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+		let encoded = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+		assert_eq!(address::ss58_encode(alice, 42), Some(encoded.as_bytes().to_vec()));
+		assert_eq!(address::ss58_decode(encoded), Some((alice, 42)));
+
+		let encoded = address::ss58_encode(alice, 1000).unwrap();
+		let encoded = std::str::from_utf8(&encoded).unwrap();
+		assert_eq!(address::ss58_decode(encoded), Some((alice, 1000)));
+
+		assert_eq!(address::ss58_encode(alice, 46), None);
+		assert_eq!(address::ss58_encode(alice, 16_384), None);
+		assert_eq!(address::ss58_decode("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"), None);
+	}
 }
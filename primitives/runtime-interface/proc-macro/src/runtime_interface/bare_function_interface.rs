@@ -166,7 +166,9 @@ fn function_std_impl(
 		).take(1),
 	);
 	let return_value = &method.sig.output;
-	let attrs = method.attrs.iter().filter(|a| !a.path.is_ident("version"));
+	// Only the public functions warn about deprecation, not the internal versioned ones.
+	let attrs = method.attrs.iter()
+		.filter(|a| !a.path.is_ident("version") && !a.path.is_ident("deprecated"));
 	// Don't make the function public accessible when this is a wasm only interface.
 	let call_to_trait = generate_call_to_trait(trait_name, method, version, is_wasm_only);
 	let call_to_trait = if !tracing {
@@ -184,6 +186,9 @@ fn function_std_impl(
 			#[cfg(feature = "std")]
 			#( #attrs )*
 			fn #function_name( #( #args, )* ) #return_value {
+				let _host_call = #crate_::host_calls::record(
+					concat!(module_path!(), "::", #function_name_str),
+				);
 				#call_to_trait
 			}
 		}
@@ -26,7 +26,7 @@ use crate::utils::{
 	get_function_argument_types_without_ref, get_function_argument_types_ref_and_mut,
 	get_function_argument_names_and_types_without_ref, get_function_arguments,
 	get_function_argument_types, create_exchangeable_host_function_ident, get_runtime_interface,
-	create_function_ident_with_version,
+	create_function_ident_with_version, get_cfg_attributes, is_deprecated,
 };
 
 use syn::{
@@ -94,6 +94,7 @@ fn generate_extern_host_function(method: &TraitItemMethod, version: u32, trait_n
 		method.sig.ident,
 	);
 	let return_value = &method.sig.output;
+	let cfg_attrs = get_cfg_attributes(method);
 
 	let ffi_return_value = match method.sig.output {
 		ReturnType::Default => quote!(),
@@ -111,6 +112,7 @@ fn generate_extern_host_function(method: &TraitItemMethod, version: u32, trait_n
 
 	Ok(
 		quote! {
+			#( #cfg_attrs )*
 			#[doc = #doc_string]
 			pub fn #function ( #( #args ),* ) #return_value {
 				extern "C" {
@@ -143,10 +145,12 @@ fn generate_exchangeable_host_function(method: &TraitItemMethod) -> Result<Token
 	let exchangeable_function = create_exchangeable_host_function_ident(&method.sig.ident);
 	let doc_string = format!(" Exchangeable host function used by [`{}`].", method.sig.ident);
 	let output = &method.sig.output;
+	let cfg_attrs = get_cfg_attributes(method);
 
 	Ok(
 		quote! {
 			#[cfg(not(feature = "std"))]
+			#( #cfg_attrs )*
 			#[allow(non_upper_case_globals)]
 			#[doc = #doc_string]
 			pub static #exchangeable_function : #crate_::wasm::ExchangeableFunction<
@@ -163,9 +167,21 @@ fn generate_host_functions_struct(trait_def: &ItemTrait, is_wasm_only: bool) ->
 
 	let host_functions = get_runtime_interface(trait_def)?
 		.all_versions()
-		.map(|(version, method)|
-			generate_host_function_implementation(&trait_def.ident, method, version, is_wasm_only)
-		)
+		.map(|(version, method)| {
+			let cfg_attrs = get_cfg_attributes(method);
+			let host_function = generate_host_function_implementation(
+				&trait_def.ident,
+				method,
+				version,
+				is_wasm_only,
+			)?;
+
+			// Push the host functions one by one, so that a configured out method is skipped.
+			Ok(quote! {
+				#( #cfg_attrs )*
+				host_functions.push(#host_function);
+			})
+		})
 		.collect::<Result<Vec<_>>>()?;
 
 	Ok(
@@ -177,7 +193,9 @@ fn generate_host_functions_struct(trait_def: &ItemTrait, is_wasm_only: bool) ->
 			#[cfg(feature = "std")]
 			impl #crate_::sp_wasm_interface::HostFunctions for HostFunctions {
 				fn host_functions() -> Vec<&'static dyn #crate_::sp_wasm_interface::Function> {
-					vec![ #( #host_functions ),* ]
+					let mut host_functions = Vec::new();
+					#( #host_functions )*
+					host_functions
 				}
 			}
 		}
@@ -207,6 +225,7 @@ fn generate_host_function_implementation(
 	let host_function_call = generate_host_function_call(&method.sig, version, is_wasm_only);
 	let into_preallocated_ffi_value = generate_into_preallocated_ffi_value(&method.sig)?;
 	let convert_return_value = generate_return_value_into_wasm_value(&method.sig);
+	let warn_deprecated = generate_deprecation_warning(method, &name);
 
 	Ok(
 		quote! {
@@ -227,6 +246,7 @@ fn generate_host_function_implementation(
 						__function_context__: &mut dyn #crate_::sp_wasm_interface::FunctionContext,
 						args: &mut dyn Iterator<Item = #crate_::sp_wasm_interface::Value>,
 					) -> std::result::Result<Option<#crate_::sp_wasm_interface::Value>, String> {
+						#warn_deprecated
 						#( #wasm_to_ffi_values )*
 						#( #ffi_to_host_values )*
 						#host_function_call
@@ -241,6 +261,24 @@ fn generate_host_function_implementation(
 	)
 }
 
+/// Generate the warning logged the first time the runtime calls a deprecated host function.
+fn generate_deprecation_warning(method: &TraitItemMethod, name: &str) -> TokenStream {
+	if !is_deprecated(method) {
+		return quote!()
+	}
+
+	let crate_ = generate_crate_access();
+	quote! {
+		static WARN_ONCE: std::sync::Once = std::sync::Once::new();
+		WARN_ONCE.call_once(|| #crate_::sp_tracing::warn!(
+			target: "runtime_interface",
+			"The runtime called the deprecated host function `{}`, which may be removed in the \
+			future",
+			#name,
+		));
+	}
+}
+
 /// Generate the `wasm_interface::Signature` for the given host function `sig`.
 fn generate_wasm_interface_signature_for_host_function(sig: &Signature) -> Result<TokenStream> {
 	let crate_ = generate_crate_access();
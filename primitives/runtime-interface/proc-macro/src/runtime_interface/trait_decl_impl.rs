@@ -110,7 +110,7 @@ impl Fold for ToEssentialTraitDef {
 
 		self.error_on_generic_parameters(&method.sig.generics);
 
-		method.attrs.retain(|a| !a.path.is_ident("version"));
+		method.attrs.retain(|a| !a.path.is_ident("version") && !a.path.is_ident("deprecated"));
 
 		fold::fold_trait_item_method(self, method)
 	}
@@ -161,7 +161,7 @@ fn impl_trait_for_externalities(trait_def: &ItemTrait, is_wasm_only: bool) -> Re
 	let interface = get_runtime_interface(trait_def)?;
 	let methods = interface.all_versions().map(|(version, method)| {
 		let mut cloned = method.clone();
-		cloned.attrs.retain(|a| !a.path.is_ident("version"));
+		cloned.attrs.retain(|a| !a.path.is_ident("version") && !a.path.is_ident("deprecated"));
 		cloned.sig.ident = create_function_ident_with_version(&cloned.sig.ident, version);
 		cloned
 	});
@@ -203,6 +203,19 @@ pub fn get_function_argument_types_ref_and_mut<'a>(
 		})
 }
 
+/// Returns the `#[cfg(...)]` attributes of the given method.
+///
+/// These attributes need to be put on every item generated for the method, to keep the generated
+/// items consistent when the method is configured out.
+pub fn get_cfg_attributes(method: &TraitItemMethod) -> Vec<&Attribute> {
+	method.attrs.iter().filter(|a| a.path.is_ident("cfg")).collect()
+}
+
+/// Returns whether the given method is marked `#[deprecated]`.
+pub fn is_deprecated(method: &TraitItemMethod) -> bool {
+	method.attrs.iter().any(|a| a.path.is_ident("deprecated"))
+}
+
 /// Returns an iterator over all trait methods for the given trait definition.
 fn get_trait_methods<'a>(trait_def: &'a ItemTrait) -> impl Iterator<Item = &'a TraitItemMethod> {
 	trait_def
@@ -0,0 +1,165 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Counting of the calls made to host functions.
+//!
+//! Counting is opt-in and done per thread: once started with [`start_counting`], every host
+//! function called on the current thread, natively or from wasm, is recorded until
+//! [`stop_counting`] is called. Used to calibrate weights against the host calls the runtime
+//! actually makes.
+//!
+//! Only the outermost host call is counted, host functions calling other host functions are
+//! counted once. A host call is only counted when it returns and if counting was already
+//! started when it was made, so the calls starting and stopping the counting are never
+//! counted themselves. While no thread is counting, recording a call is a single atomic load.
+
+use std::{
+	cell::RefCell, collections::BTreeMap, sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The number of threads currently counting.
+static COUNTING_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// The counting state of a thread.
+#[derive(Default)]
+struct State {
+	/// The number of calls of each host function, `None` when not counting.
+	counts: Option<BTreeMap<&'static str, u32>>,
+	/// Incremented every time counting is started, to skip the calls made before.
+	generation: u64,
+	/// The number of host calls being recorded on the thread.
+	depth: u32,
+}
+
+thread_local! {
+	static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Starts counting the host function calls made on the current thread.
+///
+/// Resets the counts if counting was already started.
+pub fn start_counting() {
+	STATE.with(|state| {
+		let mut state = state.borrow_mut();
+		if state.counts.is_none() {
+			COUNTING_THREADS.fetch_add(1, Ordering::Relaxed);
+		}
+		state.counts = Some(BTreeMap::new());
+		state.generation += 1;
+	});
+}
+
+/// Stops counting the host function calls made on the current thread.
+///
+/// Returns the number of calls of each host function since counting was started, sorted by the
+/// path of the function. Returns an empty list if counting wasn't started.
+pub fn stop_counting() -> Vec<(&'static str, u32)> {
+	let counts = STATE.with(|state| state.borrow_mut().counts.take());
+	if counts.is_some() {
+		COUNTING_THREADS.fetch_sub(1, Ordering::Relaxed);
+	}
+	counts.map(|counts| counts.into_iter().collect()).unwrap_or_default()
+}
+
+/// A host call being recorded, counted when dropped.
+#[doc(hidden)]
+#[must_use]
+pub struct HostCall {
+	path: &'static str,
+	generation: u64,
+}
+
+impl Drop for HostCall {
+	fn drop(&mut self) {
+		STATE.with(|state| {
+			let mut state = state.borrow_mut();
+			state.depth -= 1;
+			if state.depth == 0 && state.generation == self.generation {
+				if let Some(counts) = state.counts.as_mut() {
+					*counts.entry(self.path).or_default() += 1;
+				}
+			}
+		});
+	}
+}
+
+/// Records a call to the host function at `path`, if counting on the current thread.
+///
+/// The call is counted when the returned value is dropped.
+#[doc(hidden)]
+pub fn record(path: &'static str) -> Option<HostCall> {
+	if COUNTING_THREADS.load(Ordering::Relaxed) == 0 {
+		return None
+	}
+
+	STATE.with(|state| {
+		let mut state = state.borrow_mut();
+		state.counts.as_ref()?;
+		state.depth += 1;
+		Some(HostCall { path, generation: state.generation })
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counts_only_while_counting() {
+		drop(record("storage::get"));
+		assert!(stop_counting().is_empty());
+
+		start_counting();
+		drop(record("storage::get"));
+		drop(record("hashing::twox_128"));
+		drop(record("storage::get"));
+		assert_eq!(stop_counting(), vec![("hashing::twox_128", 1), ("storage::get", 2)]);
+
+		drop(record("storage::get"));
+		assert!(stop_counting().is_empty());
+	}
+
+	#[test]
+	fn counts_only_outermost_calls() {
+		start_counting();
+		{
+			let _outer = record("storage::root");
+			drop(record("hashing::blake2_256"));
+			drop(record("hashing::blake2_256"));
+		}
+		assert_eq!(stop_counting(), vec![("storage::root", 1)]);
+	}
+
+	#[test]
+	fn does_not_count_the_counting_calls() {
+		let start = record("benchmarking::start_host_call_counting");
+		start_counting();
+		drop(start);
+
+		drop(record("storage::get"));
+
+		let stop = record("benchmarking::stop_host_call_counting");
+		assert_eq!(stop_counting(), vec![("storage::get", 1)]);
+		drop(stop);
+
+		start_counting();
+		let restart = record("benchmarking::start_host_call_counting");
+		start_counting();
+		drop(restart);
+		assert!(stop_counting().is_empty());
+	}
+}
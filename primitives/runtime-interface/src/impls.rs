@@ -540,6 +540,10 @@ impl PassBy for sp_wasm_interface::Value {
 	type PassBy = Codec<sp_wasm_interface::Value>;
 }
 
+impl PassBy for sp_wasm_interface::AllocationStats {
+	type PassBy = Codec<Self>;
+}
+
 impl PassBy for sp_storage::TrackedStorageKey {
 	type PassBy = Codec<Self>;
 }
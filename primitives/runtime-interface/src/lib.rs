@@ -307,6 +307,8 @@ pub use codec;
 pub(crate) mod impls;
 #[cfg(feature = "std")]
 pub mod host;
+#[cfg(feature = "std")]
+pub mod host_calls;
 #[cfg(any(not(feature = "std"), doc))]
 pub mod wasm;
 pub mod pass_by;
@@ -130,6 +130,10 @@ pub trait TestApi {
 	) -> (Vec<u8>, u32, Option<Vec<u32>>, u8) {
 		(a, b, c, d)
 	}
+
+	/// A function that is always configured out, so it must not be a host function.
+	#[cfg(any())]
+	fn configured_out() {}
 }
 
 /// This function is not used, but we require it for the compiler to include `sp-io`.
@@ -138,6 +138,14 @@ fn test_array_return_value_memory_is_freed() {
 	call_wasm_method::<HostFunctions>(&wasm_binary_unwrap()[..], "test_array_return_value_memory_is_freed");
 }
 
+#[test]
+fn configured_out_method_is_not_a_host_function() {
+	let names = HostFunctions::host_functions().iter().map(|f| f.name()).collect::<Vec<_>>();
+
+	assert!(names.contains(&"ext_test_api_return_input_version_1"));
+	assert!(!names.iter().any(|name| name.contains("configured_out")));
+}
+
 #[test]
 fn test_versionining_with_new_host_works() {
 	// We call to the new wasm binary with new host function.
@@ -0,0 +1,16 @@
+#![deny(deprecated)]
+
+use sp_runtime_interface::runtime_interface;
+
+#[runtime_interface]
+trait Test {
+	fn current() {}
+
+	#[deprecated(note = "Use `current` instead")]
+	fn old() {}
+}
+
+fn main() {
+	test::current();
+	test::old();
+}
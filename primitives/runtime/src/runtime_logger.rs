@@ -40,13 +40,13 @@ impl RuntimeLogger {
 		static LOGGER: RuntimeLogger = RuntimeLogger;
 		let _ = log::set_logger(&LOGGER);
 
-		// Set max level to `TRACE` to ensure we propagate
-		// all log entries to the native side that will do the
-		// final filtering on what should be printed.
+		// Use the max level of the native side, so that log entries
+		// it would discard anyway are not even formatted. The native
+		// side still does the final filtering on what should be printed.
 		//
 		// If we don't set any level, logging is disabled
 		// completly.
-		log::set_max_level(log::LevelFilter::Trace);
+		log::set_max_level(sp_io::logging::max_level().into());
 	}
 }
 
@@ -245,6 +245,13 @@ pub trait Backend<H: Hasher>: sp_std::fmt::Debug {
 
 	/// Update the whitelist for tracking db reads/writes
 	fn set_whitelist(&self, _: Vec<TrackedStorageKey>) {}
+
+	/// Returns the encoded size of the storage proof recorded so far.
+	///
+	/// `None` if this backend doesn't record a proof.
+	fn proof_size(&self) -> Option<u32> {
+		None
+	}
 }
 
 impl<'a, T: Backend<H>, H: Hasher> Backend<H> for &'a T {
@@ -325,6 +332,10 @@ impl<'a, T: Backend<H>, H: Hasher> Backend<H> for &'a T {
 	fn usage_info(&self) -> UsageInfo {
 		(*self).usage_info()
 	}
+
+	fn proof_size(&self) -> Option<u32> {
+		(*self).proof_size()
+	}
 }
 
 /// Trait that allows consolidate two transactions together.
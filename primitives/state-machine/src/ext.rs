@@ -598,6 +598,10 @@ where
 		});
 	}
 
+	fn proof_size(&self) -> Option<u32> {
+		self.backend.proof_size()
+	}
+
 	#[cfg(not(feature = "std"))]
 	fn storage_changes_root(&mut self, _parent_hash: &[u8]) -> Result<Option<Vec<u8>>, ()> {
 		Ok(None)
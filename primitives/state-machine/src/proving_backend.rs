@@ -19,7 +19,7 @@
 
 use std::{sync::Arc, collections::HashMap};
 use parking_lot::RwLock;
-use codec::{Decode, Codec};
+use codec::{Decode, Codec, Compact, Encode};
 use log::debug;
 use hash_db::{Hasher, HashDB, EMPTY_PREFIX, Prefix};
 use sp_trie::{
@@ -157,6 +157,18 @@ impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> ProvingBackend<'a, S, H>
 			.collect();
 		StorageProof::new(trie_nodes)
 	}
+
+	/// Returns the size of the encoded proof gathered so far, without building it.
+	pub fn estimate_encoded_size(&self) -> usize {
+		let recorder = self.0.essence().backend_storage().proof_recorder.read();
+		let mut count = 0;
+		let mut size = 0;
+		for value in recorder.values().filter_map(|v| v.as_ref()) {
+			count += 1;
+			size += Compact(value.len() as u32).encoded_size() + value.len();
+		}
+		Compact(count as u32).encoded_size() + size
+	}
 }
 
 impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> TrieBackendStorage<H>
@@ -277,6 +289,10 @@ impl<'a, S, H> Backend<H> for ProvingBackend<'a, S, H>
 	fn usage_info(&self) -> crate::stats::UsageInfo {
 		self.0.usage_info()
 	}
+
+	fn proof_size(&self) -> Option<u32> {
+		Some(self.estimate_encoded_size() as u32)
+	}
 }
 
 /// Create proof check backend.
@@ -326,6 +342,19 @@ mod tests {
 		assert!(!backend.extract_proof().is_empty());
 	}
 
+	#[test]
+	fn proof_size_matches_encoded_proof() {
+		let trie_backend = test_trie();
+		let backend = test_proving(&trie_backend);
+		assert_eq!(backend.proof_size(), Some(1));
+		assert_eq!(backend.storage(b"key").unwrap(), Some(b"value".to_vec()));
+		assert_eq!(
+			backend.proof_size(),
+			Some(backend.extract_proof().encoded_size() as u32),
+		);
+		assert!(trie_backend.proof_size().is_none());
+	}
+
 	#[test]
 	fn proof_is_invalid_when_does_not_contains_root() {
 		use sp_core::H256;
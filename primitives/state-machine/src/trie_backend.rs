@@ -29,6 +29,10 @@ use crate::{
 };
 use sp_std::{boxed::Box, vec::Vec};
 
+/// Number of top trie writes above which the storage root is computed in parallel.
+#[cfg(feature = "std")]
+const PARALLEL_STORAGE_ROOT_THRESHOLD: usize = 10_000;
+
 /// Patricia trie-based backend. Transaction type is an overlay of changes to commit.
 pub struct TrieBackend<S: TrieBackendStorage<H>, H: Hasher> {
 	pub (crate) essence: TrieBackendEssence<S, H>,
@@ -57,6 +61,129 @@ impl<S: TrieBackendStorage<H>, H: Hasher> TrieBackend<S, H> where H::Out: Codec
 		self.essence.backend_storage_mut()
 	}
 
+	/// Compute the new storage root by partitioning the delta on the nibble
+	/// following the partial key of the root node.
+	///
+	/// Each partition only touches its own sub-trie of the root branch, so the
+	/// partitions are applied on separate threads, and the root node is then
+	/// rebuilt from the sub-trie roots they produced.
+	///
+	/// Returns `None` when the delta cannot be partitioned this way (the root is
+	/// not a branch, a key ends at or diverges from the root node, or the root
+	/// node would change shape), in which case the root should be computed
+	/// sequentially.
+	#[cfg(feature = "std")]
+	fn partitioned_storage_root<'a>(
+		&self,
+		delta: &[(&'a [u8], Option<&'a [u8]>)],
+	) -> Option<(H::Out, S::Overlay)> {
+		use sp_trie::{NodeCodec, EMPTY_PREFIX, nibble_ops::NIBBLE_LENGTH};
+		use trie_db::{
+			NodeCodec as NodeCodecT, ChildReference, NibbleSlice,
+			node::{Node, NodeHandle},
+		};
+		use crate::backend::Consolidate;
+
+		fn child_reference<H: Hasher>(handle: &NodeHandle) -> ChildReference<H::Out> {
+			let mut hash = H::Out::default();
+			match handle {
+				NodeHandle::Hash(data) => {
+					hash.as_mut().copy_from_slice(data);
+					ChildReference::Hash(hash)
+				},
+				NodeHandle::Inline(data) => {
+					hash.as_mut()[..data.len()].copy_from_slice(data);
+					ChildReference::Inline(hash, data.len())
+				},
+			}
+		}
+
+		let root = *self.essence.root();
+		let storage = self.essence.backend_storage();
+		let root_data = storage.get(&root, EMPTY_PREFIX).ok()??;
+		let (partial, children, value) = match NodeCodec::<H>::decode(&root_data).ok()? {
+			Node::NibbledBranch(partial, children, value) => (partial, children, value),
+			_ => return None,
+		};
+
+		let depth = partial.len();
+		let mut partitions = vec![Vec::new(); NIBBLE_LENGTH];
+		for (key, change) in delta {
+			let nibbles = NibbleSlice::new(key);
+			if nibbles.len() <= depth || !nibbles.starts_with(&partial) {
+				return None
+			}
+			partitions[nibbles.at(depth) as usize].push((*key, *change));
+		}
+
+		let sub_roots = crossbeam_utils::thread::scope(|scope| {
+			let handles: Vec<_> = partitions.into_iter()
+				.enumerate()
+				.filter(|(_, delta)| !delta.is_empty())
+				.map(|(index, delta)| scope.spawn(move |_| {
+					let mut overlay = S::Overlay::default();
+					let sub_root = delta_trie_root::<Layout<H>, _, _, _, _, _>(
+						&mut Ephemeral::new(storage, &mut overlay),
+						root,
+						delta,
+					).ok()?;
+					Some((index, sub_root, overlay))
+				}))
+				.collect();
+
+			handles.into_iter()
+				.map(|handle| handle.join().expect("Storage root computation panicked"))
+				.collect::<Option<Vec<_>>>()
+		}).expect("Storage root computation threads are joined above; qed")?;
+
+		let mut children: Vec<_> = children.iter()
+			.map(|child| child.as_ref().map(child_reference::<H>))
+			.collect();
+		let mut write_overlay = S::Overlay::default();
+		let mut changed = 0;
+		for (index, sub_root, overlay) in sub_roots {
+			write_overlay.consolidate(overlay);
+			if sub_root == root {
+				continue
+			}
+
+			let sub_root_data = hash_db::HashDB::get(&write_overlay, &sub_root, EMPTY_PREFIX)?;
+			match NodeCodec::<H>::decode(&sub_root_data).ok()? {
+				Node::NibbledBranch(sub_partial, sub_children, sub_value)
+					if sub_partial == partial && sub_value == value =>
+				{
+					children[index] = sub_children[index].as_ref().map(child_reference::<H>);
+				},
+				_ => return None,
+			}
+			// The sub-trie root only exists to carry the new child, the root node
+			// replacing it is inserted below.
+			hash_db::HashDB::remove(&mut write_overlay, &sub_root, EMPTY_PREFIX);
+			changed += 1;
+		}
+
+		if changed == 0 {
+			return Some((root, write_overlay))
+		}
+		if value.is_none() && children.iter().filter(|child| child.is_some()).count() < 2 {
+			return None
+		}
+
+		// Every changed partition removed the previous root node once.
+		for _ in 1..changed {
+			hash_db::HashDB::insert(&mut write_overlay, EMPTY_PREFIX, &root_data);
+		}
+		let root_data = NodeCodec::<H>::branch_node_nibbled(
+			partial.right_iter(),
+			depth,
+			children.into_iter(),
+			value,
+		);
+		let root = hash_db::HashDB::insert(&mut write_overlay, EMPTY_PREFIX, &root_data);
+
+		Some((root, write_overlay))
+	}
+
 	/// Get trie root.
 	pub fn root(&self) -> &H::Out {
 		self.essence.root()
@@ -172,6 +299,17 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 		&self,
 		delta: impl Iterator<Item=(&'a [u8], Option<&'a [u8]>)>,
 	) -> (H::Out, Self::Transaction) where H::Out: Ord {
+		#[cfg(feature = "std")]
+		let delta = {
+			let delta: Vec<_> = delta.collect();
+			if delta.len() >= PARALLEL_STORAGE_ROOT_THRESHOLD && num_cpus::get() > 1 {
+				if let Some(result) = self.partitioned_storage_root(&delta) {
+					return result
+				}
+			}
+			delta.into_iter()
+		};
+
 		let mut write_overlay = S::Overlay::default();
 		let mut root = *self.essence.root();
 
@@ -249,7 +387,7 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 
 #[cfg(test)]
 pub mod tests {
-	use std::{collections::HashSet, iter};
+	use std::{collections::{HashMap, HashSet}, iter};
 	use sp_core::H256;
 	use codec::Encode;
 	use sp_trie::{TrieMut, PrefixedMemoryDB, trie_types::TrieDBMut, KeySpacedDBMut};
@@ -357,4 +495,35 @@ pub mod tests {
 		expected.insert(b"value2".to_vec());
 		assert_eq!(seen, expected);
 	}
+
+	#[test]
+	fn partitioned_storage_root_matches_sequential() {
+		fn changes(
+			tx: &mut PrefixedMemoryDB<BlakeTwo256>,
+		) -> HashMap<Vec<u8>, (Vec<u8>, i32)> {
+			tx.drain().into_iter().filter(|(_, (_, rc))| *rc != 0).collect()
+		}
+
+		let trie = test_trie();
+		let keys: Vec<_> = (0..PARALLEL_STORAGE_ROOT_THRESHOLD as u32)
+			.map(|i| i.to_le_bytes())
+			.collect();
+		let mut delta: Vec<_> = keys.iter().map(|k| (&k[..], Some(&k[..]))).collect();
+		delta.push((&b"value1"[..], None));
+
+		let (root, mut tx) = trie.partitioned_storage_root(&delta)
+			.expect("Root of the test trie is a branch");
+		let (expected_root, mut expected_tx) = {
+			let mut overlay = PrefixedMemoryDB::default();
+			let root = delta_trie_root::<Layout<BlakeTwo256>, _, _, _, _, _>(
+				&mut Ephemeral::new(trie.essence.backend_storage(), &mut overlay),
+				*trie.root(),
+				delta.clone(),
+			).unwrap();
+			(root, overlay)
+		};
+
+		assert_eq!(root, expected_root);
+		assert_eq!(changes(&mut tx), changes(&mut expected_tx));
+	}
 }
@@ -23,6 +23,7 @@ use std::{
 	sync::Arc,
 	pin::Pin,
 };
+use codec::Encode;
 use futures::{Future, Stream};
 use serde::{Deserialize, Serialize};
 use sp_runtime::{
@@ -53,6 +54,53 @@ impl PoolStatus {
 	}
 }
 
+/// Queue of the pool holding a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionQueue {
+	/// The transaction can be included in the next block.
+	Ready,
+	/// The transaction waits for the tags it requires.
+	Future,
+}
+
+/// Diagnostics about a transaction in the pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDiagnostics<Hash> {
+	/// Hash of the transaction.
+	pub hash: Hash,
+	/// Priority of the transaction, serialized as a string.
+	#[serde(with = "number_as_string")]
+	pub priority: TransactionPriority,
+	/// Block number at which the transaction becomes invalid, serialized as a string.
+	#[serde(with = "number_as_string")]
+	pub valid_till: TransactionLongevity,
+	/// Tags required by the transaction.
+	pub requires: Vec<TransactionTag>,
+	/// Tags provided by the transaction.
+	pub provides: Vec<TransactionTag>,
+	/// Length of the encoded transaction.
+	pub bytes: usize,
+	/// Milliseconds elapsed since the transaction entered the pool.
+	pub time_in_pool: u64,
+	/// Queue holding the transaction.
+	pub queue: TransactionQueue,
+}
+
+/// (De)serialize a `u64` as a decimal string, as JSON numbers can't represent all of them.
+mod number_as_string {
+	use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+	pub fn serialize<S: Serializer>(number: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(number)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+	}
+}
+
 /// Possible transaction status events.
 ///
 /// This events are being emitted by `TransactionPool` watchers,
@@ -204,6 +252,18 @@ pub trait TransactionPool: Send + Sync {
 		xt: TransactionFor<Self>,
 	) -> PoolFuture<TxHash<Self>, Self::Error>;
 
+	/// Returns a future that imports one unverified transaction given along with its encoding.
+	///
+	/// The transaction hash and length are computed from `encoded`, sparing a re-encoding of
+	/// transactions received from the network.
+	fn submit_one_encoded(
+		&self,
+		at: &BlockId<Self::Block>,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+		encoded: &[u8],
+	) -> PoolFuture<TxHash<Self>, Self::Error>;
+
 	/// Returns a future that import a single transaction and starts to watch their progress in the pool.
 	fn submit_and_watch(
 		&self,
@@ -223,6 +283,13 @@ pub trait TransactionPool: Send + Sync {
 	/// Get an iterator for ready transactions ordered by priority.
 	fn ready(&self) -> Box<dyn Iterator<Item=Arc<Self::InPoolTransaction>> + Send>;
 
+	/// Get diagnostics about all transactions in the pool, ready ones first.
+	///
+	/// The default implementation returns nothing, for pools that don't track them.
+	fn inspect(&self) -> Vec<TransactionDiagnostics<TxHash<Self>>> {
+		Vec::new()
+	}
+
 	// *** Block production
 	/// Remove transactions identified by given hashes (and dependent transactions) from the pool.
 	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>>;
@@ -242,11 +309,15 @@ pub trait TransactionPool: Send + Sync {
 	/// Returns transaction hash
 	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self>;
 
+	/// Returns transaction hash given its SCALE encoding.
+	fn hash_of_encoded(&self, encoded: &[u8]) -> TxHash<Self>;
+
 	/// Return specific ready transaction by hash, if there is one.
 	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>>;
 }
 
 /// Events that the transaction pool listens for.
+#[derive(Clone)]
 pub enum ChainEvent<B: BlockT> {
 	/// New best block have been added to the chain
 	NewBestBlock {
@@ -270,6 +341,66 @@ pub trait MaintainedTransactionPool: TransactionPool {
 	fn maintain(&self, event: ChainEvent<Self::Block>) -> Pin<Box<dyn Future<Output=()> + Send>>;
 }
 
+/// Maintenance performed on chain events.
+///
+/// The transaction pool maintains itself through [`MaintainedTransactionPool`]; implementing this
+/// trait allows nodes to replace or extend that maintenance, for instance with
+/// [`ChainedMaintainer`].
+pub trait TransactionPoolMaintainer: Send + Sync {
+	/// Block type.
+	type Block: BlockT;
+
+	/// Perform maintenance
+	fn maintain(&self, event: ChainEvent<Self::Block>) -> Pin<Box<dyn Future<Output=()> + Send>>;
+}
+
+impl<P: MaintainedTransactionPool + ?Sized> TransactionPoolMaintainer for Arc<P> {
+	type Block = P::Block;
+
+	fn maintain(&self, event: ChainEvent<Self::Block>) -> Pin<Box<dyn Future<Output=()> + Send>> {
+		MaintainedTransactionPool::maintain(&**self, event)
+	}
+}
+
+impl<B: BlockT> TransactionPoolMaintainer for Box<dyn TransactionPoolMaintainer<Block = B>> {
+	type Block = B;
+
+	fn maintain(&self, event: ChainEvent<Self::Block>) -> Pin<Box<dyn Future<Output=()> + Send>> {
+		(**self).maintain(event)
+	}
+}
+
+/// Maintainer running two maintainers one after the other.
+pub struct ChainedMaintainer<First, Second> {
+	first: First,
+	second: Arc<Second>,
+}
+
+impl<First, Second> ChainedMaintainer<First, Second> {
+	/// Create a maintainer awaiting the maintenance of `first`, then that of `second`.
+	///
+	/// `second` only starts its maintenance once that of `first` has completed.
+	pub fn new(first: First, second: Second) -> Self {
+		ChainedMaintainer { first, second: Arc::new(second) }
+	}
+}
+
+impl<First, Second> TransactionPoolMaintainer for ChainedMaintainer<First, Second> where
+	First: TransactionPoolMaintainer,
+	Second: TransactionPoolMaintainer<Block = First::Block> + 'static,
+{
+	type Block = First::Block;
+
+	fn maintain(&self, event: ChainEvent<Self::Block>) -> Pin<Box<dyn Future<Output=()> + Send>> {
+		let first = self.first.maintain(event.clone());
+		let second = self.second.clone();
+		Box::pin(async move {
+			first.await;
+			second.maintain(event).await;
+		})
+	}
+}
+
 /// Transaction pool interface for submitting local transactions that exposes a
 /// blocking interface for submission.
 pub trait LocalTransactionPool: Send + Sync {
@@ -333,3 +464,28 @@ impl<TPool: LocalTransactionPool> OffchainSubmitTransaction<TPool::Block> for TP
 		})
 	}
 }
+
+/// Read-only access to the transaction pool for offchain calls.
+///
+/// Lets offchain workers see what is already pending before submitting their own transactions,
+/// for instance to aggregate them.
+pub trait OffchainReadyTransactions: Send + Sync {
+	/// Returns the encoded hash and extrinsic of the transactions ready to be included in a block.
+	///
+	/// Returns at most `max_count` transactions, whose encodings add up to at most `max_bytes`.
+	fn ready_transactions(&self, max_count: u32, max_bytes: u32) -> Vec<(Vec<u8>, Vec<u8>)>;
+}
+
+impl<TPool: TransactionPool> OffchainReadyTransactions for TPool where TPool::Hash: Encode {
+	fn ready_transactions(&self, max_count: u32, max_bytes: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let mut total_bytes = 0usize;
+		self.ready()
+			.take(max_count as usize)
+			.map(|tx| (tx.hash().encode(), tx.data().encode()))
+			.take_while(|(_, xt)| {
+				total_bytes = total_bytes.saturating_add(xt.len());
+				total_bytes <= max_bytes as usize
+			})
+			.collect()
+	}
+}
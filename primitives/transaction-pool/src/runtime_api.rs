@@ -22,7 +22,7 @@ use sp_runtime::traits::Block as BlockT;
 
 sp_api::decl_runtime_apis! {
 	/// The `TaggedTransactionQueue` api trait for interfering with the transaction queue.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait TaggedTransactionQueue {
 		/// Validate the transaction.
 		#[changed_in(2)]
@@ -39,5 +39,10 @@ sp_api::decl_runtime_apis! {
 			source: TransactionSource,
 			tx: <Block as BlockT>::Extrinsic,
 		) -> TransactionValidity;
+
+		/// Returns the maximum encoded length of an extrinsic that fits in a block.
+		///
+		/// The transaction pool rejects longer transactions without validating them.
+		fn max_extrinsic_length() -> u32;
 	}
 }
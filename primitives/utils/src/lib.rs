@@ -17,6 +17,7 @@
 
 //! Utilities Primitives for Substrate
 
+pub mod memory_usage;
 pub mod metrics;
 pub mod mpsc;
 pub mod status_sinks;
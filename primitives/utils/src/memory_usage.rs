@@ -0,0 +1,68 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory usage metrics of caches and pools.
+//!
+//! Components holding a large amount of data in memory (state cache, transaction pool, ...)
+//! enforce their own size limits. They report their usage, their limit and the data they
+//! evict to stay within it through a [`MemoryUsage`], which exposes them in the global
+//! metrics registered by [`crate::metrics::register_globals`].
+
+use crate::metrics::{MEMORY_LIMIT, MEMORY_USED, MEMORY_EVICTED};
+
+/// Memory usage metrics of a single component.
+#[derive(Debug, Clone)]
+pub struct MemoryUsage {
+	component: &'static str,
+}
+
+impl MemoryUsage {
+	/// Create the metrics of the given component, which limits itself to `limit` bytes.
+	pub fn new(component: &'static str, limit: usize) -> Self {
+		MEMORY_LIMIT.with_label_values(&[component]).set(limit as u64);
+		MemoryUsage { component }
+	}
+
+	/// Report the number of bytes currently used by the component.
+	pub fn report_usage(&self, used: usize) {
+		MEMORY_USED.with_label_values(&[self.component]).set(used as u64);
+	}
+
+	/// Report that the component evicted `bytes` to stay within its limit.
+	pub fn report_eviction(&self, bytes: usize) {
+		if bytes > 0 {
+			MEMORY_EVICTED.with_label_values(&[self.component]).inc_by(bytes as u64);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reports_usage_and_evictions() {
+		let usage = MemoryUsage::new("test", 100);
+		usage.report_usage(101);
+		assert_eq!(MEMORY_USED.with_label_values(&["test"]).get(), 101);
+		assert_eq!(MEMORY_LIMIT.with_label_values(&["test"]).get(), 100);
+
+		usage.report_eviction(10);
+		usage.report_eviction(0);
+		assert_eq!(MEMORY_EVICTED.with_label_values(&["test"]).get(), 10);
+	}
+}
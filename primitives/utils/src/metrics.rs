@@ -20,12 +20,10 @@
 use lazy_static::lazy_static;
 use prometheus::{
 	Registry, Error as PrometheusError,
-	core::{ AtomicU64, GenericGauge, GenericCounter },
+	core::{ AtomicU64, GenericGauge, GenericCounter, GenericGaugeVec, GenericCounterVec },
+	Opts,
 };
 
-#[cfg(feature = "metered")]
-use prometheus::{core::GenericCounterVec, Opts};
-
 
 lazy_static! {
 	pub static ref TOKIO_THREADS_TOTAL: GenericCounter<AtomicU64> = GenericCounter::new(
@@ -35,6 +33,24 @@ lazy_static! {
 	pub static ref TOKIO_THREADS_ALIVE: GenericGauge<AtomicU64> = GenericGauge::new(
 		"tokio_threads_alive", "Number of threads alive right now"
 	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref MEMORY_LIMIT: GenericGaugeVec<AtomicU64> = GenericGaugeVec::new(
+		Opts::new("memory_limit_bytes", "Memory limit of each component"),
+		&["component"]
+	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref MEMORY_USED: GenericGaugeVec<AtomicU64> = GenericGaugeVec::new(
+		Opts::new("memory_used_bytes", "Memory used by each component"),
+		&["component"]
+	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref MEMORY_EVICTED: GenericCounterVec<AtomicU64> = GenericCounterVec::new(
+		Opts::new(
+			"memory_evicted_bytes_total",
+			"Bytes evicted by each component to stay within its limit",
+		),
+		&["component"]
+	).expect("Creating of statics doesn't fail. qed");
 }
 
 #[cfg(feature = "metered")]
@@ -51,6 +67,9 @@ lazy_static! {
 pub fn register_globals(registry: &Registry) -> Result<(), PrometheusError> {
 	registry.register(Box::new(TOKIO_THREADS_ALIVE.clone()))?;
 	registry.register(Box::new(TOKIO_THREADS_TOTAL.clone()))?;
+	registry.register(Box::new(MEMORY_LIMIT.clone()))?;
+	registry.register(Box::new(MEMORY_USED.clone()))?;
+	registry.register(Box::new(MEMORY_EVICTED.clone()))?;
 
 	#[cfg(feature = "metered")]
 	registry.register(Box::new(UNBOUNDED_CHANNELS_COUNTER.clone()))?;
@@ -272,6 +272,21 @@ impl PartialEq for dyn Function {
 	}
 }
 
+/// Statistics of the allocator of a wasm instance.
+///
+/// Sizes include the headers of the allocations.
+#[derive(Clone, Copy, PartialEq, Eq, Default, codec::Encode, codec::Decode, Debug)]
+pub struct AllocationStats {
+	/// Number of bytes currently allocated.
+	pub bytes_allocated: u32,
+	/// Highest number of bytes allocated at once.
+	pub bytes_allocated_peak: u32,
+	/// Number of allocations not freed yet.
+	pub live_allocations: u32,
+	/// Number of allocations made, freed or not.
+	pub total_allocations: u32,
+}
+
 /// Context used by `Function` to interact with the allocator and the memory of the wasm instance.
 pub trait FunctionContext {
 	/// Read memory from `address` into a vector.
@@ -288,6 +303,8 @@ pub trait FunctionContext {
 	fn allocate_memory(&mut self, size: WordSize) -> Result<Pointer<u8>>;
 	/// Deallocate a given memory instance.
 	fn deallocate_memory(&mut self, ptr: Pointer<u8>) -> Result<()>;
+	/// Returns the statistics of the allocator.
+	fn allocation_stats(&self) -> AllocationStats;
 	/// Provides access to the sandbox.
 	fn sandbox(&mut self) -> &mut dyn Sandbox;
 }
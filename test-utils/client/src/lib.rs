@@ -273,6 +273,7 @@ impl<Block: BlockT, E, Backend, G: GenesisInit> TestClientBuilder<
 			executor,
 			Box::new(sp_core::testing::TaskExecutor::new()),
 			Default::default(),
+			None,
 		).expect("Creates LocalCallExecutor");
 
 		self.build_with_executor(executor)
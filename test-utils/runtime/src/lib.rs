@@ -54,7 +54,7 @@ use sp_version::NativeVersion;
 use frame_support::{
 	impl_outer_origin, parameter_types,
 	traits::KeyOwnerProofSystem,
-	weights::RuntimeDbWeight,
+	weights::{RuntimeDbWeight, DispatchClass},
 };
 use frame_system::limits::{BlockWeights, BlockLength};
 use sp_inherents::{CheckInherentsResult, InherentData};
@@ -366,6 +366,8 @@ cfg_if! {
 				fn test_multiple_arguments(data: Vec<u8>, other: Vec<u8>, num: u32);
 				/// Traces log "Hey I'm runtime."
 				fn do_trace_log();
+				/// Returns the inherent data with the given identifier provided by the node.
+				fn inherent_data(identifier: Vec<u8>) -> Option<Vec<u8>>;
 			}
 		}
 	} else {
@@ -419,6 +421,8 @@ cfg_if! {
 				fn test_multiple_arguments(data: Vec<u8>, other: Vec<u8>, num: u32);
 				/// Traces log "Hey I'm runtime."
 				fn do_trace_log();
+				/// Returns the inherent data with the given identifier provided by the node.
+				fn inherent_data(identifier: Vec<u8>) -> Option<Vec<u8>>;
 			}
 		}
 	}
@@ -647,6 +651,10 @@ cfg_if! {
 
 					system::validate_transaction(utx)
 				}
+
+				fn max_extrinsic_length() -> u32 {
+					*RuntimeBlockLength::get().max.get(DispatchClass::Normal)
+				}
 			}
 
 			impl sp_block_builder::BlockBuilder<Block> for Runtime {
@@ -760,6 +768,10 @@ cfg_if! {
 				fn do_trace_log() {
 					log::trace!("Hey I'm runtime");
 				}
+
+				fn inherent_data(identifier: Vec<u8>) -> Option<Vec<u8>> {
+					sp_io::inherent_data::get(&identifier)
+				}
 			}
 
 			impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
@@ -904,6 +916,10 @@ cfg_if! {
 
 					system::validate_transaction(utx)
 				}
+
+				fn max_extrinsic_length() -> u32 {
+					*RuntimeBlockLength::get().max.get(DispatchClass::Normal)
+				}
 			}
 
 			impl sp_block_builder::BlockBuilder<Block> for Runtime {
@@ -1021,6 +1037,10 @@ cfg_if! {
 				fn do_trace_log() {
 					log::error!("Hey I'm runtime: {}", log::STATIC_MAX_LEVEL);
 				}
+
+				fn inherent_data(identifier: Vec<u8>) -> Option<Vec<u8>> {
+					sp_io::inherent_data::get(&identifier)
+				}
 			}
 
 			impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
@@ -272,6 +272,7 @@ impl<T: ChainInfo> Node<T> {
 				keystore,
 				on_demand: None,
 				transaction_pool: transaction_pool.clone(),
+				transaction_pool_maintainer: None,
 				rpc_extensions_builder: Box::new(move |_, _| jsonrpc_core::IoHandler::default()),
 				remote_blockchain: None,
 				network,
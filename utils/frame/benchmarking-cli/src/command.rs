@@ -18,7 +18,7 @@
 use std::sync::Arc;
 use crate::BenchmarkCmd;
 use codec::{Decode, Encode};
-use frame_benchmarking::{Analysis, BenchmarkBatch, BenchmarkSelector};
+use frame_benchmarking::{Analysis, BenchmarkBatch, BenchmarkResults, BenchmarkSelector};
 use sc_cli::{SharedParams, CliConfiguration, ExecutionStrategy, Result};
 use sc_client_db::BenchmarkingState;
 use sc_executor::NativeExecutor;
@@ -31,7 +31,7 @@ use sp_keystore::{
 	SyncCryptoStorePtr, KeystoreExt,
 	testing::KeyStore,
 };
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
 
 impl BenchmarkCmd {
 	/// Runs the command and benchmarks the chain.
@@ -126,19 +126,28 @@ impl BenchmarkCmd {
 						// Print the table header
 						batch.results[0].components.iter().for_each(|param| print!("{:?},", param.0));
 
-						print!("extrinsic_time,storage_root_time,reads,repeat_reads,writes,repeat_writes\n");
+						print!(
+							"extrinsic_time,storage_root_time,reads,repeat_reads,writes,repeat_writes,\
+							host_calls\n"
+						);
 						// Print the values
 						batch.results.iter().for_each(|result| {
 							let parameters = &result.components;
 							parameters.iter().for_each(|param| print!("{:?},", param.1));
 							// Print extrinsic time and storage root time
-							print!("{:?},{:?},{:?},{:?},{:?},{:?}\n",
+							print!("{:?},{:?},{:?},{:?},{:?},{:?},\"{}\"\n",
 								result.extrinsic_time,
 								result.storage_root_time,
 								result.reads,
 								result.repeat_reads,
 								result.writes,
 								result.repeat_writes,
+								result.host_calls.iter()
+									.map(|(path, count)| {
+										format!("{}={}", String::from_utf8_lossy(path), count)
+									})
+									.collect::<Vec<_>>()
+									.join(";"),
 							);
 						});
 
@@ -170,6 +179,14 @@ impl BenchmarkCmd {
 							println!("Writes = {:?}", analysis);
 						}
 					}
+
+					let host_calls = host_call_ranges(&batch.results);
+					if !host_calls.is_empty() {
+						println!("Host Calls\n========");
+						for (path, (min, max)) in host_calls {
+							println!("{} = {}..={}", path, min, max);
+						}
+					}
 				}
 			},
 			Err(error) => eprintln!("Error: {}", error),
@@ -179,6 +196,32 @@ impl BenchmarkCmd {
 	}
 }
 
+/// The lowest and highest number of calls of each host function across the given results.
+///
+/// A host function not called by some of the results counts as called zero times by them.
+fn host_call_ranges(results: &[BenchmarkResults]) -> BTreeMap<String, (u32, u32)> {
+	let mut ranges = BTreeMap::new();
+	for result in results {
+		for (path, count) in &result.host_calls {
+			ranges.entry(String::from_utf8_lossy(path).into_owned())
+				.and_modify(|(min, max): &mut (u32, u32)| {
+					*min = (*min).min(*count);
+					*max = (*max).max(*count);
+				})
+				.or_insert((*count, *count));
+		}
+	}
+	for (path, (min, _)) in ranges.iter_mut() {
+		let called_by_all = results.iter().all(|result| {
+			result.host_calls.iter().any(|(p, _)| String::from_utf8_lossy(p) == path.as_str())
+		});
+		if !called_by_all {
+			*min = 0;
+		}
+	}
+	ranges
+}
+
 impl CliConfiguration for BenchmarkCmd {
 	fn shared_params(&self) -> &SharedParams {
 		&self.shared_params
@@ -17,19 +17,22 @@
 
 //! `Structopt`-ready struct for `try-runtime`.
 
-use parity_scale_codec::Decode;
+use parity_scale_codec::{Decode, Encode};
 use std::{fmt::Debug, path::PathBuf, str::FromStr};
 use sc_service::Configuration;
 use sc_cli::{CliConfiguration, ExecutionStrategy, WasmExecutionMethod};
 use sc_executor::NativeExecutor;
 use sc_service::NativeExecutionDispatch;
 use sp_state_machine::StateMachine;
-use sp_runtime::traits::{Block as BlockT, NumberFor};
-use sp_core::storage::{StorageData, StorageKey, well_known_keys};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use sp_core::{hexdisplay::HexDisplay, storage::{StorageData, StorageKey, well_known_keys}};
+use remote_externalities::TestExternalities;
+
+mod offchain;
+
+pub use offchain::{HttpExchange, OffchainWorkerCmd, Recording};
 
 /// Various commands to try out the new runtime, over configurable states.
-///
-/// For now this only assumes running the `on_runtime_upgrade` hooks.
 #[derive(Debug, structopt::StructOpt)]
 pub struct TryRuntimeCmd {
 	/// The shared parameters
@@ -57,9 +60,29 @@ pub struct TryRuntimeCmd {
 	)]
 	pub wasm_method: WasmExecutionMethod,
 
-	/// The state to use to run the migration.
+	/// What to run.
 	#[structopt(subcommand)]
-	pub state: State,
+	pub command: Command,
+}
+
+/// What to run on top of the state.
+#[derive(Debug, structopt::StructOpt)]
+pub enum Command {
+	/// Run the `on_runtime_upgrade` hooks.
+	OnRuntimeUpgrade {
+		/// The state to use to run the migration.
+		#[structopt(subcommand)]
+		state: State,
+	},
+
+	/// Replay the offchain workers of a block against a recording of their environment.
+	OffchainWorker(OffchainWorkerCmd),
+
+	/// Run the `on_runtime_upgrade` hooks, as `on-runtime-upgrade` does.
+	///
+	/// Keeps `try-runtime <state>` working, as it was before the other commands were added.
+	#[structopt(flatten)]
+	State(State),
 }
 
 /// The state to use for a migration dry-run.
@@ -117,6 +140,47 @@ fn parse_url(s: &str) -> Result<String, &'static str> {
 	}
 }
 
+/// Build the externalities of `state`, with the code of the local runtime.
+///
+/// `default_at` is the block used for a live state when none is given.
+async fn build_externalities<B: BlockT>(
+	state: &State,
+	code: StorageData,
+	default_at: Option<B::Hash>,
+) -> sc_cli::Result<TestExternalities>
+where
+	B::Hash: FromStr,
+	<B::Hash as FromStr>::Err: Debug,
+{
+	use remote_externalities::{Builder, Mode, SnapshotConfig, OfflineConfig, OnlineConfig};
+	let code_key = StorageKey(well_known_keys::CODE.to_vec());
+	let builder = match state {
+		State::Snap { snapshot_path } => {
+			Builder::<B>::new().mode(Mode::Offline(OfflineConfig {
+				state_snapshot: SnapshotConfig::new(snapshot_path),
+			}))
+		},
+		State::Live {
+			url,
+			snapshot_path,
+			block_at,
+			modules
+		} => Builder::<B>::new().mode(Mode::Online(OnlineConfig {
+			uri: url.into(),
+			state_snapshot: snapshot_path.as_ref().map(SnapshotConfig::new),
+			modules: modules.clone().unwrap_or_default(),
+			at: match block_at {
+				Some(b) => Some(b.parse().map_err(|e| format!("Could not parse hash: {:?}", e))?),
+				None => default_at,
+			},
+			..Default::default()
+		})),
+	};
+
+	// inject the code into this ext.
+	Ok(builder.inject(&[(code_key, code)]).build().await?)
+}
+
 impl TryRuntimeCmd {
 	pub async fn run<B, ExecDispatch>(&self, config: Configuration) -> sc_cli::Result<()>
 	where
@@ -137,7 +201,6 @@ impl TryRuntimeCmd {
 				.expect("code key must exist in genesis storage; qed")
 				.to_vec(),
 		);
-		let code_key = StorageKey(well_known_keys::CODE.to_vec());
 
 		let wasm_method = self.wasm_method;
 		let execution = self.execution;
@@ -152,58 +215,87 @@ impl TryRuntimeCmd {
 			max_runtime_instances,
 		);
 
-		let ext = {
-			use remote_externalities::{Builder, Mode, SnapshotConfig, OfflineConfig, OnlineConfig};
-			let builder = match &self.state {
-				State::Snap { snapshot_path } => {
-					Builder::<B>::new().mode(Mode::Offline(OfflineConfig {
-						state_snapshot: SnapshotConfig::new(snapshot_path),
-					}))
-				},
-				State::Live {
-					url,
-					snapshot_path,
-					block_at,
-					modules
-				} => Builder::<B>::new().mode(Mode::Online(OnlineConfig {
-					uri: url.into(),
-					state_snapshot: snapshot_path.as_ref().map(SnapshotConfig::new),
-					modules: modules.clone().unwrap_or_default(),
-					at: match block_at {
-						Some(b) => Some(b.parse().map_err(|e| format!("Could not parse hash: {:?}", e))?),
-						None => None,
-					},
-					..Default::default()
-				})),
-			};
-
-			// inject the code into this ext.
-			builder.inject(&[(code_key, code)]).build().await?
-		};
-
-		let encoded_result = StateMachine::<_, _, NumberFor<B>, _>::new(
-			&ext.backend,
-			None,
-			&mut changes,
-			&executor,
-			"TryRuntime_on_runtime_upgrade",
-			&[],
-			ext.extensions,
-			&sp_state_machine::backend::BackendRuntimeCode::new(&ext.backend)
-				.runtime_code()?,
-			sp_core::testing::TaskExecutor::new(),
-		)
-		.execute(execution.into())
-		.map_err(|e| format!("failed to execute 'TryRuntime_on_runtime_upgrade' due to {:?}", e))?;
-
-		let (weight, total_weight) = <(u64, u64) as Decode>::decode(&mut &*encoded_result)
-			.map_err(|e| format!("failed to decode output due to {:?}", e))?;
-		log::info!(
-			"try-runtime executed without errors. Consumed weight = {}, total weight = {} ({})",
-			weight,
-			total_weight,
-			weight as f64 / total_weight as f64
-		);
+		match &self.command {
+			Command::OnRuntimeUpgrade { state } | Command::State(state) => {
+				let ext = build_externalities::<B>(state, code, None).await?;
+
+				let encoded_result = StateMachine::<_, _, NumberFor<B>, _>::new(
+					&ext.backend,
+					None,
+					&mut changes,
+					&executor,
+					"TryRuntime_on_runtime_upgrade",
+					&[],
+					ext.extensions,
+					&sp_state_machine::backend::BackendRuntimeCode::new(&ext.backend)
+						.runtime_code()?,
+					sp_core::testing::TaskExecutor::new(),
+				)
+				.execute(execution.into())
+				.map_err(|e| format!(
+					"failed to execute 'TryRuntime_on_runtime_upgrade' due to {:?}",
+					e,
+				))?;
+
+				let (weight, total_weight) = <(u64, u64) as Decode>::decode(&mut &*encoded_result)
+					.map_err(|e| format!("failed to decode output due to {:?}", e))?;
+				log::info!(
+					"try-runtime executed without errors. \
+					Consumed weight = {}, total weight = {} ({})",
+					weight,
+					total_weight,
+					weight as f64 / total_weight as f64
+				);
+			},
+			Command::OffchainWorker(cmd) => {
+				let recording = Recording::<B::Header>::load(&cmd.recording)?;
+				let header = recording.header.clone();
+				let ext = build_externalities::<B>(&cmd.state, code, Some(header.hash())).await?;
+				let backend_code = sp_state_machine::backend::BackendRuntimeCode::new(&ext.backend);
+				let runtime_code = backend_code.runtime_code()?;
+				let (extensions, offchain_state, pool_state) = recording.extensions();
+
+				let result = StateMachine::<_, _, NumberFor<B>, _>::new(
+					&ext.backend,
+					None,
+					&mut changes,
+					&executor,
+					"OffchainWorkerApi_offchain_worker",
+					&header.encode(),
+					extensions,
+					&runtime_code,
+					sp_core::testing::TaskExecutor::new(),
+				)
+				.execute(execution.into());
+				if let Err(e) = result {
+					// `offchain_state` panics when dropped with expected requests left.
+					offchain_state.write().take_unfulfilled_requests();
+					return Err(format!(
+						"failed to execute 'OffchainWorkerApi_offchain_worker' due to {:?}",
+						e,
+					).into())
+				}
+
+				let transactions = std::mem::take(&mut pool_state.write().transactions);
+				log::info!(
+					"offchain workers of {:?} executed without errors, submitting {} transactions",
+					header.hash(),
+					transactions.len(),
+				);
+				for transaction in transactions {
+					log::info!("submitted transaction: 0x{}", HexDisplay::from(&transaction));
+				}
+				let unfulfilled = offchain_state.write().take_unfulfilled_requests();
+				if !unfulfilled.is_empty() {
+					let uris = unfulfilled.iter().map(|r| r.uri.as_str()).collect::<Vec<_>>();
+					return Err(format!(
+						"offchain workers did not send {} of the recorded requests: {:?}",
+						unfulfilled.len(),
+						uris,
+					).into())
+				}
+			},
+		}
 
 		Ok(())
 	}
@@ -0,0 +1,185 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replay of the offchain workers of a block.
+//!
+//! The environment the workers saw when they ran on a node (offchain storage, HTTP responses,
+//! time and randomness) is described by a [`Recording`], and is served back to them so that their
+//! execution can be reproduced deterministically.
+
+use std::{fs, path::{Path, PathBuf}, sync::Arc};
+use serde::{Serialize, Deserialize};
+use sp_core::{
+	H256,
+	offchain::{
+		OffchainDbExt, OffchainWorkerExt, OffchainStorage, Timestamp, TransactionPoolExt,
+		testing::{
+			OffchainState, PendingRequest, PoolState, TestOffchainExt, TestTransactionPoolExt,
+		},
+	},
+};
+use sp_externalities::Extensions;
+use sp_keystore::{KeystoreExt, testing::KeyStore};
+use parking_lot::RwLock;
+
+use crate::State;
+
+/// Replay the offchain workers of a block.
+#[derive(Debug, structopt::StructOpt)]
+pub struct OffchainWorkerCmd {
+	/// JSON file with the recording of the environment of the workers.
+	#[structopt(long, value_name = "PATH")]
+	pub recording: PathBuf,
+
+	/// The state of the block the workers ran on.
+	///
+	/// When replaying against a live chain without a block hash, the state is taken at the block
+	/// of the recorded header.
+	#[structopt(subcommand)]
+	pub state: State,
+}
+
+/// Environment seen by the offchain workers while they ran on a block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording<Header> {
+	/// Header of the block the workers ran on, as returned by `chain_getHeader`.
+	pub header: Header,
+	/// Time seen by the workers, in milliseconds since the UNIX epoch.
+	#[serde(default)]
+	pub timestamp: u64,
+	/// Random seed returned to the workers.
+	#[serde(default)]
+	pub seed: H256,
+	/// Contents of the persistent offchain storage.
+	#[serde(default)]
+	pub persistent_storage: Vec<(sp_core::Bytes, sp_core::Bytes)>,
+	/// Contents of the local offchain storage.
+	#[serde(default)]
+	pub local_storage: Vec<(sp_core::Bytes, sp_core::Bytes)>,
+	/// HTTP requests the workers are expected to send, in order, along with their responses.
+	#[serde(default)]
+	pub http: Vec<HttpExchange>,
+}
+
+/// An HTTP request sent by the workers and the response it got.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpExchange {
+	/// HTTP method of the request.
+	pub method: String,
+	/// URI of the request.
+	pub uri: String,
+	/// Headers of the request.
+	#[serde(default)]
+	pub headers: Vec<(String, String)>,
+	/// Body of the request.
+	#[serde(default, with = "sp_core::bytes")]
+	pub body: Vec<u8>,
+	/// Body of the response.
+	#[serde(with = "sp_core::bytes")]
+	pub response: Vec<u8>,
+	/// Headers of the response.
+	#[serde(default)]
+	pub response_headers: Vec<(String, String)>,
+}
+
+impl<Header: serde::de::DeserializeOwned> Recording<Header> {
+	/// Read a recording from a JSON file.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let file = fs::File::open(path)
+			.map_err(|e| format!("Could not open recording {:?}: {}", path, e))?;
+		serde_json::from_reader(file)
+			.map_err(|e| format!("Could not parse recording {:?}: {}", path, e))
+	}
+}
+
+impl<Header> Recording<Header> {
+	/// Extensions serving the recorded environment to the workers.
+	///
+	/// The returned handles give access to the offchain state and the transactions submitted by
+	/// the workers once they ran. Dropping the offchain state panics if some of the recorded
+	/// requests were never sent.
+	pub fn extensions(&self) -> (Extensions, Arc<RwLock<OffchainState>>, Arc<RwLock<PoolState>>) {
+		let (offchain, offchain_state) = TestOffchainExt::new();
+		{
+			let mut state = offchain_state.write();
+			state.timestamp = Timestamp::from_unix_millis(self.timestamp);
+			state.seed = self.seed.to_fixed_bytes();
+			for (key, value) in &self.persistent_storage {
+				state.persistent_storage.set(b"", &key.0, &value.0);
+			}
+			for (key, value) in &self.local_storage {
+				state.local_storage.set(b"", &key.0, &value.0);
+			}
+			for exchange in &self.http {
+				state.expect_request(PendingRequest {
+					method: exchange.method.clone(),
+					uri: exchange.uri.clone(),
+					headers: exchange.headers.clone(),
+					body: exchange.body.clone(),
+					response: Some(exchange.response.clone()),
+					response_headers: exchange.response_headers.clone(),
+					sent: true,
+					..Default::default()
+				});
+			}
+		}
+		let (pool, pool_state) = TestTransactionPoolExt::new();
+
+		let mut extensions = Extensions::default();
+		extensions.register(OffchainWorkerExt::new(offchain.clone()));
+		extensions.register(OffchainDbExt::new(offchain));
+		extensions.register(TransactionPoolExt::new(pool));
+		extensions.register(KeystoreExt(Arc::new(KeyStore::new())));
+
+		(extensions, offchain_state, pool_state)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::offchain::{DbExternalities, Externalities, StorageKind};
+
+	const RECORDING: &str = r#"{
+		"header": 42,
+		"timestamp": 1600000000000,
+		"persistentStorage": [["0x01", "0x0203"]],
+		"http": [{
+			"method": "GET",
+			"uri": "https://prices.example.com/dot",
+			"response": "0x7b7d"
+		}]
+	}"#;
+
+	#[test]
+	fn serves_the_recorded_environment() {
+		let recording: Recording<u32> = serde_json::from_str(RECORDING).unwrap();
+		assert_eq!(recording.header, 42);
+
+		let (_, state, _) = recording.extensions();
+		let mut offchain = TestOffchainExt(state.clone());
+
+		assert_eq!(offchain.timestamp().unix_millis(), 1_600_000_000_000);
+		assert_eq!(offchain.local_storage_get(StorageKind::PERSISTENT, &[1]), Some(vec![2, 3]));
+
+		let id = offchain.http_request_start("GET", "https://prices.example.com/dot", &[]).unwrap();
+		offchain.http_request_write_body(id, &[], None).unwrap();
+		assert_eq!(state.read().requests[&id].response, Some(b"{}".to_vec()));
+	}
+}
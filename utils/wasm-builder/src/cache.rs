@@ -0,0 +1,277 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of the built wasm binaries.
+//!
+//! Cargo reruns the `build.rs` of a runtime whenever any of the files it watches is touched, which
+//! includes changes that leave the wasm binary untouched. Every build is stored under a fingerprint
+//! of its inputs, so that a binary built before from the same inputs is reused instead of being
+//! compiled again.
+//!
+//! The environment variables a build depends on are only known once it is done: they are recorded
+//! with the build, which is only reused while they keep the same values.
+
+use std::{
+	collections::{hash_map::DefaultHasher, BTreeSet}, env, fs, hash::{Hash, Hasher},
+	path::{Path, PathBuf}, time::SystemTime,
+};
+use walkdir::WalkDir;
+
+/// Name of the folder of the wasm project containing the cached builds.
+const CACHE_FOLDER: &str = "cache";
+
+/// Name of the file touched whenever a cached build is used.
+const LAST_USED_FILE: &str = "last-used";
+
+/// Name of the file recording the environment variables a cached build depends on.
+const ENV_FILE: &str = "env";
+
+/// Number of builds kept in the cache of a project.
+const MAX_CACHED_BUILDS: usize = 4;
+
+/// Fingerprint of the inputs of a wasm build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Fingerprint(String);
+
+impl Fingerprint {
+	/// Compute the fingerprint of a build from its `settings` and the contents of the given
+	/// `files`.
+	///
+	/// Missing files are part of the fingerprint as well.
+	pub(crate) fn new<'a>(
+		settings: impl IntoIterator<Item = &'a str>,
+		files: impl IntoIterator<Item = PathBuf>,
+	) -> Self {
+		let mut hasher = DefaultHasher::new();
+		settings.into_iter().for_each(|setting| setting.hash(&mut hasher));
+		for file in files {
+			file.hash(&mut hasher);
+			fs::read(&file).ok().hash(&mut hasher);
+		}
+		Fingerprint(format!("{:016x}", hasher.finish()))
+	}
+}
+
+fn build_folder(project: &Path, fingerprint: &Fingerprint) -> PathBuf {
+	project.join(CACHE_FOLDER).join(&fingerprint.0)
+}
+
+fn file_name(file: &Path) -> &std::ffi::OsStr {
+	file.file_name().expect("Wasm binaries are files; qed")
+}
+
+fn env_var_hash(name: &str) -> String {
+	let mut hasher = DefaultHasher::new();
+	env::var_os(name).hash(&mut hasher);
+	format!("{:016x}", hasher.finish())
+}
+
+/// Returns the names of the environment variables the build in the `target` directory depends on.
+///
+/// They are read from the dependency files rustc writes for `env!` and `option_env!`, and from the
+/// `rerun-if-env-changed` instructions of the build scripts.
+pub(crate) fn env_dependencies(target: &Path) -> BTreeSet<String> {
+	let parent_name = |path: &Path, level: usize| path.ancestors()
+		.nth(level)
+		.and_then(|dir| dir.file_name())
+		.map(|name| name.to_os_string());
+
+	WalkDir::new(target)
+		.max_depth(5)
+		.into_iter()
+		.filter_map(|entry| entry.ok().map(|entry| entry.into_path()))
+		.filter(|path| {
+			(path.extension() == Some("d".as_ref()) && parent_name(path, 1) == Some("deps".into()))
+				|| (path.file_name() == Some("output".as_ref())
+					&& parent_name(path, 2) == Some("build".into()))
+		})
+		.filter_map(|path| fs::read_to_string(path).ok())
+		.flat_map(|contents| contents.lines()
+			.filter_map(|line| line.strip_prefix("# env-dep:")
+				.map(|dep| dep.split('=').next().unwrap_or_default())
+				.or_else(|| line.strip_prefix("cargo:rerun-if-env-changed="))
+				.map(ToOwned::to_owned))
+			.collect::<Vec<_>>())
+		.filter(|name| !name.is_empty())
+		.collect()
+}
+
+/// Restore the `files` of the build with the given fingerprint.
+///
+/// Returns `false` if no such build is cached, or if one of the environment variables it depends
+/// on changed since.
+pub(crate) fn restore(project: &Path, fingerprint: &Fingerprint, files: &[&Path]) -> bool {
+	let folder = build_folder(project, fingerprint);
+	if !files.iter().all(|file| folder.join(file_name(file)).is_file()) {
+		return false
+	}
+
+	let env_unchanged = fs::read_to_string(folder.join(ENV_FILE))
+		.map(|env| env.lines().all(|line| match line.rfind('=') {
+			Some(index) => env_var_hash(&line[..index]) == line[index + 1..],
+			None => false,
+		}))
+		.unwrap_or(false);
+	if !env_unchanged {
+		return false
+	}
+
+	let restored = files.iter().all(|file| fs::copy(folder.join(file_name(file)), file).is_ok());
+	if restored {
+		let _ = fs::write(folder.join(LAST_USED_FILE), "");
+	}
+	restored
+}
+
+/// Store the `files` of the build with the given fingerprint, along with the current values of
+/// the environment variables it depends on, evicting the least recently used builds.
+///
+/// Failing to store a build is not an error, it will only have to be rebuilt the next time.
+pub(crate) fn store(
+	project: &Path,
+	fingerprint: &Fingerprint,
+	files: &[&Path],
+	env_dependencies: &BTreeSet<String>,
+) {
+	let env = env_dependencies
+		.iter()
+		.map(|name| format!("{}={}\n", name, env_var_hash(name)))
+		.collect::<String>();
+
+	let folder = build_folder(project, fingerprint);
+	let stored = fs::create_dir_all(&folder).is_ok()
+		&& files.iter().all(|file| fs::copy(file, folder.join(file_name(file))).is_ok())
+		&& fs::write(folder.join(ENV_FILE), env).is_ok()
+		&& fs::write(folder.join(LAST_USED_FILE), "").is_ok();
+
+	if !stored {
+		let _ = fs::remove_dir_all(&folder);
+		return
+	}
+
+	evict(&project.join(CACHE_FOLDER), MAX_CACHED_BUILDS);
+}
+
+/// Remove all the builds in `cache` but the `keep` most recently used ones.
+fn evict(cache: &Path, keep: usize) {
+	let last_used = |folder: &Path| fs::metadata(folder.join(LAST_USED_FILE))
+		.and_then(|m| m.modified())
+		.unwrap_or(SystemTime::UNIX_EPOCH);
+
+	let mut builds = match fs::read_dir(cache) {
+		Ok(entries) => entries
+			.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+			.filter(|path| path.is_dir())
+			.map(|path| (last_used(&path), path))
+			.collect::<Vec<_>>(),
+		Err(_) => return,
+	};
+
+	builds.sort_by(|a, b| b.0.cmp(&a.0));
+	builds.into_iter().skip(keep).for_each(|(_, path)| {
+		let _ = fs::remove_dir_all(path);
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fingerprint_depends_on_settings_and_file_contents() {
+		let dir = tempfile::tempdir().unwrap();
+		let file = dir.path().join("lib.rs");
+		fs::write(&file, "fn main() {}").unwrap();
+
+		let fingerprint = || Fingerprint::new(vec!["release"], vec![file.clone()]);
+		let first = fingerprint();
+		assert_eq!(first, fingerprint());
+		assert_ne!(first, Fingerprint::new(vec!["debug"], vec![file.clone()]));
+
+		fs::write(&file, "fn main() { loop {} }").unwrap();
+		assert_ne!(first, fingerprint());
+	}
+
+	#[test]
+	fn restores_stored_builds() {
+		let project = tempfile::tempdir().unwrap();
+		let wasm = project.path().join("runtime.wasm");
+		let fingerprint = Fingerprint::new(vec!["release"], vec![]);
+
+		assert!(!restore(project.path(), &fingerprint, &[&wasm]));
+
+		fs::write(&wasm, b"\0asm").unwrap();
+		store(project.path(), &fingerprint, &[&wasm], &Default::default());
+		fs::write(&wasm, b"garbage").unwrap();
+
+		assert!(restore(project.path(), &fingerprint, &[&wasm]));
+		assert_eq!(fs::read(&wasm).unwrap(), b"\0asm");
+	}
+
+	#[test]
+	fn builds_are_not_restored_once_their_environment_changed() {
+		let project = tempfile::tempdir().unwrap();
+		let wasm = project.path().join("runtime.wasm");
+		fs::write(&wasm, b"\0asm").unwrap();
+		let fingerprint = Fingerprint::new(vec!["release"], vec![]);
+
+		let target = project.path().join("target/wasm32-unknown-unknown/release");
+		fs::create_dir_all(target.join("deps")).unwrap();
+		fs::create_dir_all(target.join("build/runtime-0123/out")).unwrap();
+		fs::write(
+			target.join("deps/runtime-0123.d"),
+			"runtime.wasm: src/lib.rs\n\n# env-dep:WASM_CACHE_TEST_ENV_DEP=1\n",
+		).unwrap();
+		fs::write(
+			target.join("build/runtime-0123/output"),
+			"cargo:rerun-if-env-changed=WASM_CACHE_TEST_BUILD_DEP\n",
+		).unwrap();
+
+		let dependencies = env_dependencies(&project.path().join("target"));
+		assert_eq!(
+			dependencies.iter().map(String::as_str).collect::<Vec<_>>(),
+			vec!["WASM_CACHE_TEST_BUILD_DEP", "WASM_CACHE_TEST_ENV_DEP"],
+		);
+
+		env::set_var("WASM_CACHE_TEST_BUILD_DEP", "1");
+		store(project.path(), &fingerprint, &[&wasm], &dependencies);
+		assert!(restore(project.path(), &fingerprint, &[&wasm]));
+
+		env::set_var("WASM_CACHE_TEST_BUILD_DEP", "2");
+		assert!(!restore(project.path(), &fingerprint, &[&wasm]));
+		env::remove_var("WASM_CACHE_TEST_BUILD_DEP");
+	}
+
+	#[test]
+	fn evicts_least_recently_used_builds() {
+		let project = tempfile::tempdir().unwrap();
+		let wasm = project.path().join("runtime.wasm");
+		fs::write(&wasm, b"\0asm").unwrap();
+
+		let fingerprints = (0..MAX_CACHED_BUILDS + 1)
+			.map(|i| Fingerprint::new(vec![i.to_string().as_str()], vec![]))
+			.collect::<Vec<_>>();
+		for fingerprint in &fingerprints {
+			store(project.path(), fingerprint, &[&wasm], &Default::default());
+			// Make sure the builds have distinct last use times.
+			std::thread::sleep(std::time::Duration::from_millis(10));
+		}
+
+		assert!(!restore(project.path(), &fingerprints[0], &[&wasm]));
+		assert!(restore(project.path(), &fingerprints[MAX_CACHED_BUILDS], &[&wasm]));
+	}
+}
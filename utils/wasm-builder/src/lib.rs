@@ -75,6 +75,10 @@
 //! - `FORCE_WASM_BUILD` - Can be set to force a Wasm build. On subsequent calls the value of the variable
 //!                        needs to change. As wasm-builder instructs `cargo` to watch for file changes
 //!                        this environment variable should only be required in certain circumstances.
+//!                        A forced build never reuses a cached Wasm binary.
+//! - `WASM_BUILD_NO_CACHE` - Disables the cache of built Wasm binaries. By default a Wasm binary is
+//!                           only rebuilt if its sources, features, `RUSTFLAGS` or toolchain changed
+//!                           since it was built last, otherwise the cached binary is reused.
 //! - `WASM_BUILD_RUSTFLAGS` - Extend `RUSTFLAGS` given to `cargo build` while building the wasm binary.
 //! - `WASM_BUILD_NO_COLOR` - Disable color output of the wasm build.
 //! - `WASM_TARGET_DIRECTORY` - Will copy any build Wasm binary to the given directory. The path needs
@@ -99,6 +103,7 @@
 use std::{env, fs, path::{PathBuf, Path}, process::Command, io::BufRead};
 
 mod builder;
+mod cache;
 mod prerequisites;
 mod wasm_project;
 
@@ -130,6 +135,9 @@ const WASM_BUILD_TOOLCHAIN: &str = "WASM_BUILD_TOOLCHAIN";
 /// Environment variable that makes sure the WASM build is triggered.
 const FORCE_WASM_BUILD_ENV: &str = "FORCE_WASM_BUILD";
 
+/// Environment variable to disable the cache of built wasm binaries.
+const WASM_BUILD_NO_CACHE_ENV: &str = "WASM_BUILD_NO_CACHE";
+
 /// Write to the given `file` if the `content` is different.
 fn write_file_if_changed(file: impl AsRef<Path>, content: impl AsRef<str>) {
 	if fs::read_to_string(file.as_ref()).ok().as_deref() != Some(content.as_ref()) {
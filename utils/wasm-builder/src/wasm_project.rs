@@ -15,10 +15,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{write_file_if_changed, CargoCommandVersioned};
+use crate::{write_file_if_changed, CargoCommandVersioned, cache};
 
 use std::{
-	fs, path::{Path, PathBuf}, borrow::ToOwned, process, env, collections::HashSet,
+	fs, path::{Path, PathBuf}, borrow::ToOwned, process, env, collections::{BTreeSet, HashSet},
 	hash::{Hash, Hasher}, ops::Deref,
 };
 
@@ -113,20 +113,53 @@ pub(crate) fn create_and_compile(
 		features_to_enable,
 	);
 
-	build_project(&project, default_rustflags, cargo_cmd);
-	let (wasm_binary, bloaty) = compact_wasm_file(
+	let project_metadata = MetadataCommand::new()
+		.manifest_path(project.join("Cargo.toml"))
+		.exec()
+		.expect("`cargo metadata` can not fail!");
+	let packages = runtime_packages(project_cargo_toml, &project_metadata, &wasm_workspace);
+
+	let rustflags = wasm_rustflags(default_rustflags);
+	let (wasm_compact_file, bloaty_file) =
+		wasm_binary_paths(&project, project_cargo_toml, wasm_binary_name.as_deref());
+	let build_files = wasm_compact_file.iter().chain(Some(&bloaty_file))
+		.map(|file| file.as_path())
+		.collect::<Vec<_>>();
+
+	let fingerprint = build_fingerprint(
 		&project,
-		project_cargo_toml,
-		wasm_binary_name,
+		&packages,
+		&rustflags,
+		&cargo_cmd,
+		wasm_binary_name.as_deref(),
 	);
 
+	if !is_cache_enabled() || env::var(crate::FORCE_WASM_BUILD_ENV).is_ok()
+		|| !cache::restore(&project, &fingerprint, &build_files)
+	{
+		build_project(&project, &rustflags, cargo_cmd);
+		compact_wasm_file(&project, project_cargo_toml, wasm_compact_file.as_deref(), &bloaty_file);
+
+		if is_cache_enabled() {
+			let env_dependencies = cache::env_dependencies(&project.join("target"));
+			cache::store(&project, &fingerprint, &build_files, &env_dependencies);
+		}
+	} else {
+		println!(
+			"{} {}",
+			colorize_info_message("Using the cached wasm binary built from the same sources:"),
+			bloaty_file.display(),
+		);
+	}
+
+	let wasm_binary = wasm_compact_file.map(WasmBinary);
 	wasm_binary.as_ref().map(|wasm_binary|
 		copy_wasm_to_target_directory(project_cargo_toml, wasm_binary)
 	);
 
-	generate_rerun_if_changed_instructions(project_cargo_toml, &project, &wasm_workspace);
+	generate_rerun_if_changed_instructions(project_cargo_toml, &packages);
 
-	(wasm_binary, bloaty)
+	(wasm_binary, WasmBinaryBloaty(bloaty_file))
 }
 
 /// Find the `Cargo.lock` relative to the `OUT_DIR` environment variable.
@@ -401,16 +434,24 @@ fn is_release_build() -> bool {
 	}
 }
 
-/// Build the project to create the WASM binary.
-fn build_project(project: &Path, default_rustflags: &str, cargo_cmd: CargoCommandVersioned) {
-	let manifest_path = project.join("Cargo.toml");
-	let mut build_cmd = cargo_cmd.command();
+/// Returns if built wasm binaries should be cached and reused.
+fn is_cache_enabled() -> bool {
+	env::var(crate::WASM_BUILD_NO_CACHE_ENV).is_err()
+}
 
-	let rustflags = format!(
+/// Returns the `RUSTFLAGS` used to build the WASM binary.
+fn wasm_rustflags(default_rustflags: &str) -> String {
+	format!(
 		"-C link-arg=--export-table {} {}",
 		default_rustflags,
 		env::var(crate::WASM_BUILD_RUSTFLAGS_ENV).unwrap_or_default(),
-	);
+	)
+}
+
+/// Build the project to create the WASM binary.
+fn build_project(project: &Path, rustflags: &str, cargo_cmd: CargoCommandVersioned) {
+	let manifest_path = project.join("Cargo.toml");
+	let mut build_cmd = cargo_cmd.command();
 
 	build_cmd.args(&["-Zfeatures=build_dep", "rustc", "--target=wasm32-unknown-unknown"])
 		.arg(format!("--manifest-path={}", manifest_path.display()))
@@ -441,43 +482,85 @@ fn build_project(project: &Path, default_rustflags: &str, cargo_cmd: CargoComman
 	}
 }
 
-/// Compact the WASM binary using `wasm-gc`. Returns the path to the bloaty WASM binary.
+/// Returns the paths of the compact WASM binary, only built for release builds, and of the bloaty
+/// WASM binary in the project dir.
+fn wasm_binary_paths(
+	project: &Path,
+	cargo_manifest: &Path,
+	wasm_binary_name: Option<&str>,
+) -> (Option<PathBuf>, PathBuf) {
+	let wasm_binary_name = wasm_binary_name
+		.map(ToOwned::to_owned)
+		.unwrap_or_else(|| get_wasm_binary_name(cargo_manifest));
+
+	let wasm_compact_file = if is_release_build() {
+		Some(project.join(format!("{}.compact.wasm", wasm_binary_name)))
+	} else {
+		None
+	};
+
+	(wasm_compact_file, project.join(format!("{}.wasm", wasm_binary_name)))
+}
+
+/// Compact the WASM binary using `wasm-gc` and copy the bloaty WASM binary to the project dir.
 fn compact_wasm_file(
 	project: &Path,
 	cargo_manifest: &Path,
-	wasm_binary_name: Option<String>,
-) -> (Option<WasmBinary>, WasmBinaryBloaty) {
-	let is_release_build = is_release_build();
-	let target = if is_release_build { "release" } else { "debug" };
-	let default_wasm_binary_name = get_wasm_binary_name(cargo_manifest);
+	wasm_compact_file: Option<&Path>,
+	bloaty_file: &Path,
+) {
+	let target = if is_release_build() { "release" } else { "debug" };
 	let wasm_file = project.join("target/wasm32-unknown-unknown")
 		.join(target)
-		.join(format!("{}.wasm", default_wasm_binary_name));
+		.join(format!("{}.wasm", get_wasm_binary_name(cargo_manifest)));
 
-	let wasm_compact_file = if is_release_build {
-		let wasm_compact_file = project.join(
-			format!(
-				"{}.compact.wasm",
-				wasm_binary_name.clone().unwrap_or_else(|| default_wasm_binary_name.clone()),
-			)
-		);
-		wasm_gc::garbage_collect_file(&wasm_file, &wasm_compact_file)
+	if let Some(wasm_compact_file) = wasm_compact_file {
+		wasm_gc::garbage_collect_file(&wasm_file, wasm_compact_file)
 			.expect("Failed to compact generated WASM binary.");
-		Some(WasmBinary(wasm_compact_file))
-	} else {
-		None
-	};
+	}
 
-	let bloaty_file_name = if let Some(name) = wasm_binary_name {
-		format!("{}.wasm", name)
-	} else {
-		format!("{}.wasm", default_wasm_binary_name)
-	};
+	fs::copy(wasm_file, bloaty_file).expect("Copying the bloaty file to the project dir.");
+}
+
+/// Compute the fingerprint of the inputs of the WASM build.
+///
+/// Packages from a registry or a git repository are identified by their version and source, the
+/// contents of all the files of the other packages are taken into account, which includes the
+/// assets they include with `include_bytes!` and friends.
+fn build_fingerprint(
+	project: &Path,
+	packages: &HashSet<DeduplicatePackage>,
+	rustflags: &str,
+	cargo_cmd: &CargoCommandVersioned,
+	wasm_binary_name: Option<&str>,
+) -> cache::Fingerprint {
+	let (path_packages, other_packages): (Vec<_>, Vec<_>) = packages
+		.iter()
+		.partition(|package| package.source.is_none());
 
-	let bloaty_file = project.join(bloaty_file_name);
-	fs::copy(wasm_file, &bloaty_file).expect("Copying the bloaty file to the project dir.");
+	let identifiers = other_packages
+		.iter()
+		.map(|package| package.identifier.as_str())
+		.collect::<BTreeSet<_>>();
 
-	(wasm_compact_file, WasmBinaryBloaty(bloaty_file))
+	let files = path_packages
+		.iter()
+		.flat_map(|package| package_files(package))
+		.filter(|path| !path.is_dir())
+		.collect::<BTreeSet<_>>();
+
+	let build_type = if is_release_build() { "release" } else { "debug" };
+	let settings = vec![
+		cargo_cmd.rustc_version(),
+		rustflags,
+		build_type,
+		wasm_binary_name.unwrap_or_default(),
+	];
+
+	cache::Fingerprint::new(
+		settings.into_iter().chain(identifiers),
+		vec![project.join("Cargo.toml"), project.join("Cargo.lock")].into_iter().chain(files),
+	)
 }
 
 /// Custom wrapper for a [`cargo_metadata::Package`] to store it in
@@ -519,23 +602,13 @@ impl<'a> Deref for DeduplicatePackage<'a> {
 	}
 }
 
-/// Generate the `rerun-if-changed` instructions for cargo to make sure that the WASM binary is
-/// rebuilt when needed.
-fn generate_rerun_if_changed_instructions(
+/// Collect the crate we want to compile for wasm and all the packages it depends on, using the
+/// `metadata` of the wasm project.
+fn runtime_packages<'a>(
 	cargo_manifest: &Path,
-	project_folder: &Path,
+	metadata: &'a Metadata,
 	wasm_workspace: &Path,
-) {
-	// Rerun `build.rs` if the `Cargo.lock` changes
-	if let Some(cargo_lock) = find_cargo_lock(cargo_manifest) {
-		rerun_if_changed(cargo_lock);
-	}
-
-	let metadata = MetadataCommand::new()
-		.manifest_path(project_folder.join("Cargo.toml"))
-		.exec()
-		.expect("`cargo metadata` can not fail!");
-
+) -> HashSet<DeduplicatePackage<'a>> {
 	let package = metadata.packages
 		.iter()
 		.find(|p| p.manifest_path == cargo_manifest)
@@ -571,8 +644,22 @@ fn generate_rerun_if_changed_instructions(
 		}
 	}
 
+	packages
+}
+
+/// Generate the `rerun-if-changed` instructions for cargo to make sure that the WASM binary is
+/// rebuilt when needed.
+fn generate_rerun_if_changed_instructions(
+	cargo_manifest: &Path,
+	packages: &HashSet<DeduplicatePackage>,
+) {
+	// Rerun `build.rs` if the `Cargo.lock` changes
+	if let Some(cargo_lock) = find_cargo_lock(cargo_manifest) {
+		rerun_if_changed(cargo_lock);
+	}
+
 	// Make sure that if any file/folder of a dependency change, we need to rerun the `build.rs`
-	packages.iter().for_each(package_rerun_if_changed);
+	packages.iter().flat_map(|package| package_files(package)).for_each(rerun_if_changed);
 
 	// Register our env variables
 	println!("cargo:rerun-if-env-changed={}", crate::SKIP_BUILD_ENV);
@@ -580,30 +667,34 @@ fn generate_rerun_if_changed_instructions(
 	println!("cargo:rerun-if-env-changed={}", crate::WASM_BUILD_RUSTFLAGS_ENV);
 	println!("cargo:rerun-if-env-changed={}", crate::WASM_TARGET_DIRECTORY);
 	println!("cargo:rerun-if-env-changed={}", crate::WASM_BUILD_TOOLCHAIN);
+	println!("cargo:rerun-if-env-changed={}", crate::WASM_BUILD_NO_CACHE_ENV);
 }
 
-/// Track files and paths related to the given package to rerun `build.rs` on any relevant change.
-fn package_rerun_if_changed(package: &DeduplicatePackage) {
+/// Returns the files and paths related to the given package, which may influence the WASM binary.
+///
+/// Hidden files and `target` directories are skipped.
+fn package_files(package: &DeduplicatePackage) -> impl Iterator<Item = PathBuf> {
 	let mut manifest_path = package.manifest_path.clone();
 	if manifest_path.ends_with("Cargo.toml") {
 		manifest_path.pop();
 	}
 
-	WalkDir::new(&manifest_path)
+	WalkDir::new(manifest_path.clone())
 		.into_iter()
-		.filter_entry(|p| {
+		.filter_entry(move |p| {
 			// Ignore this entry if it is a directory that contains a `Cargo.toml` that is not the
 			// `Cargo.toml` related to the current package. This is done to ignore sub-crates of a crate.
 			// If such a sub-crate is a dependency, it will be processed independently anyway.
-			p.path() == manifest_path
-				|| !p.path().is_dir()
-				|| !p.path().join("Cargo.toml").exists()
+			if p.path() == manifest_path {
+				return true
+			}
+			let name = p.file_name().to_string_lossy();
+			let is_dir = p.path().is_dir();
+			!name.starts_with('.')
+				&& !(is_dir && name == "target")
+				&& !(is_dir && p.path().join("Cargo.toml").exists())
 		})
 		.filter_map(|p| p.ok().map(|p| p.into_path()))
-		.filter(|p| {
-			p.is_dir() || p.extension().map(|e| e == "rs" || e == "toml").unwrap_or_default()
-		})
-		.for_each(|p| rerun_if_changed(p));
 }
 
 /// Copy the WASM binary to the target directory set in `WASM_TARGET_DIRECTORY` environment